@@ -17,16 +17,34 @@ pub(crate) fn default() -> EntryPoints {
     // view functions
     entry_points.add_entry_point(EntryPoint::new(
         String::from("get_block_hash"),
-        vec![Parameter::new("height", CLType::U64)],
+        vec![Parameter::new("timestamp", CLType::U64)],
         CLType::String,
         EntryPointAccess::Public,
         EntryPointType::Contract,
     ));
 
+    entry_points.add_entry_point(EntryPoint::new(
+        String::from("get_block_ref"),
+        vec![Parameter::new("timestamp", CLType::U64)],
+        CLType::Any,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        String::from("get_latest_block_ref"),
+        vec![],
+        CLType::Any,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
     entry_points.add_entry_point(EntryPoint::new(
         String::from("set_block_hash"),
         vec![
+            Parameter::new("era_id", CLType::U64),
             Parameter::new("height", CLType::U64),
+            Parameter::new("timestamp", CLType::U64),
             Parameter::new("hash", CLType::String),
         ],
         CLType::Unit,
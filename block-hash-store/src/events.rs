@@ -8,18 +8,23 @@ extern crate alloc;
 use alloc::string::String;
 use casper_event_standard::Event;
 use casper_event_standard::Schemas;
+use common::data_types::BlockRef;
 use common::helpers::current_block_timestamp;
 
 #[derive(Event, Debug, PartialEq, Eq)]
 pub struct SetHash {
+    era_id: u64,
     height: u64,
+    block_timestamp: u64,
     hash: String,
     timestamp: u64,
 }
 impl SetHash {
-    pub fn new(height: u64, hash: String) -> Self {
+    pub fn new(block: BlockRef, hash: String) -> Self {
         Self {
-            height,
+            era_id: block.era_id,
+            height: block.height,
+            block_timestamp: block.timestamp,
             hash,
             timestamp: current_block_timestamp(),
         }
@@ -2,6 +2,7 @@ use alloc::string::{String, ToString};
 use casper_contract::contract_api::{runtime, storage};
 use casper_contract::unwrap_or_revert::UnwrapOrRevert;
 use casper_types::CLValue;
+use common::data_types::BlockRef;
 use common::error::Error;
 use common::helpers;
 
@@ -9,28 +10,67 @@ use crate::events::SetHash;
 
 pub fn initialize() {
     storage::new_dictionary("block_hashes").unwrap_or_revert_with(Error::FailedToCreateDictionary);
+    storage::new_dictionary("block_refs").unwrap_or_revert_with(Error::FailedToCreateDictionary);
 }
 
 // SETTER & GETTER
 #[no_mangle]
 pub extern "C" fn get_block_hash() {
-    let height: u64 = runtime::get_named_arg("height");
-    runtime::ret(CLValue::from_t(read_block_hash(height)).unwrap_or_revert())
+    let timestamp: u64 = runtime::get_named_arg("timestamp");
+    runtime::ret(CLValue::from_t(read_block_hash(timestamp)).unwrap_or_revert())
 }
 
+#[no_mangle]
+pub extern "C" fn get_block_ref() {
+    let timestamp: u64 = runtime::get_named_arg("timestamp");
+    runtime::ret(CLValue::from_t(read_block_ref(timestamp)).unwrap_or_revert())
+}
+
+#[no_mangle]
+pub extern "C" fn get_latest_block_ref() {
+    runtime::ret(CLValue::from_t(read_latest_block_ref()).unwrap_or_revert())
+}
+
+/// Stores the hash of a block under its timestamp, the reference requests commit to, along
+/// with its era and height.
 #[no_mangle]
 pub extern "C" fn set_block_hash() {
     common::owner::only_owner();
-    let height: u64 = runtime::get_named_arg("height");
-    let hash: String = runtime::get_named_arg("hash");
-    save_block_hash(height, hash.clone());
-    casper_event_standard::emit(SetHash::new(height, hash));
+    let (era_id, height, timestamp, hash): (u64, u64, u64, String) =
+        helpers::get_named_args_4(helpers::to_vec_string(&[
+            "era_id",
+            "height",
+            "timestamp",
+            "hash",
+        ]));
+    let block = BlockRef {
+        era_id,
+        height,
+        timestamp,
+    };
+    save_block_hash(block, hash.clone());
+    casper_event_standard::emit(SetHash::new(block, hash));
+}
+
+pub fn read_block_hash(timestamp: u64) -> String {
+    helpers::get_dictionary_value_from_key("block_hashes", &timestamp.to_string())
+        .unwrap_or_default()
+}
+
+pub fn read_block_ref(timestamp: u64) -> BlockRef {
+    helpers::get_dictionary_value_from_key("block_refs", &timestamp.to_string()).unwrap_or_default()
 }
 
-pub fn read_block_hash(height: u64) -> String {
-    helpers::get_dictionary_value_from_key("block_hashes", &height.to_string()).unwrap_or_default()
+/// Highest block stored so far, the tip confirmations of requests are counted to.
+pub fn read_latest_block_ref() -> BlockRef {
+    helpers::get_key("latest_block_ref").unwrap_or_default()
 }
 
-pub fn save_block_hash(height: u64, hash: String) {
-    helpers::write_dictionary_value_from_key("block_hashes", &height.to_string(), hash);
+pub fn save_block_hash(block: BlockRef, hash: String) {
+    let key = block.timestamp.to_string();
+    helpers::write_dictionary_value_from_key("block_hashes", &key, hash);
+    helpers::write_dictionary_value_from_key("block_refs", &key, block);
+    if block.height > read_latest_block_ref().height {
+        helpers::set_key("latest_block_ref", block);
+    }
 }
@@ -12,3 +12,4 @@ pub const MAX_CONSUMERS: u64 = 100;
 pub const MAX_REQUEST_CONFIRMATIONS: u64 = 200;
 pub const MAX_NUM_WORDS: u64 = 500;
 pub const GAS_FOR_CALL_EXACT_CHECK: u128 = 5000;
+pub const MAX_FEE_TIERS: usize = 50;
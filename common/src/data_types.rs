@@ -1,21 +1,55 @@
 use alloc::vec::Vec;
-use casper_types::{bytesrepr::Bytes, Key, U128, U256};
+use casper_types::{
+    bytesrepr::{self, Bytes, FromBytes, ToBytes},
+    CLType, CLTyped, HashAddr, Key, U128, U256, U512,
+};
 use casper_types_derive::{CLTyped, FromBytes, ToBytes};
 use serde::{Deserialize, Serialize};
 
-use crate::helpers;
+use crate::{constants::MAX_FEE_TIERS, helpers};
 
 #[derive(Serialize, Deserialize, Clone, CLTyped, ToBytes, FromBytes)]
 pub struct SubscriptionView {
     pub balance: U128,
+    /// Motes deposited with `deposit_cspr`, paying the fulfillments `balance` cannot cover.
+    pub native_balance: U512,
     pub req_count: u64,
     pub owner: Key,
     pub consumers: Vec<Key>,
+    pub limits: SubscriptionLimits,
+    /// Token `balance` is held in.
+    pub payment_token: Key,
+}
+
+/// Caps the owner of a subscription puts on its use, a zero leaves the cap unset.
+#[derive(
+    Serialize, Deserialize, Clone, Copy, CLTyped, ToBytes, FromBytes, Default, Debug, PartialEq, Eq,
+)]
+pub struct SubscriptionLimits {
+    /// Length of the windows spend and requests are counted in.
+    pub window_seconds: u64,
+    /// Payment fulfillments may charge in one window, new requests are refused once it is reached.
+    pub max_spend_per_window: U128,
+    /// Requests each consumer may make in one window.
+    pub max_requests_per_consumer: u64,
+    pub max_num_words: u64,
+}
+
+/// Use of a subscription, or of one of its consumers, in a `SubscriptionLimits` window.
+#[derive(
+    Serialize, Deserialize, Clone, Copy, CLTyped, ToBytes, FromBytes, Default, Debug, PartialEq, Eq,
+)]
+pub struct WindowUsage {
+    /// Block timestamp divided by the window length.
+    pub window: u64,
+    pub spent: U128,
+    pub requests: u64,
 }
 
 #[derive(Serialize, Deserialize, Clone, CLTyped, ToBytes, FromBytes, Default)]
 pub struct Subscription {
     pub balance: U128,
+    pub native_balance: U512,
     pub req_count: u64,
 }
 
@@ -24,6 +58,8 @@ pub struct SubscriptionConfig {
     pub owner: Key,
     pub requested_owner: Key,
     pub consumers: Vec<Key>,
+    /// Token the subscription pays in, fixed at creation.
+    pub payment_token: Key,
 }
 
 impl Default for SubscriptionConfig {
@@ -32,13 +68,34 @@ impl Default for SubscriptionConfig {
             owner: helpers::null_key(),
             requested_owner: helpers::null_key(),
             consumers: Default::default(),
+            payment_token: helpers::null_key(),
         }
     }
 }
 
+/// Block a request is anchored to. Contracts only see the block time, so requests commit to
+/// `timestamp` and the block-hash-store keeps the era and height of the block under it.
+#[derive(Serialize, Deserialize, Clone, Copy, ToBytes, FromBytes, Default, Debug, PartialEq, Eq)]
+pub struct BlockRef {
+    pub era_id: u64,
+    pub height: u64,
+    pub timestamp: u64,
+}
+
+/// Typed as the tuple of its fields, which it serializes as, since events cannot carry `Any`.
+impl CLTyped for BlockRef {
+    fn cl_type() -> CLType {
+        <(u64, u64, u64)>::cl_type()
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, CLTyped, ToBytes, FromBytes)]
 pub struct RequestCommitment {
-    pub block_num: u64,
+    /// Block the request was made in. The request only commits to its timestamp, the oracle
+    /// fulfills with the era and height the block-hash-store holds under that timestamp.
+    pub block: BlockRef,
+    /// Blocks the block-hash-store must hold past the request block before fulfilling.
+    pub request_confirmations: u64,
     pub sub_id: u64,
     pub callback_gas_limit: U128,
     pub num_words: u32,
@@ -49,7 +106,8 @@ impl Default for RequestCommitment {
     fn default() -> Self {
         Self {
             sender: helpers::null_key(),
-            block_num: 0,
+            block: BlockRef::default(),
+            request_confirmations: 0,
             sub_id: 0,
             callback_gas_limit: 0.into(),
             num_words: 0,
@@ -63,19 +121,214 @@ pub struct Config {
     pub max_gas_limit: U128,
     pub staleness_seconds: u64,
     pub gas_after_payment_calculation: U128,
+    pub fallback_motes_per_unit_link: U256,
+    /// Seconds after which an unfulfilled request can be cancelled, zero disables cancelling.
+    pub request_timeout_seconds: u64,
 }
 
+/// Flat fee of the fulfillments of subscriptions that made at least `reqs_threshold` requests.
+#[derive(
+    Serialize, Deserialize, Clone, Copy, CLTyped, ToBytes, FromBytes, Default, Debug, PartialEq, Eq,
+)]
+pub struct FeeTier {
+    pub reqs_threshold: u64,
+    pub fulfillment_flat_fee_link_ppm: u64,
+}
+
+/// Volume discount steps, ordered by strictly increasing threshold from a first tier at zero
+/// requests. Without tiers no flat fee is charged.
 #[derive(Serialize, Deserialize, Clone, CLTyped, ToBytes, FromBytes, Default)]
 pub struct FeeConfig {
-    pub fulfillment_flat_fee_link_ppm_tier1: u64,
-    pub fulfillment_flat_fee_link_ppm_tier2: u64,
-    pub fulfillment_flat_fee_link_ppm_tier3: u64,
-    pub fulfillment_flat_fee_link_ppm_tier4: u64,
-    pub fulfillment_flat_fee_link_ppm_tier5: u64,
-    pub reqs_for_tier2: u32,
-    pub reqs_for_tier3: u32,
-    pub reqs_for_tier4: u32,
-    pub reqs_for_tier5: u32,
+    pub tiers: Vec<FeeTier>,
+}
+
+impl FeeConfig {
+    /// Whether the tiers are ordered as described above, with at most `MAX_FEE_TIERS` of them.
+    pub fn is_valid(&self) -> bool {
+        self.tiers.len() <= MAX_FEE_TIERS
+            && self
+                .tiers
+                .first()
+                .map_or(true, |tier| tier.reqs_threshold == 0)
+            && self
+                .tiers
+                .windows(2)
+                .all(|tiers| tiers[0].reqs_threshold < tiers[1].reqs_threshold)
+    }
+
+    /// Index and flat fee of the tier of a subscription that made `req_count` requests, the
+    /// last tier whose threshold it reached. `(0, 0)` without tiers.
+    pub fn fee_tier(&self, req_count: u64) -> (u32, u64) {
+        let index = match self
+            .tiers
+            .binary_search_by_key(&req_count, |tier| tier.reqs_threshold)
+        {
+            Ok(index) => index,
+            Err(index) => index.saturating_sub(1),
+        };
+        match self.tiers.get(index) {
+            Some(tier) => (index as u32, tier.fulfillment_flat_fee_link_ppm),
+            None => (0, 0),
+        }
+    }
+}
+
+/// Terms of a CEP-18 token subscriptions can pay in besides the coordinator's `payment_token`,
+/// whose terms are the `FeeConfig`, `price_feed` and fallback price of the coordinator.
+#[derive(Serialize, Deserialize, Clone, CLTyped, ToBytes, FromBytes)]
+pub struct PaymentTokenConfig {
+    /// New subscriptions can choose the token, existing ones keep paying in it either way.
+    pub accepted: bool,
+    /// Feed of the token price in motes, the null key to always use the fallback price.
+    pub price_feed: Key,
+    pub fallback_motes_per_unit_link: U256,
+    pub fee_config: FeeConfig,
+}
+
+impl Default for PaymentTokenConfig {
+    fn default() -> Self {
+        Self {
+            accepted: false,
+            price_feed: helpers::null_key(),
+            fallback_motes_per_unit_link: U256::zero(),
+            fee_config: Default::default(),
+        }
+    }
+}
+
+/// Stage of a request in the coordinator.
+#[repr(u8)]
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum RequestStatus {
+    /// No request was made with this id.
+    Unknown = 0,
+    Pending = 1,
+    /// Words were delivered to the consumer, or stored for a pull request.
+    Fulfilled = 2,
+    /// Cancelled with `cancel_request` after the request timeout.
+    Cancelled = 3,
+    /// Paid for without calling back the consumer, whose words are stored instead.
+    Failed = 4,
+}
+
+impl RequestStatus {
+    pub fn from_u8(v: u8) -> Option<Self> {
+        match v {
+            0 => Some(Self::Unknown),
+            1 => Some(Self::Pending),
+            2 => Some(Self::Fulfilled),
+            3 => Some(Self::Cancelled),
+            4 => Some(Self::Failed),
+            _ => None,
+        }
+    }
+}
+
+impl CLTyped for RequestStatus {
+    fn cl_type() -> CLType {
+        CLType::U8
+    }
+}
+
+impl ToBytes for RequestStatus {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        (*self as u8).to_bytes()
+    }
+
+    fn serialized_length(&self) -> usize {
+        (*self as u8).serialized_length()
+    }
+}
+
+impl FromBytes for RequestStatus {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (v, remainder) = u8::from_bytes(bytes)?;
+        let status = Self::from_u8(v).ok_or(bytesrepr::Error::Formatting)?;
+        Ok((status, remainder))
+    }
+}
+
+/// Everything an oracle needs to fulfill a request, stored by the coordinator along with the
+/// hash it checks fulfillments against.
+#[derive(Serialize, Deserialize, Clone, CLTyped, ToBytes, FromBytes)]
+pub struct RequestRecord {
+    pub key_hash: HashAddr,
+    pub pre_seed: U256,
+    pub commitment: RequestCommitment,
+    pub status: RequestStatus,
+}
+
+impl Default for RequestRecord {
+    fn default() -> Self {
+        Self {
+            key_hash: HashAddr::default(),
+            pre_seed: U256::zero(),
+            commitment: RequestCommitment::default(),
+            status: RequestStatus::Unknown,
+        }
+    }
+}
+
+/// Proof format a registered proving key is verified with.
+#[repr(u8)]
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ProvingKeyType {
+    /// Secp256k1 proof with precomputed witnesses, checked by `vrf::random_value_from_vrf_proof`.
+    Secp256k1Witness = 0,
+    /// ECVRF-SECP256K1-SHA256-TAI proof following RFC 9381.
+    Secp256k1Rfc9381 = 1,
+    /// ECVRF-EDWARDS25519-SHA512-TAI proof of RFC 9381, for ed25519 keys.
+    Ed25519Rfc9381 = 2,
+}
+
+impl ProvingKeyType {
+    pub fn from_u8(v: u8) -> Option<Self> {
+        match v {
+            0 => Some(Self::Secp256k1Witness),
+            1 => Some(Self::Secp256k1Rfc9381),
+            2 => Some(Self::Ed25519Rfc9381),
+            _ => None,
+        }
+    }
+}
+
+impl CLTyped for ProvingKeyType {
+    fn cl_type() -> CLType {
+        CLType::U8
+    }
+}
+
+impl ToBytes for ProvingKeyType {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        (*self as u8).to_bytes()
+    }
+
+    fn serialized_length(&self) -> usize {
+        (*self as u8).serialized_length()
+    }
+}
+
+impl FromBytes for ProvingKeyType {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (v, remainder) = u8::from_bytes(bytes)?;
+        let key_type = Self::from_u8(v).ok_or(bytesrepr::Error::Formatting)?;
+        Ok((key_type, remainder))
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, CLTyped, ToBytes, FromBytes)]
+pub struct ProvingKey {
+    pub oracle: Key,
+    pub key_type: ProvingKeyType,
+}
+
+impl Default for ProvingKey {
+    fn default() -> Self {
+        Self {
+            oracle: helpers::null_key(),
+            key_type: ProvingKeyType::Secp256k1Witness,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, CLTyped, ToBytes, FromBytes)]
@@ -90,3 +343,33 @@ pub struct Proof {
     pub s_hash_witness: Bytes,
     pub z_inv: U256,
 }
+
+/// Configuration of the direct funding wrapper.
+#[derive(Serialize, Deserialize, Clone, CLTyped, ToBytes, FromBytes, Default, Copy)]
+pub struct WrapperConfig {
+    /// Proving key the wrapper requests words from.
+    pub key_hash: HashAddr,
+    /// Gas the wrapper spends forwarding the words, added to the callback gas limit of requests.
+    pub wrapper_gas_overhead: U128,
+    /// Premium charged on top of the coordinator payment, in percent.
+    pub wrapper_premium_percentage: u8,
+    pub max_num_words: u64,
+}
+
+/// Request made through the wrapper, kept until its words are forwarded.
+#[derive(Serialize, Deserialize, Clone, CLTyped, ToBytes, FromBytes)]
+pub struct WrapperCallback {
+    pub callback_address: Key,
+    pub callback_gas_limit: U128,
+    pub request_price: U128,
+}
+
+impl Default for WrapperCallback {
+    fn default() -> Self {
+        Self {
+            callback_address: helpers::null_key(),
+            callback_gas_limit: 0.into(),
+            request_price: 0.into(),
+        }
+    }
+}
@@ -50,6 +50,25 @@ pub enum Error {
     InvalidProof,
     ErrorGettingSignature,
     InvalidSignature,
+    InvalidProvingKeyType,
+    OnlyCoordinatorCanFulfill,
+    RequesterMustBeContract,
+    UnknownWrapperRequest,
+    NotRandomWordsRecipient,
+    RandomWordsNotFulfilled,
+    OnlyConsumerOrSubOwner,
+    RequestNotExpired,
+    NotEnoughConfirmations,
+    InvalidSubscriptionLimits,
+    NumWordsAboveSubscriptionLimit,
+    ConsumerRequestLimitExceeded,
+    SpendLimitExceeded,
+    CsprRecipientMustBeAccount,
+    PaymentTokenNotAccepted,
+    WrongPaymentToken,
+    InvalidFeeConfig,
+    IncorrectBlockRef,
+    AmountTooLarge,
 }
 
 impl From<Error> for ApiError {
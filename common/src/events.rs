@@ -1,6 +1,8 @@
 use casper_event_standard::Event;
 use casper_types::{HashAddr, U256, U128, Key};
 
+use crate::data_types::BlockRef;
+use crate::error::Error;
 use crate::helpers::current_block_timestamp;
 
 #[derive(Event, Debug, PartialEq, Eq)]
@@ -13,6 +15,8 @@ pub struct RandomWordsRequested {
     pub callback_cas_limit: U128,
     pub num_words: u64,
     pub sender: Key,
+    /// Block of the request, only its timestamp is known to the coordinator.
+    pub block: BlockRef,
     pub timestamp: u64,
 }
 #[allow(clippy::too_many_arguments)]
@@ -26,6 +30,7 @@ impl RandomWordsRequested {
         callback_cas_limit: U128,
         num_words: u64,
         sender: Key,
+        block: BlockRef,
     ) -> Self {
         Self {
             key_hash,
@@ -36,7 +41,44 @@ impl RandomWordsRequested {
             callback_cas_limit,
             num_words,
             sender,
+            block,
             timestamp: current_block_timestamp(),
         }
     }
-}
\ No newline at end of file
+}
+/// Item of a `fulfill_random_words_batch` that was skipped. `request_id` is zero when the item
+/// could not be decoded.
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct RandomWordsFulfillmentFailed {
+    pub index: u32,
+    pub request_id: U256,
+    pub error: u32,
+    pub timestamp: u64,
+}
+impl RandomWordsFulfillmentFailed {
+    pub fn new(index: u32, request_id: U256, error: Error) -> Self {
+        Self {
+            index,
+            request_id,
+            error: error as u32,
+            timestamp: current_block_timestamp(),
+        }
+    }
+}
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct RandomWordsRequestCancelled {
+    pub request_id: U256,
+    pub sub_id: u64,
+    pub cancelled_by: Key,
+    pub timestamp: u64,
+}
+impl RandomWordsRequestCancelled {
+    pub fn new(request_id: U256, sub_id: u64, cancelled_by: Key) -> Self {
+        Self {
+            request_id,
+            sub_id,
+            cancelled_by,
+            timestamp: current_block_timestamp(),
+        }
+    }
+}
@@ -259,6 +259,15 @@ pub fn require(v: bool, e: Error) {
     }
 }
 
+/// `require` for checks whose failure is returned to the caller instead of reverting.
+pub fn ensure(v: bool, e: Error) -> Result<(), Error> {
+    if v {
+        Ok(())
+    } else {
+        Err(e)
+    }
+}
+
 pub fn encode_dictionary_item_key(key: Key) -> String {
     match key {
         Key::Account(account_hash) => account_hash.to_string(),
@@ -301,6 +310,12 @@ pub fn decode_1<T1: CLTyped + FromBytes>(bytes: &[u8]) -> T1 {
     t1
 }
 
+pub fn try_decode_1<T1: CLTyped + FromBytes>(bytes: &[u8]) -> Result<T1, Error> {
+    T1::from_bytes(bytes)
+        .map(|(t1, _)| t1)
+        .map_err(|_| Error::FailedToDecodeInputBytes)
+}
+
 pub fn encode_2<T1: CLTyped + ToBytes, T2: CLTyped + ToBytes>(t1: &T1, t2: &T2) -> Vec<u8> {
     let mut bytes_1 = t1.to_bytes().unwrap_or_revert();
     let mut bytes_2 = t2.to_bytes().unwrap_or_revert();
@@ -462,6 +477,29 @@ pub fn decode_6<
     (t1, t2, t3, t4, t5, t6)
 }
 
+pub fn encode_7<
+    T1: CLTyped + ToBytes,
+    T2: CLTyped + ToBytes,
+    T3: CLTyped + ToBytes,
+    T4: CLTyped + ToBytes,
+    T5: CLTyped + ToBytes,
+    T6: CLTyped + ToBytes,
+    T7: CLTyped + ToBytes,
+>(
+    t1: &T1,
+    t2: &T2,
+    t3: &T3,
+    t4: &T4,
+    t5: &T5,
+    t6: &T6,
+    t7: &T7,
+) -> Vec<u8> {
+    let mut bytes_1 = encode_6(t1, t2, t3, t4, t5, t6);
+    let mut bytes_7 = t7.to_bytes().unwrap_or_revert();
+    bytes_1.append(&mut bytes_7);
+    bytes_1
+}
+
 pub fn wrap_cspr(wcspr: Key, purse: URef, amount: U512) {
     let _: () = runtime::call_versioned_contract(
         wcspr.into_hash().unwrap().into(),
@@ -515,14 +553,12 @@ pub fn u512_to_u256(nb: U512) -> U256 {
 
 pub fn mulmod(a: U256, b: U256, c: U256) -> U256 {
     let ret = u256_to_u512(a) * u256_to_u512(b);
-    let ret = ret.div_mod(u256_to_u512(c)).1;
-    U256::from_dec_str(&ret.to_string()).unwrap()
+    u512_to_u256(ret.div_mod(u256_to_u512(c)).1)
 }
 
 pub fn add_mod(a: U256, b: U256, c: U256) -> U256 {
     let ret = u256_to_u512(a) + u256_to_u512(b);
-    let ret = ret.div_mod(u256_to_u512(c)).1;
-    U256::from_dec_str(&ret.to_string()).unwrap()
+    u512_to_u256(ret.div_mod(u256_to_u512(c)).1)
 }
 pub(crate) fn get_named_arg_size(name: &str) -> Option<usize> {
     let mut arg_size: usize = 0;
@@ -543,13 +579,15 @@ pub(crate) fn get_named_arg_size(name: &str) -> Option<usize> {
 // The optional here is literal and does not co-relate to an Option enum type.
 // If the argument has been provided it is accepted, and is then turned into a Some.
 // If the argument is not provided at all, then it is considered as None.
+// An argument that is provided but does not decode as T reverts with `invalid`.
 pub fn get_optional_named_arg_with_user_errors<T: FromBytes>(
     name: &str,
     invalid: Error,
 ) -> Option<T> {
     match get_named_arg_with_user_errors::<T>(name, Error::Phantom, invalid) {
         Ok(val) => Some(val),
-        Err(_) => None,
+        Err(Error::Phantom) => None,
+        Err(e) => runtime::revert(e),
     }
 }
 
@@ -1,26 +1,94 @@
 use alloc::{borrow::ToOwned, string::String, vec::Vec};
 use casper_contract::contract_api::runtime::call_versioned_contract;
-use casper_types::{runtime_args, Key, RuntimeArgs, U256};
+use casper_types::{runtime_args, HashAddr, Key, RuntimeArgs, U128, U256};
 
-pub fn set_block_hash(contract_package: Key, height: u64, hash: String) {
+use crate::data_types::{BlockRef, Config, SubscriptionView};
+
+pub fn set_block_hash(contract_package: Key, block: BlockRef, hash: String) {
     call_versioned_contract::<()>(
         contract_package.into_hash().unwrap().into(),
         None,
         "set_block_hash",
         runtime_args! {
-            "height" =>  height,
+            "era_id" => block.era_id,
+            "height" => block.height,
+            "timestamp" => block.timestamp,
             "hash" => hash
         },
     );
 }
 
-pub fn get_block_hash(contract_package: Key, height: u64) -> String {
+pub fn get_block_hash(contract_package: Key, timestamp: u64) -> String {
     call_versioned_contract::<String>(
         contract_package.into_hash().unwrap().into(),
         None,
         "get_block_hash",
         runtime_args! {
-            "height" =>  height,
+            "timestamp" => timestamp,
+        },
+    )
+}
+
+pub fn get_block_ref(contract_package: Key, timestamp: u64) -> BlockRef {
+    call_versioned_contract::<BlockRef>(
+        contract_package.into_hash().unwrap().into(),
+        None,
+        "get_block_ref",
+        runtime_args! {
+            "timestamp" => timestamp,
+        },
+    )
+}
+
+pub fn get_latest_block_ref(contract_package: Key) -> BlockRef {
+    call_versioned_contract::<BlockRef>(
+        contract_package.into_hash().unwrap().into(),
+        None,
+        "get_latest_block_ref",
+        runtime_args! {},
+    )
+}
+
+pub fn get_latest_round_data(price_feed: Key) -> (U256, u64) {
+    call_versioned_contract::<(U256, u64)>(
+        price_feed.into_hash().unwrap().into(),
+        None,
+        "latest_round_data",
+        runtime_args! {},
+    )
+}
+
+pub fn request_random_words(
+    vrf_coordinator: Key,
+    key_hash: HashAddr,
+    sub_id: u64,
+    request_confirmations: u64,
+    callback_gas_limit: U128,
+    num_words: u64,
+    pull: bool,
+) -> U256 {
+    call_versioned_contract::<U256>(
+        vrf_coordinator.into_hash().unwrap().into(),
+        None,
+        "request_random_words",
+        runtime_args! {
+            "key_hash" => key_hash,
+            "sub_id" => sub_id,
+            "request_confirmations" => request_confirmations,
+            "callback_gas_limit" => callback_gas_limit,
+            "num_words" => num_words,
+            "pull" => pull,
+        },
+    )
+}
+
+pub fn claim_random_words(vrf_coordinator: Key, request_id: U256) -> Vec<U256> {
+    call_versioned_contract::<Vec<U256>>(
+        vrf_coordinator.into_hash().unwrap().into(),
+        None,
+        "claim_random_words",
+        runtime_args! {
+            "request_id" => request_id,
         },
     )
 }
@@ -36,3 +104,129 @@ pub fn call_raw_fulfill_random_words(consumer: Key, request_id: U256, random_wor
         },
     );
 }
+
+pub fn get_config(vrf_coordinator: Key) -> Config {
+    call_versioned_contract::<Config>(
+        vrf_coordinator.into_hash().unwrap().into(),
+        None,
+        "get_config",
+        runtime_args! {},
+    )
+}
+
+pub fn get_fee_tier(vrf_coordinator: Key, req_count: u64, payment_token: Key) -> (u32, u64) {
+    call_versioned_contract::<(u32, u64)>(
+        vrf_coordinator.into_hash().unwrap().into(),
+        None,
+        "get_fee_tier",
+        runtime_args! {
+            "req_count" => req_count,
+            "payment_token" => payment_token
+        },
+    )
+}
+
+pub fn get_motes_per_unit_link(vrf_coordinator: Key, payment_token: Key) -> U256 {
+    call_versioned_contract::<U256>(
+        vrf_coordinator.into_hash().unwrap().into(),
+        None,
+        "get_motes_per_unit_link",
+        runtime_args! {
+            "payment_token" => payment_token
+        },
+    )
+}
+
+pub fn get_payment_token(contract_package: Key) -> Key {
+    call_versioned_contract::<Key>(
+        contract_package.into_hash().unwrap().into(),
+        None,
+        "get_payment_token",
+        runtime_args! {},
+    )
+}
+
+pub fn create_subscription(vrf_coordinator: Key) -> u64 {
+    call_versioned_contract::<u64>(
+        vrf_coordinator.into_hash().unwrap().into(),
+        None,
+        "create_subscription",
+        runtime_args! {},
+    )
+}
+
+pub fn get_subscription_view(vrf_coordinator: Key, sub_id: u64) -> SubscriptionView {
+    call_versioned_contract::<SubscriptionView>(
+        vrf_coordinator.into_hash().unwrap().into(),
+        None,
+        "get_subscription_view",
+        runtime_args! {
+            "sub_id" => sub_id
+        },
+    )
+}
+
+pub fn cancel_subscription(vrf_coordinator: Key, sub_id: u64, to: Key) {
+    call_versioned_contract::<()>(
+        vrf_coordinator.into_hash().unwrap().into(),
+        None,
+        "cancel_subscription",
+        runtime_args! {
+            "sub_id" => sub_id,
+            "to" => to
+        },
+    );
+}
+
+pub fn add_consumer(vrf_coordinator: Key, sub_id: u64, consumer: Key) {
+    call_versioned_contract::<()>(
+        vrf_coordinator.into_hash().unwrap().into(),
+        None,
+        "add_consumer",
+        runtime_args! {
+            "sub_id" => sub_id,
+            "consumer" => consumer
+        },
+    );
+}
+
+pub fn deposit_token(vrf_coordinator: Key, sub_id: u64, amount: U128) {
+    call_versioned_contract::<()>(
+        vrf_coordinator.into_hash().unwrap().into(),
+        None,
+        "deposit_token",
+        runtime_args! {
+            "sub_id" => sub_id,
+            "amount" => amount
+        },
+    );
+}
+
+pub fn calculate_request_price(vrf_wrapper: Key, callback_gas_limit: U128) -> U128 {
+    call_versioned_contract::<U128>(
+        vrf_wrapper.into_hash().unwrap().into(),
+        None,
+        "calculate_request_price",
+        runtime_args! {
+            "callback_gas_limit" => callback_gas_limit
+        },
+    )
+}
+
+pub fn wrapper_request_random_words(
+    vrf_wrapper: Key,
+    callback_gas_limit: U128,
+    request_confirmations: u64,
+    num_words: u64,
+) -> U256 {
+    call_versioned_contract::<U256>(
+        vrf_wrapper.into_hash().unwrap().into(),
+        None,
+        "request_random_words",
+        runtime_args! {
+            "callback_gas_limit" => callback_gas_limit,
+            "request_confirmations" => request_confirmations,
+            "num_words" => num_words,
+        },
+    )
+}
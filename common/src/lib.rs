@@ -13,6 +13,7 @@ pub mod lock;
 pub mod macros;
 pub mod owner;
 pub mod pausable;
+pub mod payment;
 pub mod timestamp_testing;
 pub mod upgrade;
 pub mod events;
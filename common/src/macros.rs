@@ -45,6 +45,7 @@ macro_rules! get_set_no_set {
 #[macro_export]
 macro_rules! get_set_dict {
     ($dict_arg_name: expr, $key_name: expr, $t: ty, $v: ty, $default: expr, $save: ident, $read: ident, $get: ident, $set: ident) => {
+        #[allow(clippy::ptr_arg)]
         pub fn $save(k: &$t, v: &$v) {
             helpers::write_dictionary_value_from_key(
                 $dict_arg_name,
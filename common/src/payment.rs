@@ -0,0 +1,31 @@
+//! Pricing of fulfillments, free of contract calls so the arithmetic can be checked natively.
+use casper_types::{U128, U256};
+
+use crate::error::Error;
+
+/// Units of payment token charged per part per million of flat fee.
+pub const FLAT_FEE_UNIT: u64 = 1_000_000_000_000;
+/// Units of one payment token, the amount `motes_per_unit_link` prices.
+pub const PAYMENT_TOKEN_UNIT: u64 = 1_000_000_000_000_000_000;
+
+/// Payment owed to the oracle for a fulfillment whose consumer callback used `gas_used`,
+/// with `gas_after_payment_calculation` spent on accounting after the calculation and the
+/// flat fee of the subscription's tier, `fulfillment_flat_fee_link_ppm` millionths of a token.
+/// Gas is paid at one mote per unit, converted at `motes_per_unit_link` motes per token.
+pub fn calculate_payment_amount(
+    gas_used: U128,
+    gas_after_payment_calculation: U128,
+    fulfillment_flat_fee_link_ppm: u64,
+    motes_per_unit_link: U256,
+) -> Result<U128, Error> {
+    if motes_per_unit_link.is_zero() {
+        return Err(Error::InvalidLinkWeiPrice);
+    }
+    let gas = U256::from(gas_after_payment_calculation.as_u128()) + U256::from(gas_used.as_u128());
+    let payment_no_fee = U256::from(PAYMENT_TOKEN_UNIT) * gas / motes_per_unit_link;
+    let fee = U256::from(FLAT_FEE_UNIT) * U256::from(fulfillment_flat_fee_link_ppm);
+    if payment_no_fee > U256::from(u128::MAX) - fee {
+        return Err(Error::PaymentTooLarge);
+    }
+    Ok(U128::from((payment_no_fee + fee).as_u128()))
+}
@@ -0,0 +1,97 @@
+//! ECVRF-EDWARDS25519-SHA512-TAI of RFC 9381.
+//!
+//! Proofs reuse [`Proof`]: `pk` is the 32 byte ed25519 public key, as held by a Casper
+//! ed25519 account, and `gamma` a 32 byte encoded point. `c` and `s` carry the integers
+//! decoded from the little endian challenge and response strings. The witness fields are
+//! ignored.
+use casper_types::U256;
+use common::{data_types::Proof, error::Error, helpers::ensure};
+use curve25519_dalek::{
+    constants::ED25519_BASEPOINT_TABLE,
+    edwards::{CompressedEdwardsY, EdwardsPoint},
+    scalar::Scalar,
+    traits::IsIdentity,
+};
+use sha2::{Digest, Sha512};
+
+const SUITE_STRING: u8 = 0x03;
+const C_LEN: usize = 16;
+
+fn string_to_point(bytes: &[u8]) -> Option<EdwardsPoint> {
+    if bytes.len() != 32 {
+        return None;
+    }
+    CompressedEdwardsY::from_slice(bytes).decompress()
+}
+
+fn encode_to_curve(pk_string: &[u8], alpha: &[u8]) -> Result<EdwardsPoint, Error> {
+    for ctr in 0..=u8::MAX {
+        let hash = Sha512::new()
+            .chain([SUITE_STRING, 0x01])
+            .chain(pk_string)
+            .chain(alpha)
+            .chain([ctr, 0x00])
+            .finalize();
+        if let Some(h) = string_to_point(&hash[..32]) {
+            let h = h.mul_by_cofactor();
+            if !h.is_identity() {
+                return Ok(h);
+            }
+        }
+    }
+    Err(Error::InvalidProof)
+}
+
+fn challenge_generation(points: &[&EdwardsPoint]) -> U256 {
+    let mut hasher = Sha512::new().chain([SUITE_STRING, 0x02]);
+    for p in points {
+        hasher.update(p.compress().as_bytes());
+    }
+    let c_string = hasher.chain([0x00]).finalize();
+    U256::from_little_endian(&c_string[..C_LEN])
+}
+
+fn proof_to_hash(gamma: &EdwardsPoint) -> [u8; 64] {
+    let mut beta = [0u8; 64];
+    beta.copy_from_slice(
+        &Sha512::new()
+            .chain([SUITE_STRING, 0x03])
+            .chain(gamma.mul_by_cofactor().compress().as_bytes())
+            .chain([0x00])
+            .finalize(),
+    );
+    beta
+}
+
+fn to_scalar_bytes(v: U256) -> [u8; 32] {
+    let mut b = [0u8; 32];
+    v.to_little_endian(&mut b);
+    b
+}
+
+/// Verifies `proof` for `alpha` and returns the 64 byte VRF output beta.
+pub fn verify(proof: &Proof, alpha: &[u8]) -> Result<[u8; 64], Error> {
+    let y = string_to_point(&proof.pk).ok_or(Error::KeyNotOnCurve)?;
+    ensure(!y.is_small_order(), Error::KeyNotOnCurve)?;
+    let gamma = string_to_point(&proof.gamma).ok_or(Error::KeyNotOnCurve)?;
+    let s = Scalar::from_canonical_bytes(to_scalar_bytes(proof.s)).ok_or(Error::InvalidProof)?;
+    let c = Scalar::from_bytes_mod_order(to_scalar_bytes(proof.c));
+
+    let h = encode_to_curve(&proof.pk, alpha)?;
+    let u = &ED25519_BASEPOINT_TABLE * &s - y * c;
+    let v = h * s - gamma * c;
+
+    let derived_c = challenge_generation(&[&y, &h, &gamma, &u, &v]);
+    ensure(proof.c == derived_c, Error::InvalidProof)?;
+    Ok(proof_to_hash(&gamma))
+}
+
+/// Verifies `proof` for the 32 byte big endian encoding of `seed` and returns the first 32
+/// bytes of beta, read as a big endian integer.
+#[inline(never)]
+pub fn random_value_from_vrf_proof(proof: &Proof, seed: U256) -> Result<U256, Error> {
+    let mut alpha = [0u8; 32];
+    seed.to_big_endian(&mut alpha);
+    let beta = verify(proof, &alpha)?;
+    Ok(U256::from_big_endian(&beta[..32]))
+}
@@ -0,0 +1,10 @@
+//! RFC 9381 ECVRF verifiers the coordinator selects per proving key, next to the witness
+//! verifier of its `vrf` module.
+//!
+//! They use no contract API, so the tests check them natively against known answers.
+#![no_std]
+
+extern crate alloc;
+
+pub mod edwards25519;
+pub mod secp256k1;
@@ -0,0 +1,100 @@
+//! ECVRF-SECP256K1-SHA256-TAI: the try-and-increment ECVRF of RFC 9381, instantiated over
+//! secp256k1 with SHA-256 in the same way as the ECVRF-P256-SHA256-TAI suite.
+//!
+//! Proofs reuse [`Proof`]: `pk` and `gamma` are SEC1 encoded points, `c` is the 16 byte
+//! challenge and `s` the response scalar. The witness fields are ignored.
+use alloc::vec::Vec;
+use casper_types::U256;
+use common::{data_types::Proof, error::Error, helpers::ensure};
+use k256::{
+    elliptic_curve::{
+        ff::PrimeField,
+        sec1::{FromEncodedPoint, ToEncodedPoint},
+    },
+    AffinePoint, EncodedPoint, FieldBytes, ProjectivePoint, Scalar,
+};
+use sha2::{Digest, Sha256};
+
+const SUITE_STRING: u8 = 0xFE;
+const C_LEN: usize = 16;
+
+fn field_bytes(v: U256) -> FieldBytes {
+    let mut b = [0u8; 32];
+    v.to_big_endian(&mut b);
+    FieldBytes::from(b)
+}
+
+fn string_to_point(bytes: &[u8]) -> Option<ProjectivePoint> {
+    let encoded = EncodedPoint::from_bytes(bytes).ok()?;
+    if encoded.is_identity() {
+        return None;
+    }
+    AffinePoint::from_encoded_point(&encoded).map(ProjectivePoint::from)
+}
+
+fn point_to_string(p: &ProjectivePoint) -> Vec<u8> {
+    p.to_affine().to_encoded_point(true).as_bytes().to_vec()
+}
+
+fn encode_to_curve(pk_string: &[u8], alpha: &[u8]) -> Result<ProjectivePoint, Error> {
+    for ctr in 0..=u8::MAX {
+        let hash = Sha256::new()
+            .chain([SUITE_STRING, 0x01])
+            .chain(pk_string)
+            .chain(alpha)
+            .chain([ctr, 0x00])
+            .finalize();
+        let mut candidate = [0x02; 33];
+        candidate[1..].copy_from_slice(&hash);
+        if let Some(h) = string_to_point(&candidate) {
+            return Ok(h);
+        }
+    }
+    Err(Error::InvalidProof)
+}
+
+fn challenge_generation(points: &[&ProjectivePoint]) -> U256 {
+    let mut hasher = Sha256::new().chain([SUITE_STRING, 0x02]);
+    for p in points {
+        hasher.update(point_to_string(p));
+    }
+    let c_string = hasher.chain([0x00]).finalize();
+    U256::from_big_endian(&c_string[..C_LEN])
+}
+
+fn proof_to_hash(gamma: &ProjectivePoint) -> [u8; 32] {
+    let mut beta = [0u8; 32];
+    beta.copy_from_slice(
+        &Sha256::new()
+            .chain([SUITE_STRING, 0x03])
+            .chain(point_to_string(gamma))
+            .chain([0x00])
+            .finalize(),
+    );
+    beta
+}
+
+/// Verifies `proof` for `alpha` and returns the 32 byte VRF output beta.
+pub fn verify(proof: &Proof, alpha: &[u8]) -> Result<[u8; 32], Error> {
+    let y = string_to_point(&proof.pk).ok_or(Error::KeyNotOnCurve)?;
+    let gamma = string_to_point(&proof.gamma).ok_or(Error::KeyNotOnCurve)?;
+    let s = Scalar::from_repr(field_bytes(proof.s)).ok_or(Error::InvalidProof)?;
+    let c = Scalar::from_bytes_reduced(&field_bytes(proof.c));
+
+    let h = encode_to_curve(&point_to_string(&y), alpha)?;
+    let u = ProjectivePoint::generator() * s - y * c;
+    let v = h * s - gamma * c;
+
+    let derived_c = challenge_generation(&[&y, &h, &gamma, &u, &v]);
+    ensure(proof.c == derived_c, Error::InvalidProof)?;
+    Ok(proof_to_hash(&gamma))
+}
+
+/// Verifies `proof` for the 32 byte big endian encoding of `seed` and returns the VRF output.
+#[inline(never)]
+pub fn random_value_from_vrf_proof(proof: &Proof, seed: U256) -> Result<U256, Error> {
+    let mut alpha = [0u8; 32];
+    seed.to_big_endian(&mut alpha);
+    let beta = verify(proof, &alpha)?;
+    Ok(U256::from_big_endian(&beta))
+}
@@ -0,0 +1,145 @@
+//! Flips a coin for whoever calls `flip`, with the result delivered by the VRF coordinator.
+//!
+//! Installed with `vrf_wrapper` instead of a subscription, it pays each request inline through
+//! the direct funding wrapper out of the payment tokens this contract holds.
+#![no_main]
+#![no_std]
+
+extern crate alloc;
+mod entry_points;
+
+pub mod store;
+
+use alloc::{format, string::String, vec::Vec};
+use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
+use casper_types::{
+    contracts::NamedKeys, runtime_args, ApiError, HashAddr, Key, RuntimeArgs, U256,
+};
+use common::constants::*;
+use common::helpers;
+use common::owner;
+use common::upgrade;
+
+const REQUEST_CONFIRMATIONS: u64 = 3;
+const CALLBACK_GAS_LIMIT: u128 = 100_000_000;
+const NUM_WORDS: u64 = 1;
+pub const HEADS: u8 = 1;
+pub const TAILS: u8 = 2;
+pub const FLIP_IN_PROGRESS: u8 = 3;
+
+#[repr(u16)]
+pub enum Error {
+    FlipInProgress = 20000,
+}
+
+impl From<Error> for ApiError {
+    fn from(e: Error) -> Self {
+        ApiError::User(e as u16)
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn init() {
+    if helpers::get_key::<Key>("contract_hash").is_some() {
+        runtime::revert(common::error::Error::ContractAlreadyInitialized);
+    }
+
+    let caller = helpers::get_immediate_caller_key();
+    let contract_hash: Key = runtime::get_named_arg("contract_hash");
+    let contract_package_hash: Key = runtime::get_named_arg("contract_package_hash");
+
+    helpers::set_key("contract_hash", contract_hash);
+    helpers::set_key("contract_package_hash", contract_package_hash);
+    owner::init(caller);
+    match vrf_wrapper_arg() {
+        Some(vrf_wrapper) => {
+            vrf_consumer::init(vrf_wrapper);
+            store::initialize(HashAddr::default(), 0);
+        }
+        None => {
+            let vrf_coordinator: Key = runtime::get_named_arg("vrf_coordinator");
+            let key_hash: HashAddr = runtime::get_named_arg("key_hash");
+            let sub_id: u64 = runtime::get_named_arg("sub_id");
+            vrf_consumer::init(vrf_coordinator);
+            store::initialize(key_hash, sub_id);
+        }
+    }
+}
+
+/**
+ * @notice Flips a coin for the caller, whose result is readable through get_result once the
+ * coordinator fulfills the request
+ * @dev without a subscription the request is paid to the wrapper, which forwards the word
+ */
+#[no_mangle]
+pub extern "C" fn flip() {
+    let player = helpers::get_immediate_caller_key();
+    if store::read_result(&player) == FLIP_IN_PROGRESS {
+        runtime::revert(Error::FlipInProgress);
+    }
+    let sub_id = store::read_sub_id();
+    let request_id = if sub_id == 0 {
+        vrf_consumer::request_randomness(
+            CALLBACK_GAS_LIMIT.into(),
+            REQUEST_CONFIRMATIONS,
+            NUM_WORDS,
+        )
+    } else {
+        vrf_consumer::request_random_words(
+            store::read_key_hash(),
+            sub_id,
+            REQUEST_CONFIRMATIONS,
+            CALLBACK_GAS_LIMIT.into(),
+            NUM_WORDS,
+        )
+    };
+    store::save_player(&request_id, &player);
+    store::save_result(&player, &FLIP_IN_PROGRESS);
+}
+
+fn fulfill_random_words(request_id: U256, random_words: Vec<U256>) {
+    let player = store::read_player(&request_id);
+    let result = if (random_words[0] % 2).is_zero() {
+        HEADS
+    } else {
+        TAILS
+    };
+    store::save_result(&player, &result);
+}
+
+vrf_consumer::raw_fulfill_random_words!(fulfill_random_words);
+
+fn vrf_wrapper_arg() -> Option<Key> {
+    helpers::get_optional_named_arg_with_user_errors(
+        "vrf_wrapper",
+        common::error::Error::FailedToDecodeInputBytes,
+    )
+}
+
+#[no_mangle]
+fn call() {
+    let contract_name: String = runtime::get_named_arg("contract_name");
+    if !runtime::has_key(&format!("{}_package_hash", contract_name)) {
+        let (contract_hash, contract_package_hash) =
+            upgrade::install_contract(contract_name, entry_points::default(), NamedKeys::new());
+        let mut args = runtime_args! {
+            "contract_hash" => Key::from(contract_hash),
+            "contract_package_hash" => Key::from(contract_package_hash)
+        };
+        match vrf_wrapper_arg() {
+            Some(vrf_wrapper) => args.insert("vrf_wrapper", vrf_wrapper).unwrap_or_revert(),
+            None => {
+                let vrf_coordinator: Key = runtime::get_named_arg("vrf_coordinator");
+                let key_hash: HashAddr = runtime::get_named_arg("key_hash");
+                let sub_id: u64 = runtime::get_named_arg("sub_id");
+                args.insert("vrf_coordinator", vrf_coordinator)
+                    .unwrap_or_revert();
+                args.insert("key_hash", key_hash).unwrap_or_revert();
+                args.insert("sub_id", sub_id).unwrap_or_revert();
+            }
+        }
+        runtime::call_contract::<()>(contract_hash, INIT_ENTRY_POINT_NAME, args);
+    } else {
+        upgrade::upgrade_contract(contract_name, entry_points::default(), NamedKeys::new());
+    }
+}
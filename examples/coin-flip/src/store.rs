@@ -0,0 +1,56 @@
+use casper_contract::contract_api::{runtime, storage};
+use casper_contract::unwrap_or_revert::UnwrapOrRevert;
+use casper_types::{CLValue, HashAddr, Key, U256};
+use common::error::Error;
+use common::{get_set_dict, get_set_no_set, helpers};
+
+pub fn initialize(key_hash: HashAddr, sub_id: u64) {
+    save_key_hash(key_hash);
+    save_sub_id(sub_id);
+    storage::new_dictionary("players").unwrap_or_revert_with(Error::FailedToCreateDictionary);
+    storage::new_dictionary("results").unwrap_or_revert_with(Error::FailedToCreateDictionary);
+}
+
+get_set_no_set!(
+    key_hash,
+    "key_hash",
+    HashAddr,
+    HashAddr::default(),
+    save_key_hash,
+    read_key_hash,
+    get_key_hash
+);
+
+get_set_no_set!(
+    sub_id,
+    "sub_id",
+    u64,
+    0,
+    save_sub_id,
+    read_sub_id,
+    get_sub_id
+);
+
+get_set_dict!(
+    "players",
+    "request_id",
+    U256,
+    Key,
+    helpers::null_key(),
+    save_player,
+    read_player,
+    get_player,
+    set_player
+);
+
+get_set_dict!(
+    "results",
+    "player",
+    Key,
+    u8,
+    0,
+    save_result,
+    read_result,
+    get_result,
+    set_result
+);
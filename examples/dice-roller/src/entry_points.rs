@@ -0,0 +1,42 @@
+use alloc::{string::String, vec, vec::Vec};
+use casper_types::{CLType, EntryPoint, EntryPointAccess, EntryPointType, EntryPoints, Parameter};
+
+use common::owner;
+
+fn add_entry_points(entry_points: &mut EntryPoints, list: &Vec<EntryPoint>) {
+    for e in list {
+        entry_points.add_entry_point(e.clone());
+    }
+}
+
+pub(crate) fn default() -> EntryPoints {
+    let mut entry_points = EntryPoints::new();
+    add_entry_points(&mut entry_points, &owner::entry_points());
+    add_entry_points(&mut entry_points, &vrf_consumer::entry_points());
+
+    entry_points.add_entry_point(EntryPoint::new(
+        String::from("roll_dice"),
+        vec![],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    // view functions
+    entry_points.add_entry_point(EntryPoint::new(
+        String::from("get_result"),
+        vec![Parameter::new("roller", CLType::Key)],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        String::from("init"),
+        vec![],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points
+}
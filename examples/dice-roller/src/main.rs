@@ -0,0 +1,110 @@
+//! Rolls a six sided die for whoever calls `roll_dice`, with the result delivered by the VRF
+//! coordinator.
+#![no_main]
+#![no_std]
+
+extern crate alloc;
+mod entry_points;
+
+pub mod store;
+
+use alloc::{format, string::String, vec::Vec};
+use casper_contract::contract_api::runtime;
+use casper_types::{
+    contracts::NamedKeys, runtime_args, ApiError, HashAddr, Key, RuntimeArgs, U256,
+};
+use common::constants::*;
+use common::helpers;
+use common::owner;
+use common::upgrade;
+
+const REQUEST_CONFIRMATIONS: u64 = 3;
+const CALLBACK_GAS_LIMIT: u128 = 100_000_000;
+const NUM_WORDS: u64 = 1;
+/// Result held while the roll waits for its random word, outside the 1 to 6 a die shows.
+pub const ROLL_IN_PROGRESS: u64 = 42;
+
+#[repr(u16)]
+pub enum Error {
+    RollInProgress = 20000,
+}
+
+impl From<Error> for ApiError {
+    fn from(e: Error) -> Self {
+        ApiError::User(e as u16)
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn init() {
+    if helpers::get_key::<Key>("contract_hash").is_some() {
+        runtime::revert(common::error::Error::ContractAlreadyInitialized);
+    }
+
+    let caller = helpers::get_immediate_caller_key();
+    let contract_hash: Key = runtime::get_named_arg("contract_hash");
+    let contract_package_hash: Key = runtime::get_named_arg("contract_package_hash");
+    let vrf_coordinator: Key = runtime::get_named_arg("vrf_coordinator");
+    let key_hash: HashAddr = runtime::get_named_arg("key_hash");
+    let sub_id: u64 = runtime::get_named_arg("sub_id");
+
+    helpers::set_key("contract_hash", contract_hash);
+    helpers::set_key("contract_package_hash", contract_package_hash);
+    owner::init(caller);
+    vrf_consumer::init(vrf_coordinator);
+    store::initialize(key_hash, sub_id);
+}
+
+/**
+ * @notice Requests a die roll for the caller, whose result is readable through get_result
+ * once the coordinator fulfills the request
+ */
+#[no_mangle]
+pub extern "C" fn roll_dice() {
+    let roller = helpers::get_immediate_caller_key();
+    if store::read_result(&roller) == ROLL_IN_PROGRESS {
+        runtime::revert(Error::RollInProgress);
+    }
+    let request_id = vrf_consumer::request_random_words(
+        store::read_key_hash(),
+        store::read_sub_id(),
+        REQUEST_CONFIRMATIONS,
+        CALLBACK_GAS_LIMIT.into(),
+        NUM_WORDS,
+    );
+    store::save_roller(&request_id, &roller);
+    store::save_result(&roller, &ROLL_IN_PROGRESS);
+}
+
+fn fulfill_random_words(request_id: U256, random_words: Vec<U256>) {
+    let roller = store::read_roller(&request_id);
+    let value = (random_words[0] % 6).as_u64() + 1;
+    store::save_result(&roller, &value);
+}
+
+vrf_consumer::raw_fulfill_random_words!(fulfill_random_words);
+
+#[no_mangle]
+fn call() {
+    let contract_name: String = runtime::get_named_arg("contract_name");
+    if !runtime::has_key(&format!("{}_package_hash", contract_name)) {
+        let (contract_hash, contract_package_hash) =
+            upgrade::install_contract(contract_name, entry_points::default(), NamedKeys::new());
+        let vrf_coordinator: Key = runtime::get_named_arg("vrf_coordinator");
+        let key_hash: HashAddr = runtime::get_named_arg("key_hash");
+        let sub_id: u64 = runtime::get_named_arg("sub_id");
+        runtime::call_contract::<()>(
+            contract_hash,
+            INIT_ENTRY_POINT_NAME,
+            runtime_args! {
+                "contract_hash" => Key::from(contract_hash),
+                "contract_package_hash" => Key::from(contract_package_hash),
+                "vrf_coordinator" => vrf_coordinator,
+                "key_hash" => key_hash,
+                "sub_id" => sub_id
+            },
+        );
+    } else {
+        upgrade::upgrade_contract(contract_name, entry_points::default(), NamedKeys::new());
+    }
+}
@@ -0,0 +1,44 @@
+use alloc::{string::String, vec, vec::Vec};
+use casper_types::{
+    CLType, CLTyped, EntryPoint, EntryPointAccess, EntryPointType, EntryPoints, Parameter,
+};
+
+use common::owner;
+
+fn add_entry_points(entry_points: &mut EntryPoints, list: &Vec<EntryPoint>) {
+    for e in list {
+        entry_points.add_entry_point(e.clone());
+    }
+}
+
+pub(crate) fn default() -> EntryPoints {
+    let mut entry_points = EntryPoints::new();
+    add_entry_points(&mut entry_points, &owner::entry_points());
+    add_entry_points(&mut entry_points, &vrf_consumer::entry_points());
+
+    entry_points.add_entry_point(EntryPoint::new(
+        String::from("reveal"),
+        vec![Parameter::new("token_id", CLType::U64)],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    // view functions
+    entry_points.add_entry_point(EntryPoint::new(
+        String::from("get_traits"),
+        vec![Parameter::new("token_id", CLType::U64)],
+        Vec::<u64>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        String::from("init"),
+        vec![],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points
+}
@@ -0,0 +1,123 @@
+//! Reveals the traits of NFTs minted unrevealed, drawing one random word per trait from the
+//! VRF coordinator.
+#![no_main]
+#![no_std]
+
+extern crate alloc;
+mod entry_points;
+
+pub mod store;
+
+use alloc::{format, string::String, vec::Vec};
+use casper_contract::contract_api::runtime;
+use casper_types::{
+    contracts::NamedKeys, runtime_args, ApiError, HashAddr, Key, RuntimeArgs, U256,
+};
+use common::constants::*;
+use common::helpers;
+use common::owner;
+use common::upgrade;
+
+const REQUEST_CONFIRMATIONS: u64 = 3;
+const CALLBACK_GAS_LIMIT: u128 = 100_000_000;
+/// Number of variants of each trait: background, body and accessory.
+pub const TRAIT_VARIANTS: [u64; 3] = [8, 12, 20];
+
+#[repr(u16)]
+pub enum Error {
+    AlreadyRevealed = 20000,
+    RevealInProgress = 20001,
+    UnknownRequest = 20002,
+}
+
+impl From<Error> for ApiError {
+    fn from(e: Error) -> Self {
+        ApiError::User(e as u16)
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn init() {
+    if helpers::get_key::<Key>("contract_hash").is_some() {
+        runtime::revert(common::error::Error::ContractAlreadyInitialized);
+    }
+
+    let caller = helpers::get_immediate_caller_key();
+    let contract_hash: Key = runtime::get_named_arg("contract_hash");
+    let contract_package_hash: Key = runtime::get_named_arg("contract_package_hash");
+    let vrf_coordinator: Key = runtime::get_named_arg("vrf_coordinator");
+    let key_hash: HashAddr = runtime::get_named_arg("key_hash");
+    let sub_id: u64 = runtime::get_named_arg("sub_id");
+
+    helpers::set_key("contract_hash", contract_hash);
+    helpers::set_key("contract_package_hash", contract_package_hash);
+    owner::init(caller);
+    vrf_consumer::init(vrf_coordinator);
+    store::initialize(key_hash, sub_id);
+}
+
+/**
+ * @notice Requests the random words the traits of `token_id` are drawn from, readable through
+ * get_traits once the coordinator fulfills the request
+ */
+#[no_mangle]
+pub extern "C" fn reveal() {
+    owner::only_owner();
+    let token_id: u64 = runtime::get_named_arg("token_id");
+    if !store::read_traits(&token_id).is_empty() {
+        runtime::revert(Error::AlreadyRevealed);
+    }
+    if store::read_revealing(&token_id) {
+        runtime::revert(Error::RevealInProgress);
+    }
+    let request_id = vrf_consumer::request_random_words(
+        store::read_key_hash(),
+        store::read_sub_id(),
+        REQUEST_CONFIRMATIONS,
+        CALLBACK_GAS_LIMIT.into(),
+        TRAIT_VARIANTS.len() as u64,
+    );
+    store::save_token(&request_id, &Some(token_id));
+    store::save_revealing(&token_id, &true);
+}
+
+fn fulfill_random_words(request_id: U256, random_words: Vec<U256>) {
+    let token_id = match store::read_token(&request_id) {
+        Some(token_id) => token_id,
+        None => runtime::revert(Error::UnknownRequest),
+    };
+    let traits: Vec<u64> = random_words
+        .iter()
+        .zip(TRAIT_VARIANTS.iter())
+        .map(|(word, variants)| (*word % U256::from(*variants)).as_u64())
+        .collect();
+    store::save_traits(&token_id, &traits);
+    store::save_revealing(&token_id, &false);
+}
+
+vrf_consumer::raw_fulfill_random_words!(fulfill_random_words);
+
+#[no_mangle]
+fn call() {
+    let contract_name: String = runtime::get_named_arg("contract_name");
+    if !runtime::has_key(&format!("{}_package_hash", contract_name)) {
+        let (contract_hash, contract_package_hash) =
+            upgrade::install_contract(contract_name, entry_points::default(), NamedKeys::new());
+        let vrf_coordinator: Key = runtime::get_named_arg("vrf_coordinator");
+        let key_hash: HashAddr = runtime::get_named_arg("key_hash");
+        let sub_id: u64 = runtime::get_named_arg("sub_id");
+        runtime::call_contract::<()>(
+            contract_hash,
+            INIT_ENTRY_POINT_NAME,
+            runtime_args! {
+                "contract_hash" => Key::from(contract_hash),
+                "contract_package_hash" => Key::from(contract_package_hash),
+                "vrf_coordinator" => vrf_coordinator,
+                "key_hash" => key_hash,
+                "sub_id" => sub_id
+            },
+        );
+    } else {
+        upgrade::upgrade_contract(contract_name, entry_points::default(), NamedKeys::new());
+    }
+}
@@ -0,0 +1,70 @@
+use alloc::vec::Vec;
+use casper_contract::contract_api::{runtime, storage};
+use casper_contract::unwrap_or_revert::UnwrapOrRevert;
+use casper_types::{CLValue, HashAddr, U256};
+use common::error::Error;
+use common::{get_set_dict, get_set_no_set, helpers};
+
+pub fn initialize(key_hash: HashAddr, sub_id: u64) {
+    save_key_hash(key_hash);
+    save_sub_id(sub_id);
+    storage::new_dictionary("tokens").unwrap_or_revert_with(Error::FailedToCreateDictionary);
+    storage::new_dictionary("revealing").unwrap_or_revert_with(Error::FailedToCreateDictionary);
+    storage::new_dictionary("traits").unwrap_or_revert_with(Error::FailedToCreateDictionary);
+}
+
+get_set_no_set!(
+    key_hash,
+    "key_hash",
+    HashAddr,
+    HashAddr::default(),
+    save_key_hash,
+    read_key_hash,
+    get_key_hash
+);
+
+get_set_no_set!(
+    sub_id,
+    "sub_id",
+    u64,
+    0,
+    save_sub_id,
+    read_sub_id,
+    get_sub_id
+);
+
+get_set_dict!(
+    "tokens",
+    "request_id",
+    U256,
+    Option<u64>,
+    None,
+    save_token,
+    read_token,
+    get_token,
+    set_token
+);
+
+get_set_dict!(
+    "revealing",
+    "token_id",
+    u64,
+    bool,
+    false,
+    save_revealing,
+    read_revealing,
+    get_revealing,
+    set_revealing
+);
+
+get_set_dict!(
+    "traits",
+    "token_id",
+    u64,
+    Vec<u64>,
+    Vec::new(),
+    save_traits,
+    read_traits,
+    get_traits,
+    set_traits
+);
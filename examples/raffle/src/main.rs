@@ -0,0 +1,153 @@
+//! Raffle drawing a single winner among its entrants with a random word from the VRF
+//! coordinator.
+#![no_main]
+#![no_std]
+
+extern crate alloc;
+mod entry_points;
+
+pub mod store;
+
+use alloc::{format, string::String, vec::Vec};
+use casper_contract::contract_api::runtime;
+use casper_types::{
+    contracts::NamedKeys, runtime_args, ApiError, HashAddr, Key, RuntimeArgs, U256,
+};
+use common::constants::*;
+use common::helpers;
+use common::owner;
+use common::upgrade;
+
+const REQUEST_CONFIRMATIONS: u64 = 3;
+const CALLBACK_GAS_LIMIT: u128 = 100_000_000;
+const NUM_WORDS: u64 = 1;
+
+#[repr(u16)]
+pub enum Error {
+    RaffleClosed = 20000,
+    AlreadyEntered = 20001,
+    NoEntrants = 20002,
+    UnknownRequest = 20003,
+}
+
+impl From<Error> for ApiError {
+    fn from(e: Error) -> Self {
+        ApiError::User(e as u16)
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn init() {
+    if helpers::get_key::<Key>("contract_hash").is_some() {
+        runtime::revert(common::error::Error::ContractAlreadyInitialized);
+    }
+
+    let caller = helpers::get_immediate_caller_key();
+    let contract_hash: Key = runtime::get_named_arg("contract_hash");
+    let contract_package_hash: Key = runtime::get_named_arg("contract_package_hash");
+    let vrf_coordinator: Key = runtime::get_named_arg("vrf_coordinator");
+    let key_hash: HashAddr = runtime::get_named_arg("key_hash");
+    let sub_id: u64 = runtime::get_named_arg("sub_id");
+
+    helpers::set_key("contract_hash", contract_hash);
+    helpers::set_key("contract_package_hash", contract_package_hash);
+    owner::init(caller);
+    vrf_consumer::init(vrf_coordinator);
+    store::initialize(key_hash, sub_id);
+}
+
+fn is_open() -> bool {
+    !store::read_drawing() && store::read_winner() == helpers::null_key()
+}
+
+/**
+ * @notice Enters the caller in the raffle, open until the owner picks the winner
+ */
+#[no_mangle]
+pub extern "C" fn enter() {
+    if !is_open() {
+        runtime::revert(Error::RaffleClosed);
+    }
+    let entrant = helpers::get_immediate_caller_key();
+    let mut entrants = store::read_entrants();
+    if entrants.contains(&entrant) {
+        runtime::revert(Error::AlreadyEntered);
+    }
+    entrants.push(entrant);
+    store::save_entrants(entrants);
+}
+
+/**
+ * @notice Closes the raffle and requests the random word the winner is drawn with
+ * @dev the winner is drawn in the coordinator callback, or with claim_winner if it was skipped
+ */
+#[no_mangle]
+pub extern "C" fn pick_winner() {
+    owner::only_owner();
+    if !is_open() {
+        runtime::revert(Error::RaffleClosed);
+    }
+    if store::read_entrants().is_empty() {
+        runtime::revert(Error::NoEntrants);
+    }
+    let request_id = vrf_consumer::request_random_words(
+        store::read_key_hash(),
+        store::read_sub_id(),
+        REQUEST_CONFIRMATIONS,
+        CALLBACK_GAS_LIMIT.into(),
+        NUM_WORDS,
+    );
+    store::save_request_id(request_id);
+    store::save_drawing(true);
+}
+
+fn fulfill_random_words(request_id: U256, random_words: Vec<U256>) {
+    if !store::read_drawing() || request_id != store::read_request_id() {
+        runtime::revert(Error::UnknownRequest);
+    }
+    let entrants = store::read_entrants();
+    let index = (random_words[0] % U256::from(entrants.len())).as_usize();
+    store::save_winner(entrants[index]);
+    store::save_drawing(false);
+}
+
+vrf_consumer::raw_fulfill_random_words!(fulfill_random_words);
+
+/**
+ * @notice Draws the winner with the words of a fulfillment that skipped the raffle callback
+ * @dev callable by anyone, the words are claimed from the coordinator so that a failed
+ * @dev callback does not leave the raffle drawing for good
+ */
+#[no_mangle]
+pub extern "C" fn claim_winner() {
+    if !store::read_drawing() {
+        runtime::revert(Error::UnknownRequest);
+    }
+    let request_id = store::read_request_id();
+    fulfill_random_words(request_id, vrf_consumer::claim_random_words(request_id));
+}
+
+#[no_mangle]
+fn call() {
+    let contract_name: String = runtime::get_named_arg("contract_name");
+    if !runtime::has_key(&format!("{}_package_hash", contract_name)) {
+        let (contract_hash, contract_package_hash) =
+            upgrade::install_contract(contract_name, entry_points::default(), NamedKeys::new());
+        let vrf_coordinator: Key = runtime::get_named_arg("vrf_coordinator");
+        let key_hash: HashAddr = runtime::get_named_arg("key_hash");
+        let sub_id: u64 = runtime::get_named_arg("sub_id");
+        runtime::call_contract::<()>(
+            contract_hash,
+            INIT_ENTRY_POINT_NAME,
+            runtime_args! {
+                "contract_hash" => Key::from(contract_hash),
+                "contract_package_hash" => Key::from(contract_package_hash),
+                "vrf_coordinator" => vrf_coordinator,
+                "key_hash" => key_hash,
+                "sub_id" => sub_id
+            },
+        );
+    } else {
+        upgrade::upgrade_contract(contract_name, entry_points::default(), NamedKeys::new());
+    }
+}
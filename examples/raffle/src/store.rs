@@ -0,0 +1,73 @@
+use alloc::vec::Vec;
+use casper_contract::contract_api::runtime;
+use casper_contract::unwrap_or_revert::UnwrapOrRevert;
+use casper_types::{CLValue, HashAddr, Key, U256};
+use common::{get_set_no_set, helpers};
+
+pub fn initialize(key_hash: HashAddr, sub_id: u64) {
+    save_key_hash(key_hash);
+    save_sub_id(sub_id);
+    save_entrants(Vec::new());
+    save_winner(helpers::null_key());
+    save_drawing(false);
+}
+
+get_set_no_set!(
+    key_hash,
+    "key_hash",
+    HashAddr,
+    HashAddr::default(),
+    save_key_hash,
+    read_key_hash,
+    get_key_hash
+);
+
+get_set_no_set!(
+    sub_id,
+    "sub_id",
+    u64,
+    0,
+    save_sub_id,
+    read_sub_id,
+    get_sub_id
+);
+
+get_set_no_set!(
+    entrants,
+    "entrants",
+    Vec<Key>,
+    Vec::new(),
+    save_entrants,
+    read_entrants,
+    get_entrants
+);
+
+get_set_no_set!(
+    winner,
+    "winner",
+    Key,
+    helpers::null_key(),
+    save_winner,
+    read_winner,
+    get_winner
+);
+
+get_set_no_set!(
+    drawing,
+    "drawing",
+    bool,
+    false,
+    save_drawing,
+    read_drawing,
+    get_drawing
+);
+
+get_set_no_set!(
+    request_id,
+    "request_id",
+    U256,
+    U256::zero(),
+    save_request_id,
+    read_request_id,
+    get_request_id
+);
@@ -0,0 +1,31 @@
+#![no_std]
+#![no_main]
+
+use casper_contract::{
+    contract_api::{account, runtime, system},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{runtime_args, Key, RuntimeArgs, U512};
+
+/// Moves `amount` motes of the calling account to a new purse and deposits them to
+/// subscription `sub_id` of the coordinator package `vrf_coordinator`.
+#[no_mangle]
+pub extern "C" fn call() {
+    let vrf_coordinator: Key = runtime::get_named_arg("vrf_coordinator");
+    let sub_id: u64 = runtime::get_named_arg("sub_id");
+    let amount: U512 = runtime::get_named_arg("amount");
+
+    let purse = system::create_purse();
+    system::transfer_from_purse_to_purse(account::get_main_purse(), purse, amount, None)
+        .unwrap_or_revert();
+    runtime::call_versioned_contract::<()>(
+        vrf_coordinator.into_hash().unwrap_or_revert().into(),
+        None,
+        "deposit_cspr",
+        runtime_args! {
+            "sub_id" => sub_id,
+            "purse" => purse,
+            "amount" => amount
+        },
+    );
+}
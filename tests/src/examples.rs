@@ -0,0 +1,638 @@
+use casper_types::{
+    bytesrepr::{Bytes, ToBytes},
+    runtime_args, HashAddr, Key, RuntimeArgs, U128, U256,
+};
+use common::{
+    data_types::{Proof, RequestCommitment},
+    events::RandomWordsRequested,
+};
+use vrf_prover::Prover;
+
+use crate::{
+    utils::{self, key_to_contract_package_hash},
+    vrf::{setup, VRFFixture},
+};
+
+const SK: [u8; 32] = [5u8; 32];
+// callback gas limit the examples request with
+const CALLBACK_GAS_LIMIT: u128 = 100_000_000;
+
+pub struct ExampleFixture {
+    pub vrf: VRFFixture,
+    pub prover: Prover,
+    pub sub_id: u64,
+    pub example: Key,
+}
+
+impl ExampleFixture {
+    /// Deploys `wasm` as a consumer of a funded subscription to a key registered for `prover`.
+    pub fn new(wasm: &str, contract_name: &str) -> Self {
+        let mut vrf = setup();
+        let prover = Prover::new(SK).unwrap();
+        let pk = prover.public_key();
+        vrf.register_proving_key(vrf.owner, Key::from(vrf.oracle), pk.clone());
+        let key_hash = vrf.hash_of_key(pk);
+        let sub_id = vrf.create_subscription(&[]);
+        vrf.deposit_token(vrf.sub_owner, sub_id, 1000000000000000000_u128.into());
+
+        vrf.test_env.deploy_contract(
+            Some(vrf.owner),
+            wasm,
+            runtime_args! {
+                "contract_name" => contract_name,
+                "vrf_coordinator" => vrf.vrf,
+                "key_hash" => key_hash,
+                "sub_id" => sub_id
+            },
+        );
+        let example = vrf.test_env.get_contract_package_hash(
+            vrf.owner,
+            &utils::get_contract_package_hash_key(contract_name.to_owned()),
+        );
+        vrf.add_consumer(vrf.sub_owner, sub_id, example);
+
+        Self {
+            vrf,
+            prover,
+            sub_id,
+            example,
+        }
+    }
+
+    pub fn call(&mut self, caller: Key, entry_point: &str, args: RuntimeArgs, success: bool) {
+        self.vrf.test_env.call_contract(
+            Some(caller.into_account().unwrap()),
+            key_to_contract_package_hash(self.example),
+            entry_point,
+            args,
+            success,
+        );
+    }
+
+    /// Calls `entry_point` of the example, then fulfills the request it made as the oracle
+    /// would and returns the words the example was called back with.
+    pub fn request_and_fulfill(
+        &mut self,
+        caller: Key,
+        entry_point: &str,
+        args: RuntimeArgs,
+    ) -> Vec<U256> {
+        let event_length = self.vrf.test_env.get_event_length(self.vrf.vrf);
+        self.call(caller, entry_point, args, true);
+        let (proof, rc, random_words) = self.fulfillment(event_length as usize);
+        self.vrf.fulfill_random_words(
+            self.vrf.oracle,
+            Bytes::from(proof.to_bytes().unwrap()),
+            Bytes::from(rc.to_bytes().unwrap()),
+        );
+        random_words
+    }
+
+    /// Proof and commitment the oracle would fulfill the request of event `event_index` with,
+    /// along with the words they deliver.
+    pub fn fulfillment(&mut self, event_index: usize) -> (Proof, RequestCommitment, Vec<U256>) {
+        let event: RandomWordsRequested = self
+            .vrf
+            .test_env
+            .get_event(self.vrf.vrf, event_index)
+            .unwrap();
+        assert_eq!(event.sender, self.example);
+
+        let block_hash: HashAddr = [1u8; 32];
+        let block = self
+            .vrf
+            .set_block_hash(event.block.timestamp, hex::encode(block_hash));
+        let proof = self.prover.generate_proof(event.pre_seed, block_hash);
+        let random_words =
+            vrf_prover::random_words(vrf_prover::random_value(&proof), event.num_words as u32);
+        let rc = RequestCommitment {
+            block,
+            request_confirmations: event.minimum_request_confirmations,
+            sub_id: self.sub_id,
+            callback_gas_limit: U128::from(CALLBACK_GAS_LIMIT),
+            num_words: event.num_words as u32,
+            sender: self.example,
+        };
+        (proof, rc, random_words)
+    }
+}
+
+#[cfg(test)]
+mod test_dice_roller {
+    use casper_types::{
+        bytesrepr::{Bytes, ToBytes},
+        runtime_args, Key, RuntimeArgs, U128, U256,
+    };
+    use common::{
+        constants::GAS_FOR_CALL_EXACT_CHECK,
+        data_types::RequestStatus,
+        events::{RandomWordsFulfillmentFailed, RandomWordsRequested},
+    };
+
+    use super::{ExampleFixture, CALLBACK_GAS_LIMIT};
+
+    const ROLL_IN_PROGRESS: u64 = 42;
+
+    fn get_result(fixture: &mut ExampleFixture, roller: Key) -> u64 {
+        fixture.vrf.test_env.call_view_function(
+            fixture.example,
+            "get_result",
+            runtime_args! {
+                "roller" => roller
+            },
+        )
+    }
+
+    #[test]
+    fn test_roll_dice() {
+        let mut fixture = ExampleFixture::new("dice-roller.wasm", "dice-roller");
+        let roller = Key::from(fixture.vrf.consumer);
+        let random_words = fixture.request_and_fulfill(roller, "roll_dice", runtime_args! {});
+        let result = get_result(&mut fixture, roller);
+        assert_eq!(result, (random_words[0] % U256::from(6)).as_u64() + 1);
+        assert!((1..=6).contains(&result));
+        let sub_id = fixture.sub_id;
+        assert_eq!(fixture.vrf.get_pending_requests(sub_id), 0);
+    }
+
+    #[test]
+    #[should_panic = "User(20000)"]
+    fn test_cannot_roll_while_in_progress() {
+        let mut fixture = ExampleFixture::new("dice-roller.wasm", "dice-roller");
+        let roller = Key::from(fixture.vrf.consumer);
+        fixture.call(roller, "roll_dice", runtime_args! {}, true);
+        assert_eq!(get_result(&mut fixture, roller), ROLL_IN_PROGRESS);
+        fixture.call(roller, "roll_dice", runtime_args! {}, true);
+    }
+
+    #[test]
+    fn test_fulfill_batch() {
+        let mut fixture = ExampleFixture::new("dice-roller.wasm", "dice-roller");
+        let rollers = [
+            Key::from(fixture.vrf.consumer),
+            Key::from(fixture.vrf.random),
+        ];
+        let mut fulfillments = vec![];
+        for roller in rollers {
+            let event_length = fixture.vrf.test_env.get_event_length(fixture.vrf.vrf);
+            fixture.call(roller, "roll_dice", runtime_args! {}, true);
+            fulfillments.push(fixture.fulfillment(event_length as usize));
+        }
+        let (proof, rc, random_words) = fulfillments[0].clone();
+        let (other_proof, mut other_rc, _) = fulfillments[1].clone();
+        other_rc.sub_id += 1;
+        let sub_balance = fixture.vrf.get_subscription(fixture.sub_id).balance;
+
+        let event_length = fixture.vrf.test_env.get_event_length(fixture.vrf.vrf);
+        let oracle = fixture.vrf.oracle;
+        fixture.vrf.fulfill_random_words_batch(
+            oracle,
+            vec![
+                (
+                    Bytes::from(proof.to_bytes().unwrap()),
+                    Bytes::from(rc.to_bytes().unwrap()),
+                    (false, None),
+                ),
+                (
+                    Bytes::from(other_proof.to_bytes().unwrap()),
+                    Bytes::from(other_rc.to_bytes().unwrap()),
+                    (false, None),
+                ),
+                (
+                    Bytes::from(vec![1u8]),
+                    Bytes::from(vec![2u8]),
+                    (false, None),
+                ),
+            ],
+        );
+
+        assert_eq!(
+            get_result(&mut fixture, rollers[0]),
+            (random_words[0] % U256::from(6)).as_u64() + 1
+        );
+        assert_eq!(get_result(&mut fixture, rollers[1]), ROLL_IN_PROGRESS);
+        assert!(fixture.vrf.get_subscription(fixture.sub_id).balance < sub_balance);
+
+        // the fulfilled item is followed by one failure event per skipped item
+        let failures: Vec<RandomWordsFulfillmentFailed> = (event_length + 1..event_length + 3)
+            .map(|i| {
+                fixture
+                    .vrf
+                    .test_env
+                    .get_event(fixture.vrf.vrf, i as usize)
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(failures[0].index, 1);
+        assert_eq!(failures[0].error, 10028);
+        assert_eq!(failures[1].index, 2);
+        assert_eq!(failures[1].request_id, U256::zero());
+        assert_eq!(failures[1].error, 10032);
+
+        // the skipped request is untouched and can still be fulfilled
+        let (other_proof, other_rc, other_words) = fulfillments[1].clone();
+        fixture.vrf.fulfill_random_words_batch(
+            oracle,
+            vec![(
+                Bytes::from(other_proof.to_bytes().unwrap()),
+                Bytes::from(other_rc.to_bytes().unwrap()),
+                (false, None),
+            )],
+        );
+        assert_eq!(
+            get_result(&mut fixture, rollers[1]),
+            (other_words[0] % U256::from(6)).as_u64() + 1
+        );
+    }
+
+    #[test]
+    fn test_fulfill_batch_with_item_options() {
+        let mut fixture = ExampleFixture::new("dice-roller.wasm", "dice-roller");
+        let rollers = [
+            Key::from(fixture.vrf.consumer),
+            Key::from(fixture.vrf.random),
+        ];
+        let mut fulfillments = vec![];
+        for roller in rollers {
+            let event_length = fixture.vrf.test_env.get_event_length(fixture.vrf.vrf);
+            fixture.call(roller, "roll_dice", runtime_args! {}, true);
+            let event: RandomWordsRequested = fixture
+                .vrf
+                .test_env
+                .get_event(fixture.vrf.vrf, event_length as usize)
+                .unwrap();
+            fulfillments.push((event.request_id, fixture.fulfillment(event_length as usize)));
+        }
+        let items = fulfillments
+            .iter()
+            .zip([(true, None), (false, Some(U128::from(1_000_000)))])
+            .map(|((_, (proof, rc, _)), options)| {
+                (
+                    Bytes::from(proof.to_bytes().unwrap()),
+                    Bytes::from(rc.to_bytes().unwrap()),
+                    options,
+                )
+            })
+            .collect();
+        let oracle = fixture.vrf.oracle;
+        fixture.vrf.fulfill_random_words_batch(oracle, items);
+
+        // the first request is settled without its callback, the second one calls back
+        let (skipped_id, (_, _, skipped_words)) = fulfillments[0].clone();
+        assert_eq!(get_result(&mut fixture, rollers[0]), ROLL_IN_PROGRESS);
+        assert_eq!(fixture.vrf.get_random_words(skipped_id), skipped_words);
+        assert_eq!(
+            fixture.vrf.get_request_commitment(skipped_id).status,
+            RequestStatus::Failed
+        );
+        let (_, (_, _, random_words)) = fulfillments[1].clone();
+        assert_eq!(
+            get_result(&mut fixture, rollers[1]),
+            (random_words[0] % U256::from(6)).as_u64() + 1
+        );
+    }
+
+    #[test]
+    fn test_fulfill_skipping_callback() {
+        let mut fixture = ExampleFixture::new("dice-roller.wasm", "dice-roller");
+        let roller = Key::from(fixture.vrf.consumer);
+        let event_length = fixture.vrf.test_env.get_event_length(fixture.vrf.vrf);
+        fixture.call(roller, "roll_dice", runtime_args! {}, true);
+        let (proof, rc, random_words) = fixture.fulfillment(event_length as usize);
+        let event: RandomWordsRequested = fixture
+            .vrf
+            .test_env
+            .get_event(fixture.vrf.vrf, event_length as usize)
+            .unwrap();
+
+        let oracle = fixture.vrf.oracle;
+        fixture.vrf.test_env.call_contract(
+            Some(oracle),
+            fixture.vrf.vrf.into_hash().unwrap().into(),
+            "fulfill_random_words",
+            runtime_args! {
+                "proof" => Bytes::from(proof.to_bytes().unwrap()),
+                "rc" => Bytes::from(rc.to_bytes().unwrap()),
+                "skip_callback" => true
+            },
+            true,
+        );
+
+        // the request is settled and paid, without callback gas, while the consumer is left
+        // untouched
+        assert_eq!(get_result(&mut fixture, roller), ROLL_IN_PROGRESS);
+        let stored: Vec<U256> = fixture.vrf.test_env.call_view_function(
+            fixture.vrf.vrf,
+            "get_random_words",
+            runtime_args! {
+                "request_id" => event.request_id
+            },
+        );
+        assert_eq!(stored, random_words);
+        let request = fixture.vrf.get_request_commitment(event.request_id);
+        assert_eq!(request.status, RequestStatus::Failed);
+        let withdrawable: U128 = fixture.vrf.test_env.call_view_function(
+            fixture.vrf.vrf,
+            "get_withdrawable_token",
+            runtime_args! {
+                "oracle" => Key::from(oracle)
+            },
+        );
+        let config = fixture.vrf.config;
+        let expected = U256::from(1_000_000_000_000_000_000_u128)
+            * U256::from(config.gas_after_payment_calculation.as_u128())
+            / config.fallback_motes_per_unit_link;
+        assert_eq!(withdrawable, U128::from(expected.as_u128()));
+    }
+
+    /// Rolls and fulfills the roll reporting `callback_gas_used`, returns the oracle's payment.
+    fn roll_with_measured_gas(fixture: &mut ExampleFixture, callback_gas_used: u128) -> U128 {
+        let roller = Key::from(fixture.vrf.consumer);
+        let event_length = fixture.vrf.test_env.get_event_length(fixture.vrf.vrf);
+        fixture.call(roller, "roll_dice", runtime_args! {}, true);
+        let (proof, rc, random_words) = fixture.fulfillment(event_length as usize);
+
+        let oracle = fixture.vrf.oracle;
+        fixture.vrf.test_env.call_contract(
+            Some(oracle),
+            fixture.vrf.vrf.into_hash().unwrap().into(),
+            "fulfill_random_words",
+            runtime_args! {
+                "proof" => Bytes::from(proof.to_bytes().unwrap()),
+                "rc" => Bytes::from(rc.to_bytes().unwrap()),
+                "callback_gas_used" => U128::from(callback_gas_used)
+            },
+            true,
+        );
+        assert_eq!(
+            get_result(fixture, roller),
+            (random_words[0] % U256::from(6)).as_u64() + 1
+        );
+        fixture.vrf.test_env.call_view_function(
+            fixture.vrf.vrf,
+            "get_withdrawable_token",
+            runtime_args! {
+                "oracle" => Key::from(oracle)
+            },
+        )
+    }
+
+    #[test]
+    fn test_bills_measured_callback_gas() {
+        let mut fixture = ExampleFixture::new("dice-roller.wasm", "dice-roller");
+        let payment = roll_with_measured_gas(&mut fixture, 1_000_000);
+
+        let config = fixture.vrf.config;
+        let gas =
+            config.gas_after_payment_calculation.as_u128() + 1_000_000 + GAS_FOR_CALL_EXACT_CHECK;
+        let expected = U256::from(1_000_000_000_000_000_000_u128) * U256::from(gas)
+            / config.fallback_motes_per_unit_link;
+        assert_eq!(payment, U128::from(expected.as_u128()));
+    }
+
+    #[test]
+    fn test_reported_gas_is_billed_up_to_the_limit() {
+        let mut fixture = ExampleFixture::new("dice-roller.wasm", "dice-roller");
+        // the report is billed as is, the limit caps it with the call overhead added
+        let payment = roll_with_measured_gas(&mut fixture, CALLBACK_GAS_LIMIT);
+
+        let config = fixture.vrf.config;
+        let gas = config.gas_after_payment_calculation.as_u128() + CALLBACK_GAS_LIMIT;
+        let expected = U256::from(1_000_000_000_000_000_000_u128) * U256::from(gas)
+            / config.fallback_motes_per_unit_link;
+        assert_eq!(payment, U128::from(expected.as_u128()));
+    }
+
+    #[test]
+    #[should_panic = "User(10026)"]
+    fn test_callback_measured_over_limit_should_fail() {
+        let mut fixture = ExampleFixture::new("dice-roller.wasm", "dice-roller");
+        roll_with_measured_gas(&mut fixture, CALLBACK_GAS_LIMIT + 1);
+    }
+
+    #[test]
+    #[should_panic = "User(10048)"]
+    fn test_only_coordinator_can_fulfill() {
+        let mut fixture = ExampleFixture::new("dice-roller.wasm", "dice-roller");
+        let roller = Key::from(fixture.vrf.consumer);
+        fixture.call(roller, "roll_dice", runtime_args! {}, true);
+        fixture.call(
+            roller,
+            "raw_fulfill_random_words",
+            runtime_args! {
+                "request_id" => U256::one(),
+                "random_words" => vec![U256::one()]
+            },
+            true,
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_raffle {
+    use casper_types::{
+        bytesrepr::{Bytes, ToBytes},
+        runtime_args, Key, RuntimeArgs, U256,
+    };
+
+    use super::ExampleFixture;
+
+    fn enter_all(fixture: &mut ExampleFixture) -> Vec<Key> {
+        let entrants = vec![
+            Key::from(fixture.vrf.consumer),
+            Key::from(fixture.vrf.random),
+            Key::from(fixture.vrf.sub_owner),
+        ];
+        for entrant in &entrants {
+            fixture.call(*entrant, "enter", runtime_args! {}, true);
+        }
+        entrants
+    }
+
+    #[test]
+    fn test_pick_winner() {
+        let mut fixture = ExampleFixture::new("raffle.wasm", "raffle");
+        let entrants = enter_all(&mut fixture);
+        let owner = Key::from(fixture.vrf.owner);
+        let random_words = fixture.request_and_fulfill(owner, "pick_winner", runtime_args! {});
+
+        let winner: Key = fixture.vrf.test_env.call_view_function(
+            fixture.example,
+            "get_winner",
+            runtime_args! {},
+        );
+        let index = (random_words[0] % U256::from(entrants.len())).as_usize();
+        assert_eq!(winner, entrants[index]);
+    }
+
+    #[test]
+    fn test_claims_winner_after_skipped_callback() {
+        let mut fixture = ExampleFixture::new("raffle.wasm", "raffle");
+        let entrants = enter_all(&mut fixture);
+        let owner = Key::from(fixture.vrf.owner);
+        let event_length = fixture.vrf.test_env.get_event_length(fixture.vrf.vrf);
+        fixture.call(owner, "pick_winner", runtime_args! {}, true);
+        let (proof, rc, random_words) = fixture.fulfillment(event_length as usize);
+        fixture.vrf.fulfill_random_words_with(
+            fixture.vrf.oracle,
+            Bytes::from(proof.to_bytes().unwrap()),
+            Bytes::from(rc.to_bytes().unwrap()),
+            true,
+            None,
+        );
+
+        let random = Key::from(fixture.vrf.random);
+        fixture.call(random, "claim_winner", runtime_args! {}, true);
+        let winner: Key = fixture.vrf.test_env.call_view_function(
+            fixture.example,
+            "get_winner",
+            runtime_args! {},
+        );
+        let index = (random_words[0] % U256::from(entrants.len())).as_usize();
+        assert_eq!(winner, entrants[index]);
+        // the raffle is settled, there is nothing left to claim
+        fixture.call(random, "claim_winner", runtime_args! {}, false);
+    }
+
+    #[test]
+    #[should_panic = "User(20001)"]
+    fn test_cannot_enter_twice() {
+        let mut fixture = ExampleFixture::new("raffle.wasm", "raffle");
+        let entrant = Key::from(fixture.vrf.consumer);
+        fixture.call(entrant, "enter", runtime_args! {}, true);
+        fixture.call(entrant, "enter", runtime_args! {}, true);
+    }
+
+    #[test]
+    #[should_panic = "User(20000)"]
+    fn test_cannot_enter_while_drawing() {
+        let mut fixture = ExampleFixture::new("raffle.wasm", "raffle");
+        enter_all(&mut fixture);
+        let owner = Key::from(fixture.vrf.owner);
+        fixture.call(owner, "pick_winner", runtime_args! {}, true);
+        fixture.call(owner, "enter", runtime_args! {}, true);
+    }
+
+    #[test]
+    #[should_panic = "User(20002)"]
+    fn test_no_entrants() {
+        let mut fixture = ExampleFixture::new("raffle.wasm", "raffle");
+        let owner = Key::from(fixture.vrf.owner);
+        fixture.call(owner, "pick_winner", runtime_args! {}, true);
+    }
+}
+
+#[cfg(test)]
+mod test_nft_reveal {
+    use casper_types::{runtime_args, Key, RuntimeArgs, U256};
+
+    use super::ExampleFixture;
+
+    const TRAIT_VARIANTS: [u64; 3] = [8, 12, 20];
+
+    fn get_traits(fixture: &mut ExampleFixture, token_id: u64) -> Vec<u64> {
+        fixture.vrf.test_env.call_view_function(
+            fixture.example,
+            "get_traits",
+            runtime_args! {
+                "token_id" => token_id
+            },
+        )
+    }
+
+    #[test]
+    fn test_reveal() {
+        let mut fixture = ExampleFixture::new("nft-reveal.wasm", "nft-reveal");
+        let owner = Key::from(fixture.vrf.owner);
+        assert!(get_traits(&mut fixture, 7).is_empty());
+        let random_words =
+            fixture.request_and_fulfill(owner, "reveal", runtime_args! { "token_id" => 7u64 });
+
+        let expected: Vec<u64> = random_words
+            .iter()
+            .zip(TRAIT_VARIANTS.iter())
+            .map(|(word, variants)| (*word % U256::from(*variants)).as_u64())
+            .collect();
+        assert_eq!(get_traits(&mut fixture, 7), expected);
+        assert!(get_traits(&mut fixture, 8).is_empty());
+    }
+
+    #[test]
+    #[should_panic = "User(20000)"]
+    fn test_cannot_reveal_twice() {
+        let mut fixture = ExampleFixture::new("nft-reveal.wasm", "nft-reveal");
+        let owner = Key::from(fixture.vrf.owner);
+        fixture.request_and_fulfill(owner, "reveal", runtime_args! { "token_id" => 7u64 });
+        fixture.call(owner, "reveal", runtime_args! { "token_id" => 7u64 }, true);
+    }
+
+    #[test]
+    #[should_panic = "User(20001)"]
+    fn test_cannot_reveal_while_in_progress() {
+        let mut fixture = ExampleFixture::new("nft-reveal.wasm", "nft-reveal");
+        let owner = Key::from(fixture.vrf.owner);
+        fixture.call(owner, "reveal", runtime_args! { "token_id" => 7u64 }, true);
+        fixture.call(owner, "reveal", runtime_args! { "token_id" => 7u64 }, true);
+    }
+
+    #[test]
+    fn test_only_owner_can_reveal() {
+        let mut fixture = ExampleFixture::new("nft-reveal.wasm", "nft-reveal");
+        let random = Key::from(fixture.vrf.random);
+        let event_length = fixture.vrf.test_env.get_event_length(fixture.vrf.vrf);
+        fixture.call(
+            random,
+            "reveal",
+            runtime_args! { "token_id" => 7u64 },
+            false,
+        );
+        assert_eq!(
+            fixture.vrf.test_env.get_event_length(fixture.vrf.vrf),
+            event_length
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_coin_flip {
+    use casper_types::{runtime_args, Key, RuntimeArgs};
+
+    use super::ExampleFixture;
+
+    const HEADS: u8 = 1;
+    const TAILS: u8 = 2;
+    const FLIP_IN_PROGRESS: u8 = 3;
+
+    fn get_result(fixture: &mut ExampleFixture, player: Key) -> u8 {
+        fixture.vrf.test_env.call_view_function(
+            fixture.example,
+            "get_result",
+            runtime_args! {
+                "player" => player
+            },
+        )
+    }
+
+    #[test]
+    fn test_flip() {
+        let mut fixture = ExampleFixture::new("coin-flip.wasm", "coin-flip");
+        let player = Key::from(fixture.vrf.consumer);
+        let random_words = fixture.request_and_fulfill(player, "flip", runtime_args! {});
+        let expected = if (random_words[0] % 2).is_zero() {
+            HEADS
+        } else {
+            TAILS
+        };
+        assert_eq!(get_result(&mut fixture, player), expected);
+    }
+
+    #[test]
+    #[should_panic = "User(20000)"]
+    fn test_cannot_flip_while_in_progress() {
+        let mut fixture = ExampleFixture::new("coin-flip.wasm", "coin-flip");
+        let player = Key::from(fixture.vrf.consumer);
+        fixture.call(player, "flip", runtime_args! {}, true);
+        assert_eq!(get_result(&mut fixture, player), FLIP_IN_PROGRESS);
+        fixture.call(player, "flip", runtime_args! {}, true);
+    }
+}
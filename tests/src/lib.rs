@@ -4,3 +4,13 @@ extern crate alloc;
 mod vrf;
 #[cfg(test)]
 mod utils;
+#[cfg(test)]
+mod oracle;
+#[cfg(test)]
+mod examples;
+#[cfg(test)]
+mod wrapper;
+#[cfg(test)]
+mod rfc9381;
+#[cfg(test)]
+mod payment;
@@ -0,0 +1,334 @@
+use std::collections::BTreeMap;
+
+use casper_types::{HashAddr, Key, U128, U256};
+use common::data_types::{BlockRef, Proof, RequestCommitment};
+use vrf_oracle::{
+    chain::{ChainClient, DeployStatus},
+    error::Error,
+    event_source::{MemorySource, RequestedEvent},
+    oracle::{is_callback_failure, Oracle, RetryConfig},
+};
+use vrf_prover::Prover;
+
+const SK: [u8; 32] = [7u8; 32];
+const REQUEST_HEIGHT: u64 = 100;
+const REQUEST_TIMESTAMP: u64 = 1_700_000_000;
+const REQUEST_BLOCK: BlockRef = BlockRef {
+    era_id: 1,
+    height: REQUEST_HEIGHT,
+    timestamp: REQUEST_TIMESTAMP,
+};
+
+#[derive(Default)]
+pub struct MockChain {
+    pub height: u64,
+    pub blocks: BTreeMap<u64, (BlockRef, HashAddr)>,
+    pub callback_gas: Option<U128>,
+    pub submitted: Vec<(Proof, RequestCommitment, bool, Option<U128>)>,
+    pub statuses: BTreeMap<String, DeployStatus>,
+    pub reject_deploys: bool,
+}
+
+impl MockChain {
+    /// Stores the block of the requests in the block-hash-store.
+    pub fn store_request_block(&mut self) {
+        self.blocks
+            .insert(REQUEST_TIMESTAMP, (REQUEST_BLOCK, [1u8; 32]));
+    }
+}
+
+impl ChainClient for MockChain {
+    fn latest_block_height(&mut self) -> Result<u64, Error> {
+        Ok(self.height)
+    }
+
+    fn block(&mut self, timestamp: u64) -> Result<Option<(BlockRef, HashAddr)>, Error> {
+        Ok(self.blocks.get(&timestamp).copied())
+    }
+
+    fn estimate_callback_gas(
+        &mut self,
+        _proof: &Proof,
+        _rc: &RequestCommitment,
+    ) -> Result<Option<U128>, Error> {
+        Ok(self.callback_gas)
+    }
+
+    fn fulfill_random_words(
+        &mut self,
+        proof: &Proof,
+        rc: &RequestCommitment,
+        skip_callback: bool,
+        callback_gas_used: Option<U128>,
+    ) -> Result<String, Error> {
+        if self.reject_deploys {
+            return Err(Error::Client("deploy rejected".into()));
+        }
+        self.submitted
+            .push((proof.clone(), rc.clone(), skip_callback, callback_gas_used));
+        Ok(format!("deploy-{}", self.submitted.len()))
+    }
+
+    fn deploy_status(&mut self, deploy_hash: &str) -> Result<DeployStatus, Error> {
+        Ok(self
+            .statuses
+            .get(deploy_hash)
+            .cloned()
+            .unwrap_or(DeployStatus::Pending))
+    }
+}
+
+fn event(key_hash: HashAddr, request_id: u64) -> RequestedEvent {
+    RequestedEvent {
+        block_height: REQUEST_HEIGHT,
+        key_hash,
+        request_id: request_id.into(),
+        pre_seed: U256::from(request_id) * 1000,
+        sub_id: 1,
+        minimum_request_confirmations: 3,
+        callback_gas_limit: 1000.into(),
+        num_words: 2,
+        sender: Key::Hash([3u8; 32]),
+        timestamp: REQUEST_TIMESTAMP,
+    }
+}
+
+fn oracle(retry: RetryConfig) -> (Oracle<MemorySource, MockChain>, HashAddr) {
+    let prover = Prover::new(SK).unwrap();
+    let key_hash = vrf_prover::blake2b(prover.public_key());
+    let oracle = Oracle::new(
+        MemorySource::new(),
+        MockChain::default(),
+        prover,
+        retry,
+        None,
+    )
+    .unwrap();
+    (oracle, key_hash)
+}
+
+#[test]
+fn test_waits_for_confirmations_and_block_hash() {
+    let (mut oracle, key_hash) = oracle(RetryConfig::default());
+    oracle.source_mut().push(event(key_hash, 1));
+
+    oracle.chain_mut().height = REQUEST_HEIGHT + 2;
+    oracle.chain_mut().store_request_block();
+    oracle.poll().unwrap();
+    assert!(oracle.chain_mut().submitted.is_empty());
+
+    oracle.chain_mut().height = REQUEST_HEIGHT + 3;
+    oracle.chain_mut().blocks.clear();
+    oracle.poll().unwrap();
+    assert!(oracle.chain_mut().submitted.is_empty());
+
+    oracle.chain_mut().store_request_block();
+    oracle.poll().unwrap();
+    let (proof, rc, skip_callback, callback_gas_used) = oracle.chain_mut().submitted[0].clone();
+    assert_eq!(proof.seed, U256::from(1000));
+    assert_eq!(proof.pk, Prover::new(SK).unwrap().public_key());
+    assert_eq!(rc.block, REQUEST_BLOCK);
+    assert_eq!(rc.request_confirmations, 3);
+    assert_eq!(rc.num_words, 2);
+    assert_eq!(rc.sender, Key::Hash([3u8; 32]));
+    assert!(!skip_callback);
+    assert_eq!(callback_gas_used, None);
+}
+
+#[test]
+fn test_executed_request_is_done() {
+    let (mut oracle, key_hash) = oracle(RetryConfig::default());
+    oracle.source_mut().push(event(key_hash, 1));
+    oracle.chain_mut().height = REQUEST_HEIGHT + 3;
+    oracle.chain_mut().store_request_block();
+    oracle.poll().unwrap();
+    oracle.poll().unwrap();
+    assert_eq!(oracle.chain_mut().submitted.len(), 1);
+
+    oracle
+        .chain_mut()
+        .statuses
+        .insert("deploy-1".into(), DeployStatus::Executed);
+    oracle.poll().unwrap();
+    assert!(oracle.progress().pending.is_empty());
+    assert!(oracle.progress().failed.is_empty());
+    assert_eq!(oracle.progress().next_event, 1);
+}
+
+#[test]
+fn test_ignores_other_proving_keys() {
+    let (mut oracle, _) = oracle(RetryConfig::default());
+    oracle.source_mut().push(event([9u8; 32], 1));
+    oracle.chain_mut().height = REQUEST_HEIGHT + 3;
+    oracle.chain_mut().store_request_block();
+    oracle.poll().unwrap();
+    assert!(oracle.chain_mut().submitted.is_empty());
+    assert!(oracle.progress().pending.is_empty());
+    assert_eq!(oracle.progress().next_event, 1);
+}
+
+#[test]
+fn test_retries_failed_deploys_then_gives_up() {
+    let (mut oracle, key_hash) = oracle(RetryConfig {
+        max_attempts: 2,
+        retry_delay_blocks: 5,
+    });
+    oracle.source_mut().push(event(key_hash, 1));
+    oracle.chain_mut().height = REQUEST_HEIGHT + 3;
+    oracle.chain_mut().store_request_block();
+    oracle.poll().unwrap();
+    oracle.chain_mut().statuses.insert(
+        "deploy-1".into(),
+        DeployStatus::Failed("User error: 10026".into()),
+    );
+    oracle.poll().unwrap();
+    assert_eq!(oracle.progress().pending["1"].attempts, 1);
+
+    // not retried before the delay has passed
+    oracle.poll().unwrap();
+    assert_eq!(oracle.chain_mut().submitted.len(), 1);
+
+    oracle.chain_mut().height += 5;
+    oracle.chain_mut().reject_deploys = true;
+    oracle.poll().unwrap();
+    assert!(oracle.progress().pending.is_empty());
+    let failed = &oracle.progress().failed[0];
+    assert_eq!(failed.attempts, 2);
+    assert_eq!(
+        failed.last_error.as_deref(),
+        Some("client error: deploy rejected")
+    );
+}
+
+#[test]
+fn test_resumes_from_saved_progress() {
+    let path =
+        std::env::temp_dir().join(format!("vrf-oracle-progress-{}.json", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let prover = || Prover::new(SK).unwrap();
+    let key_hash = vrf_prover::blake2b(prover().public_key());
+
+    let mut source = MemorySource::new();
+    source.push(event(key_hash, 1));
+    let chain = MockChain {
+        height: REQUEST_HEIGHT + 3,
+        ..Default::default()
+    };
+    let mut oracle = Oracle::new(
+        source,
+        chain,
+        prover(),
+        RetryConfig::default(),
+        Some(path.clone()),
+    )
+    .unwrap();
+    oracle.poll().unwrap();
+
+    let mut source = MemorySource::new();
+    source.push(event(key_hash, 1));
+    source.push(event(key_hash, 2));
+    let mut chain = MockChain {
+        height: REQUEST_HEIGHT + 3,
+        ..Default::default()
+    };
+    chain.store_request_block();
+    let mut oracle = Oracle::new(
+        source,
+        chain,
+        prover(),
+        RetryConfig::default(),
+        Some(path.clone()),
+    )
+    .unwrap();
+    oracle.poll().unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    // request 1 was restored from the saved progress rather than read again
+    assert_eq!(oracle.progress().next_event, 2);
+    assert_eq!(oracle.chain_mut().submitted.len(), 2);
+    assert_eq!(oracle.progress().pending.len(), 2);
+}
+
+#[test]
+fn test_skips_callback_after_consumer_failure() {
+    let (mut oracle, key_hash) = oracle(RetryConfig {
+        max_attempts: 3,
+        retry_delay_blocks: 0,
+    });
+    oracle.source_mut().push(event(key_hash, 1));
+    oracle.chain_mut().height = REQUEST_HEIGHT + 3;
+    oracle.chain_mut().store_request_block();
+    oracle.poll().unwrap();
+
+    // a failure of the coordinator's own checks is retried as is
+    oracle.chain_mut().statuses.insert(
+        "deploy-1".into(),
+        DeployStatus::Failed("User error: 10011".into()),
+    );
+    oracle.poll().unwrap();
+    oracle.poll().unwrap();
+    assert!(!oracle.chain_mut().submitted[1].2);
+
+    oracle.chain_mut().statuses.insert(
+        "deploy-2".into(),
+        DeployStatus::Failed("User error: 20000".into()),
+    );
+    oracle.poll().unwrap();
+    oracle.poll().unwrap();
+    assert!(oracle.chain_mut().submitted[2].2);
+    assert!(oracle.progress().pending["1"].skip_callback);
+}
+
+#[test]
+fn test_retries_out_of_gas_with_callback() {
+    let (mut oracle, key_hash) = oracle(RetryConfig {
+        max_attempts: 3,
+        retry_delay_blocks: 0,
+    });
+    oracle.source_mut().push(event(key_hash, 1));
+    oracle.chain_mut().height = REQUEST_HEIGHT + 3;
+    oracle.chain_mut().store_request_block();
+    oracle.poll().unwrap();
+
+    oracle.chain_mut().statuses.insert(
+        "deploy-1".into(),
+        DeployStatus::Failed("Out of gas error".into()),
+    );
+    oracle.poll().unwrap();
+    oracle.poll().unwrap();
+    assert_eq!(oracle.chain_mut().submitted.len(), 2);
+    assert!(!oracle.chain_mut().submitted[1].2);
+    assert!(!oracle.progress().pending["1"].skip_callback);
+}
+
+#[test]
+fn test_reports_measured_callback_gas() {
+    let (mut oracle, key_hash) = oracle(RetryConfig::default());
+    oracle.source_mut().push(event(key_hash, 1));
+    oracle.chain_mut().height = REQUEST_HEIGHT + 3;
+    oracle.chain_mut().store_request_block();
+    oracle.chain_mut().callback_gas = Some(1000.into());
+    oracle.poll().unwrap();
+    let submitted = &oracle.chain_mut().submitted;
+    assert!(!submitted[0].2);
+    assert_eq!(submitted[0].3, Some(1000.into()));
+
+    // a callback over its gas limit is skipped right away
+    oracle.chain_mut().callback_gas = Some(1001.into());
+    oracle.source_mut().push(event(key_hash, 2));
+    oracle.poll().unwrap();
+    let submitted = &oracle.chain_mut().submitted;
+    assert_eq!(submitted.len(), 2);
+    assert!(submitted[1].2);
+    assert_eq!(submitted[1].3, Some(1001.into()));
+    assert!(oracle.progress().pending["2"].skip_callback);
+}
+
+#[test]
+fn test_is_callback_failure() {
+    assert!(is_callback_failure("User error: 20000"));
+    assert!(!is_callback_failure("Out of gas error"));
+    assert!(!is_callback_failure("Interpreter error: trap: Code(Unreachable)"));
+    assert!(!is_callback_failure("User error: 10027"));
+    assert!(!is_callback_failure("User error: 10044"));
+}
@@ -0,0 +1,79 @@
+#[cfg(test)]
+mod test_calculate_payment_amount {
+    use casper_types::{U128, U256};
+    use common::{
+        error::Error,
+        payment::{calculate_payment_amount, FLAT_FEE_UNIT, PAYMENT_TOKEN_UNIT},
+    };
+
+    const GAS_AFTER_PAYMENT_CALCULATION: u128 = 100_000_000_000;
+    // one mote per token unit, gas is billed 1:1 in token units
+    const PAR: u64 = PAYMENT_TOKEN_UNIT;
+
+    #[test]
+    fn test_bills_callback_and_accounting_gas() {
+        let payment = calculate_payment_amount(
+            1000.into(),
+            GAS_AFTER_PAYMENT_CALCULATION.into(),
+            0,
+            PAR.into(),
+        );
+        assert_eq!(
+            payment.ok(),
+            Some(U128::from(GAS_AFTER_PAYMENT_CALCULATION + 1000))
+        );
+    }
+
+    #[test]
+    fn test_adds_flat_fee_of_tier() {
+        let payment = calculate_payment_amount(
+            1000.into(),
+            GAS_AFTER_PAYMENT_CALCULATION.into(),
+            500,
+            PAR.into(),
+        );
+        assert_eq!(
+            payment.ok(),
+            Some(U128::from(
+                GAS_AFTER_PAYMENT_CALCULATION + 1000 + 500 * FLAT_FEE_UNIT as u128
+            ))
+        );
+    }
+
+    #[test]
+    fn test_converts_gas_at_token_price() {
+        // a token worth ten times the gas it pays for
+        let payment = calculate_payment_amount(
+            1000.into(),
+            GAS_AFTER_PAYMENT_CALCULATION.into(),
+            500,
+            U256::from(PAR) * 10,
+        );
+        assert_eq!(
+            payment.ok(),
+            Some(U128::from(
+                (GAS_AFTER_PAYMENT_CALCULATION + 1000) / 10 + 500 * FLAT_FEE_UNIT as u128
+            ))
+        );
+    }
+
+    #[test]
+    fn test_payment_at_u128_max() {
+        let payment = calculate_payment_amount(U128::MAX - 1, 1.into(), 0, PAR.into());
+        assert_eq!(payment.ok(), Some(U128::MAX));
+    }
+
+    #[test]
+    fn test_payment_too_large_should_fail() {
+        let payment = calculate_payment_amount(U128::MAX, 1.into(), 0, PAR.into());
+        assert!(matches!(payment, Err(Error::PaymentTooLarge)));
+        let payment = calculate_payment_amount(U128::MAX - 1000, 0.into(), 1, PAR.into());
+        assert!(matches!(payment, Err(Error::PaymentTooLarge)));
+    }
+
+    #[test]
+    fn test_zero_price_should_fail() {
+        let payment = calculate_payment_amount(1000.into(), 0.into(), 0, U256::zero());
+        assert!(matches!(payment, Err(Error::InvalidLinkWeiPrice)));
+    }
+}
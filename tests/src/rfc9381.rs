@@ -0,0 +1,166 @@
+//! Known answer tests of the RFC 9381 verifiers of the coordinator.
+
+use casper_types::{bytesrepr::Bytes, Key, U256};
+use common::data_types::Proof;
+
+fn proof(pk: &[u8], gamma: &[u8], c: U256, s: U256) -> Proof {
+    Proof {
+        pk: Bytes::from(pk.to_vec()),
+        gamma: Bytes::from(gamma.to_vec()),
+        c,
+        s,
+        seed: U256::zero(),
+        u_witness: Key::Hash([0u8; 32]),
+        c_gamma_witness: Bytes::new(),
+        s_hash_witness: Bytes::new(),
+        z_inv: U256::zero(),
+    }
+}
+
+#[cfg(test)]
+mod test_ecvrf_edwards25519 {
+    use casper_types::{ApiError, U256};
+    use common::{error::Error, helpers::u256_from_hex};
+
+    use ecvrf::edwards25519;
+
+    use super::proof;
+
+    /// Proof of the 80 byte string `pi` of an RFC 9381 test vector, gamma || c || s with c
+    /// and s little endian.
+    fn verify_vector(pk: &str, alpha: &str, pi: &str) -> Result<[u8; 64], ApiError> {
+        let pk = hex::decode(pk).unwrap();
+        let pi = hex::decode(pi).unwrap();
+        let proof = proof(
+            &pk,
+            &pi[..32],
+            U256::from_little_endian(&pi[32..48]),
+            U256::from_little_endian(&pi[48..]),
+        );
+        edwards25519::verify(&proof, &hex::decode(alpha).unwrap()).map_err(ApiError::from)
+    }
+
+    // RFC 9381, appendix B.3, examples 16 to 18
+    const VECTORS: [(&str, &str, &str, &str); 3] = [
+        (
+            "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+            "",
+            "8657106690b5526245a92b003bb079ccd1a92130477671f6fc01ad16f26f723f26f8a57ccaed74ee1b190bed1f479d9727d2d0f9b005a6e456a35d4fb0daab1268a1b0db10836d9826a528ca76567805",
+            "90cf1df3b703cce59e2a35b925d411164068269d7b2d29f3301c03dd757876ff66b71dda49d2de59d03450451af026798e8f81cd2e333de5cdf4f3e140fdd8ae",
+        ),
+        (
+            "3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c",
+            "72",
+            "f3141cd382dc42909d19ec5110469e4feae18300e94f304590abdced48aed5933bf0864a62558b3ed7f2fea45c92a465301b3bbf5e3e54ddf2d935be3b67926da3ef39226bbc355bdc9850112c8f4b02",
+            "eb4440665d3891d668e7e0fcaf587f1b4bd7fbfe99d0eb2211ccec90496310eb5e33821bc613efb94db5e5b54c70a848a0bef4553a41befc57663b56373a5031",
+        ),
+        (
+            "fc51cd8e6218a1a38da47ed00230f0580816ed13ba3303ac5deb911548908025",
+            "af82",
+            "9bc0f79119cc5604bf02d23b4caede71393cedfbb191434dd016d30177ccbf8096bb474e53895c362d8628ee9f9ea3c0e52c7a5c691b6c18c9979866568add7a2d41b00b05081ed0f58ee5e31b3a970e",
+            "645427e5d00c62a23fb703732fa5d892940935942101e456ecca7bb217c61c452118fec1219202a0edcf038bb6373241578be7217ba85a2687f7a0310b2df19f",
+        ),
+    ];
+
+    #[test]
+    fn test_rfc9381_vectors_verify() {
+        for (pk, alpha, pi, beta) in VECTORS {
+            let output = verify_vector(pk, alpha, pi).unwrap();
+            assert_eq!(hex::encode(output), beta);
+        }
+    }
+
+    #[test]
+    fn test_random_value_of_seed() {
+        // the key of example 16 proving the 32 byte encoding of seed 0
+        let pi = hex::decode("d6e0255ca9860d8c94e552379bc3e820f984f5ada3236db07959905cfbffe765a1031cdf187ad5df6ecbe31cfe7f387a3f9394fa6611705b601f00c79a16fbe7076e7f1c22a9bde5b627b1fad2a4060a").unwrap();
+        let proof = proof(
+            &hex::decode(VECTORS[0].0).unwrap(),
+            &pi[..32],
+            U256::from_little_endian(&pi[32..48]),
+            U256::from_little_endian(&pi[48..]),
+        );
+        let random_value = edwards25519::random_value_from_vrf_proof(&proof, U256::zero())
+            .map_err(ApiError::from)
+            .unwrap();
+        // first half of beta
+        assert_eq!(
+            random_value,
+            u256_from_hex("f8196ed09063fa3c61b8c9230b3414c77dd15f4133732c898986db8a84811847")
+        );
+    }
+
+    #[test]
+    fn test_vector_for_other_alpha_should_fail() {
+        let (pk, _, pi, _) = VECTORS[1];
+        assert_eq!(
+            verify_vector(pk, "73", pi),
+            Err(ApiError::from(Error::InvalidProof))
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_ecvrf_secp256k1 {
+    use casper_types::{ApiError, U256};
+    use common::{error::Error, helpers::u256_from_hex};
+
+    use ecvrf::secp256k1;
+
+    use super::proof;
+
+    // regression vectors: RFC 9381 has no secp256k1 suite and so no published vectors, these
+    // were computed with a separate affine-coordinate model of the suite's steps, from secret
+    // key c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721 with the nonce
+    // derived as in RFC 6979
+    const PK: &str = "032c8c31fc9f990c6b55e3865a184a4ce50e09481f2eaeb3e60ec1cea13a6ae645";
+    // alpha "sample"
+    const ALPHA: &str = "73616d706c65";
+    const GAMMA: &str = "0338ec99b5d0f94ebcc2c704c04af3de8b4289df8798e5fb9f920d7f5d77ac03d7";
+    const C: &str = "718b9677d1c9348649ac2ec4f7ecbe51";
+    const S: &str = "9b30dd10c4eb5efc21dd5944709f2f3b7e97a25f6f095334593502d05103bc5b";
+    const BETA: &str = "d466c22e14dc3b7fd169668dd3ee9ac6351429a24aebc5e8af61a0f0de89b65a";
+    // alpha the 32 byte encoding of seed 0, as the coordinator verifies requests
+    const SEED_GAMMA: &str = "02ad529f609a862f14b13d2d353a3f01fc7e3d9d417270a78528b0b7a6a939197a";
+    const SEED_C: &str = "23ddf285ef81d7bd9c0476bdeb439f6f";
+    const SEED_S: &str = "21a276d24edf12797ba899148c590be16f4e4218519905614028feb8f341773c";
+    const SEED_BETA: &str = "32bff49da2df447baf07c64225243b18cec9de23b3bf860e9361706b97639a36";
+
+    fn verify(gamma: &str, c: U256, s: &str, alpha: &str) -> Result<[u8; 32], ApiError> {
+        let proof = proof(
+            &hex::decode(PK).unwrap(),
+            &hex::decode(gamma).unwrap(),
+            c,
+            u256_from_hex(s),
+        );
+        secp256k1::verify(&proof, &hex::decode(alpha).unwrap()).map_err(ApiError::from)
+    }
+
+    #[test]
+    fn test_known_answer_verifies() {
+        let beta = verify(GAMMA, u256_from_hex(C), S, ALPHA).unwrap();
+        assert_eq!(hex::encode(beta), BETA);
+    }
+
+    #[test]
+    fn test_random_value_of_seed() {
+        let proof = proof(
+            &hex::decode(PK).unwrap(),
+            &hex::decode(SEED_GAMMA).unwrap(),
+            u256_from_hex(SEED_C),
+            u256_from_hex(SEED_S),
+        );
+        let random_value = secp256k1::random_value_from_vrf_proof(&proof, U256::zero())
+            .map_err(ApiError::from)
+            .unwrap();
+        assert_eq!(random_value, u256_from_hex(SEED_BETA));
+    }
+
+    #[test]
+    fn test_tampered_c_should_fail() {
+        assert_eq!(
+            verify(GAMMA, u256_from_hex(C) + 1, S, ALPHA),
+            Err(ApiError::from(Error::InvalidProof))
+        );
+    }
+}
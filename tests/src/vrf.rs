@@ -1,16 +1,58 @@
 use casper_types::{
     account::AccountHash,
     bytesrepr::{Bytes, ToBytes},
-    runtime_args, HashAddr, Key, RuntimeArgs, SECP256K1_TAG, U128, U256,
+    runtime_args, HashAddr, Key, RuntimeArgs, SECP256K1_TAG, U128, U256, U512,
 };
 use common::{
-    data_types::{Config, FeeConfig, SubscriptionView},
+    constants::MAX_REQUEST_CONFIRMATIONS,
+    data_types::{
+        BlockRef, Config, FeeConfig, Proof, RequestCommitment, RequestRecord, SubscriptionLimits,
+        SubscriptionView,
+    },
+    events::RandomWordsRequested,
     helpers::null_key,
 };
 use test_env::env::TestEnv;
 
 use crate::utils::{self, key_to_contract_package_hash};
 
+// height set_block_hash stores request blocks at
+pub const REQUEST_BLOCK_HEIGHT: u64 = 1;
+// hash request_and_fulfill stores for request blocks
+pub const REQUEST_BLOCK_HASH: HashAddr = [1u8; 32];
+
+/// Request made and fulfilled by `VRFFixture::request_and_fulfill`.
+#[derive(Clone, Copy)]
+pub struct TestRequest {
+    pub key_hash: HashAddr,
+    pub sub_id: u64,
+    /// Consumer contract forwarding the request, the consumer account requests when `None`.
+    pub requester: Option<Key>,
+    pub pull: bool,
+    pub callback_gas_limit: U128,
+    pub num_words: u64,
+    /// Height the request block is stored at in the block hash store.
+    pub block_height: u64,
+    pub skip_callback: bool,
+    pub callback_gas_used: Option<U128>,
+}
+
+impl TestRequest {
+    pub fn new(key_hash: HashAddr, sub_id: u64) -> Self {
+        TestRequest {
+            key_hash,
+            sub_id,
+            requester: None,
+            pull: false,
+            callback_gas_limit: 1000.into(),
+            num_words: 1,
+            block_height: REQUEST_BLOCK_HEIGHT,
+            skip_callback: false,
+            callback_gas_used: None,
+        }
+    }
+}
+
 pub struct VRFFixture {
     pub test_env: TestEnv,
     pub vrf: Key,
@@ -35,7 +77,9 @@ impl VRFFixture {
         max_gas_limit: U128,
         staleness_seconds: u64,
         gas_after_payment_calculation: U128,
+        fallback_motes_per_unit_link: U256,
         fee_config_bytes: Bytes,
+        request_timeout_seconds: u64,
         success: bool,
     ) {
         self.test_env.call_contract(
@@ -47,7 +91,9 @@ impl VRFFixture {
                 "max_gas_limit" => max_gas_limit,
                 "staleness_seconds" => staleness_seconds,
                 "gas_after_payment_calculation" => gas_after_payment_calculation,
-                "fee_config_bytes" => fee_config_bytes
+                "fallback_motes_per_unit_link" => fallback_motes_per_unit_link,
+                "fee_config_bytes" => fee_config_bytes,
+                "request_timeout_seconds" => request_timeout_seconds
             },
             success,
         );
@@ -58,26 +104,56 @@ impl VRFFixture {
             .call_view_function(self.vrf, "get_config", runtime_args! {})
     }
 
-    pub fn get_fee_config(&mut self) -> FeeConfig {
-        self.test_env
-            .call_view_function(self.vrf, "get_fee_config", runtime_args! {})
+    pub fn get_fee_tier(&mut self, req_count: u64) -> (u32, u64) {
+        self.test_env.call_view_function(
+            self.vrf,
+            "get_fee_tier",
+            runtime_args! {
+                "req_count" => req_count
+            },
+        )
     }
 
-    pub fn get_block_hash_store(&mut self) -> Key {
-        self.test_env
-            .call_view_function(self.vrf, "get_block_hash_store", runtime_args! {})
+    /// Keeps the fixture config and replaces the fee tiers.
+    pub fn set_fee_config(&mut self, fee_config: FeeConfig) {
+        let config = self.config;
+        self.set_config(
+            Some(self.owner),
+            config.minimum_request_confirmations,
+            config.max_gas_limit,
+            config.staleness_seconds,
+            config.gas_after_payment_calculation,
+            config.fallback_motes_per_unit_link,
+            Bytes::from(fee_config.to_bytes().unwrap()),
+            config.request_timeout_seconds,
+            true,
+        );
     }
 
     pub fn create_subscription(&mut self, consumers: &[Key]) -> u64 {
+        self.create_subscription_with_token(consumers, None)
+    }
+
+    /// Creates a subscription of the sub owner paying in `payment_token`, the coordinator's
+    /// payment token if `None`.
+    pub fn create_subscription_with_token(
+        &mut self,
+        consumers: &[Key],
+        payment_token: Option<Key>,
+    ) -> u64 {
         let mut sub_id: u64 =
             self.test_env
                 .call_view_function(self.vrf, "get_current_sub_id", runtime_args! {});
         sub_id += 1;
+        let mut args = runtime_args! {};
+        if let Some(payment_token) = payment_token {
+            args.insert("payment_token", payment_token).unwrap();
+        }
         self.test_env.call_contract(
             Some(self.sub_owner),
             key_to_contract_package_hash(self.vrf),
             "create_subscription",
-            runtime_args! {},
+            args,
             true,
         );
 
@@ -134,6 +210,19 @@ impl VRFFixture {
             true,
         );
     }
+
+    pub fn owner_cancel_subscription(&mut self, caller: AccountHash, sub_id: u64) {
+        self.test_env.call_contract(
+            Some(caller),
+            self.vrf.into_hash().unwrap().into(),
+            "owner_cancel_subscription",
+            runtime_args! {
+                "sub_id" => sub_id
+            },
+            true,
+        );
+    }
+
     pub fn get_subscription(&mut self, sub_id: u64) -> SubscriptionView {
         self.test_env.call_view_function(
             self.vrf,
@@ -144,6 +233,28 @@ impl VRFFixture {
         )
     }
 
+    pub fn set_subscription_limits(
+        &mut self,
+        caller: AccountHash,
+        sub_id: u64,
+        limits: SubscriptionLimits,
+        success: bool,
+    ) {
+        self.test_env.call_contract(
+            Some(caller),
+            self.vrf.into_hash().unwrap().into(),
+            "set_subscription_limits",
+            runtime_args! {
+                "sub_id" => sub_id,
+                "window_seconds" => limits.window_seconds,
+                "max_spend_per_window" => limits.max_spend_per_window,
+                "max_requests_per_consumer" => limits.max_requests_per_consumer,
+                "max_num_words" => limits.max_num_words
+            },
+            success,
+        );
+    }
+
     pub fn deposit_token(&mut self, caller: AccountHash, sub_id: u64, amount: U128) {
         self.test_env
             .approve(self.payment_token, caller, self.vrf, U256::MAX);
@@ -159,6 +270,129 @@ impl VRFFixture {
         );
     }
 
+    /// Calls the coordinator's token-receiver hook as the payment token would after a transfer.
+    pub fn on_token_transfer(&mut self, caller: AccountHash, amount: U256, data: Bytes) {
+        self.test_env.call_contract(
+            Some(caller),
+            self.vrf.into_hash().unwrap().into(),
+            "on_token_transfer",
+            runtime_args! {
+                "sender" => Key::from(caller),
+                "amount" => amount,
+                "data" => data
+            },
+            true,
+        );
+    }
+
+    /// Deposits `amount` motes of `caller` to subscription `sub_id` through the deposit session.
+    pub fn deposit_cspr(&mut self, caller: AccountHash, sub_id: u64, amount: U512) {
+        self.test_env.deploy_contract(
+            Some(caller),
+            "deposit-cspr-session.wasm",
+            runtime_args! {
+                "vrf_coordinator" => self.vrf,
+                "sub_id" => sub_id,
+                "amount" => amount
+            },
+        );
+    }
+
+    pub fn get_total_native_balance(&mut self) -> U512 {
+        self.test_env
+            .call_view_function(self.vrf, "get_total_native_balance", runtime_args! {})
+    }
+
+    pub fn get_withdrawable_cspr(&mut self, oracle: Key) -> U512 {
+        self.test_env.call_view_function(
+            self.vrf,
+            "get_withdrawable_cspr",
+            runtime_args! {
+                "oracle" => oracle
+            },
+        )
+    }
+
+    pub fn oracle_withdraw(&mut self, caller: AccountHash, recipient: Key, amount: U128) {
+        self.test_env.call_contract(
+            Some(caller),
+            self.vrf.into_hash().unwrap().into(),
+            "oracle_withdraw",
+            runtime_args! {
+                "recipient" => recipient,
+                "amount" => amount
+            },
+            true,
+        );
+    }
+
+    pub fn oracle_withdraw_cspr(&mut self, caller: AccountHash, recipient: Key, amount: U512) {
+        self.test_env.call_contract(
+            Some(caller),
+            self.vrf.into_hash().unwrap().into(),
+            "oracle_withdraw_cspr",
+            runtime_args! {
+                "recipient" => recipient,
+                "amount" => amount
+            },
+            true,
+        );
+    }
+
+    pub fn set_payment_token_config(
+        &mut self,
+        caller: AccountHash,
+        payment_token: Key,
+        fallback_motes_per_unit_link: U256,
+        fee_config: FeeConfig,
+    ) {
+        self.test_env.call_contract(
+            Some(caller),
+            key_to_contract_package_hash(self.vrf),
+            "set_payment_token_config",
+            runtime_args! {
+                "payment_token" => payment_token,
+                "price_feed" => null_key(),
+                "fallback_motes_per_unit_link" => fallback_motes_per_unit_link,
+                "fee_config_bytes" => Bytes::from(fee_config.to_bytes().unwrap())
+            },
+            true,
+        );
+    }
+
+    pub fn remove_payment_token(&mut self, caller: AccountHash, payment_token: Key) {
+        self.test_env.call_contract(
+            Some(caller),
+            key_to_contract_package_hash(self.vrf),
+            "remove_payment_token",
+            runtime_args! {
+                "payment_token" => payment_token
+            },
+            true,
+        );
+    }
+
+    pub fn get_total_balance(&mut self, payment_token: Key) -> U128 {
+        self.test_env.call_view_function(
+            self.vrf,
+            "get_total_balance",
+            runtime_args! {
+                "payment_token" => payment_token
+            },
+        )
+    }
+
+    pub fn get_withdrawable_token(&mut self, oracle: Key, payment_token: Key) -> U128 {
+        self.test_env.call_view_function(
+            self.vrf,
+            "get_withdrawable_token",
+            runtime_args! {
+                "oracle" => oracle,
+                "payment_token" => payment_token
+            },
+        )
+    }
+
     pub fn balance_of(&mut self, token: Key, addr: Key) -> U128 {
         let b: U256 = self.test_env.call_view_function(
             token,
@@ -219,6 +453,26 @@ impl VRFFixture {
         );
     }
 
+    pub fn register_proving_key_with_type(
+        &mut self,
+        caller: AccountHash,
+        oracle: Key,
+        test_key: Bytes,
+        key_type: u8,
+    ) {
+        self.test_env.call_contract(
+            Some(caller),
+            self.vrf.into_hash().unwrap().into(),
+            "register_proving_key",
+            runtime_args! {
+                "oracle" => oracle,
+                "public_proving_key" => test_key,
+                "key_type" => key_type,
+            },
+            true,
+        );
+    }
+
     pub fn deregister_proving_key(&mut self, caller: AccountHash, test_key: Bytes) {
         self.test_env.call_contract(
             Some(caller),
@@ -244,70 +498,346 @@ impl VRFFixture {
         );
     }
 
-    pub fn hash_of_key(&mut self, test_key: Bytes) -> HashAddr {
+    pub fn fulfill_random_words_with(
+        &mut self,
+        caller: AccountHash,
+        proof: Bytes,
+        rc: Bytes,
+        skip_callback: bool,
+        callback_gas_used: Option<U128>,
+    ) {
+        let mut args = runtime_args! {
+            "proof" => proof,
+            "rc" => rc,
+            "skip_callback" => skip_callback,
+        };
+        if let Some(callback_gas_used) = callback_gas_used {
+            args.insert("callback_gas_used", callback_gas_used).unwrap();
+        }
+        self.test_env.call_contract(
+            Some(caller),
+            self.vrf.into_hash().unwrap().into(),
+            "fulfill_random_words",
+            args,
+            true,
+        );
+    }
+
+    /// Makes `request`, stores `REQUEST_BLOCK_HASH` for its block and fulfills it from the
+    /// oracle with the proof `prove` returns for the request event. Returns the event and the
+    /// words the proof yields.
+    pub fn request_and_fulfill(
+        &mut self,
+        request: &TestRequest,
+        prove: impl FnOnce(&RandomWordsRequested) -> Proof,
+    ) -> (RandomWordsRequested, Vec<U256>) {
+        let event_length = self.test_env.get_event_length(self.vrf);
+        let (package, sender) = match request.requester {
+            Some(consumer) => (key_to_contract_package_hash(consumer), consumer),
+            None => (
+                key_to_contract_package_hash(self.vrf),
+                Key::from(self.consumer),
+            ),
+        };
+        let mut args = runtime_args! {
+            "key_hash" => request.key_hash,
+            "sub_id" => request.sub_id,
+            "request_confirmations" => 1u64,
+            "callback_gas_limit" => request.callback_gas_limit,
+            "num_words" => request.num_words
+        };
+        if request.pull {
+            args.insert("pull", true).unwrap();
+        }
+        self.test_env.call_contract(
+            Some(self.consumer),
+            package,
+            "request_random_words",
+            args,
+            true,
+        );
+        let event: RandomWordsRequested = self
+            .test_env
+            .get_event(self.vrf, event_length as usize)
+            .unwrap();
+
+        let block = BlockRef {
+            era_id: 0,
+            height: request.block_height,
+            timestamp: event.block.timestamp,
+        };
+        self.set_block_ref(block, hex::encode(REQUEST_BLOCK_HASH));
+        let proof = prove(&event);
+        let random_words =
+            vrf_prover::random_words(vrf_prover::random_value(&proof), request.num_words as u32);
+        let rc = RequestCommitment {
+            block,
+            request_confirmations: event.minimum_request_confirmations,
+            sub_id: request.sub_id,
+            callback_gas_limit: request.callback_gas_limit,
+            num_words: request.num_words as u32,
+            sender,
+        };
+        self.fulfill_random_words_with(
+            self.oracle,
+            proof.to_bytes().unwrap().into(),
+            rc.to_bytes().unwrap().into(),
+            request.skip_callback,
+            request.callback_gas_used,
+        );
+        (event, random_words)
+    }
+
+    /// Fulfills `requests`, (proof, rc, (skip_callback, callback_gas_used)) items.
+    pub fn fulfill_random_words_batch(
+        &mut self,
+        caller: AccountHash,
+        requests: Vec<(Bytes, Bytes, (bool, Option<U128>))>,
+    ) {
+        self.test_env.call_contract(
+            Some(caller),
+            self.vrf.into_hash().unwrap().into(),
+            "fulfill_random_words_batch",
+            runtime_args! {
+                "requests" => requests,
+            },
+            true,
+        );
+    }
+
+    pub fn get_request_commitment(&mut self, request_id: U256) -> RequestRecord {
         self.test_env.call_view_function(
             self.vrf,
-            "hash_of_key",
+            "get_request_commitment",
             runtime_args! {
-                "public_proving_key" => test_key.clone()
+                "request_id" => request_id
             },
         )
     }
-}
 
-pub fn setup() -> VRFFixture {
-    let owner = test_env::env::generate_random_account(SECP256K1_TAG);
-    let sub_owner = test_env::env::generate_random_account(SECP256K1_TAG);
-    let sub_owner_address = Key::from(sub_owner);
-    let consumer = test_env::env::generate_random_account(SECP256K1_TAG);
-    let random = test_env::env::generate_random_account(SECP256K1_TAG);
-    let random_address = Key::from(random);
-    let oracle = test_env::env::generate_random_account(SECP256K1_TAG);
-    let mut test_env = TestEnv::new(&[owner, sub_owner, consumer, random, oracle], 0);
-
-    test_env.deploy_contract(
-        Some(owner),
-        "cep18.wasm",
-        runtime_args! {
-            "name" => "T0",
-            "symbol" => "T0",
-            "decimals" => 18u8,
-            "total_supply" =>
-            U256::from(10_000_000_000_000_000_000_000_000_000_000_u128),
-            "minter_list" => vec![Key::from(owner)],
-            "admin_list" => vec![Key::from(owner)],
-            "enable_mint_burn" => 1u8,
-            "contract_name" => "T0",
-        },
-    );
-
-    let payment_token = test_env.get_contract_package_hash(
-        owner,
-        &utils::get_contract_package_hash_key_cep18("T0".to_owned()),
-    );
+    pub fn get_pending_requests(&mut self, sub_id: u64) -> u64 {
+        self.test_env.call_view_function(
+            self.vrf,
+            "get_pending_requests",
+            runtime_args! {
+                "sub_id" => sub_id
+            },
+        )
+    }
 
-    test_env.deploy_contract(
-        Some(owner),
-        "block-hash-store.wasm",
-        runtime_args! {
-            "contract_name" => "block-hash-store"
-        },
-    );
-    let block_hash_store = test_env.get_contract_package_hash(
-        owner,
-        &utils::get_contract_package_hash_key("block-hash-store".to_owned()),
-    );
+    /// Moves the coordinator's clock `seconds` ahead.
+    pub fn roll_timestamp(&mut self, seconds: u64) {
+        self.test_env.call_contract(
+            Some(self.owner),
+            self.vrf.into_hash().unwrap().into(),
+            "roll_timestamp",
+            runtime_args! {
+                "roll_timestamp" => seconds
+            },
+            true,
+        );
+    }
 
-    let price_feed = null_key();
+    pub fn cancel_request(&mut self, caller: AccountHash, request_id: U256, rc: Bytes) {
+        self.test_env.call_contract(
+            Some(caller),
+            self.vrf.into_hash().unwrap().into(),
+            "cancel_request",
+            runtime_args! {
+                "request_id" => request_id,
+                "rc" => rc
+            },
+            true,
+        );
+    }
 
-    test_env.deploy_contract(
+    pub fn get_random_words(&mut self, request_id: U256) -> Vec<U256> {
+        self.test_env.call_view_function(
+            self.vrf,
+            "get_random_words",
+            runtime_args! {
+                "request_id" => request_id
+            },
+        )
+    }
+
+    pub fn claim_random_words(&mut self, caller: AccountHash, request_id: U256, success: bool) {
+        self.test_env.call_contract(
+            Some(caller),
+            self.vrf.into_hash().unwrap().into(),
+            "claim_random_words",
+            runtime_args! {
+                "request_id" => request_id,
+            },
+            success,
+        );
+    }
+
+    /// Stores `hash` for the block at `timestamp`, as block `REQUEST_BLOCK_HEIGHT` of era 0.
+    /// Stores `hash` for the block at `timestamp` at `REQUEST_BLOCK_HEIGHT`, returns the block.
+    pub fn set_block_hash(&mut self, timestamp: u64, hash: String) -> BlockRef {
+        let block = BlockRef {
+            era_id: 0,
+            height: REQUEST_BLOCK_HEIGHT,
+            timestamp,
+        };
+        self.set_block_ref(block, hash);
+        block
+    }
+
+    pub fn set_block_ref(&mut self, block: BlockRef, hash: String) {
+        self.test_env.call_contract(
+            Some(self.owner),
+            key_to_contract_package_hash(self.block_hash_store),
+            "set_block_hash",
+            runtime_args! {
+                "era_id" => block.era_id,
+                "height" => block.height,
+                "timestamp" => block.timestamp,
+                "hash" => hash,
+            },
+            true,
+        );
+    }
+
+    pub fn get_block_ref(&mut self, timestamp: u64) -> BlockRef {
+        self.test_env.call_view_function(
+            self.block_hash_store,
+            "get_block_ref",
+            runtime_args! {
+                "timestamp" => timestamp
+            },
+        )
+    }
+
+    pub fn get_latest_block_ref(&mut self) -> BlockRef {
+        self.test_env.call_view_function(
+            self.block_hash_store,
+            "get_latest_block_ref",
+            runtime_args! {},
+        )
+    }
+
+    /// Deploys a consumer contract that forwards requests to the coordinator and keeps the
+    /// words it is called back with.
+    pub fn deploy_test_consumer(&mut self) -> Key {
+        self.test_env.deploy_contract(
+            Some(self.owner),
+            "test-consumer.wasm",
+            runtime_args! {
+                "vrf_coordinator" => self.vrf
+            },
+        );
+        self.test_env.get_contract_package_hash(
+            self.owner,
+            &utils::get_contract_package_hash_key("test_consumer".to_owned()),
+        )
+    }
+
+    /// Deploys a price feed answering what `set_feed_answer` sets and makes it the feed of
+    /// the coordinator's payment token.
+    pub fn deploy_test_price_feed(&mut self) -> Key {
+        self.test_env
+            .deploy_contract(Some(self.owner), "test-price-feed.wasm", runtime_args! {});
+        let price_feed = self.test_env.get_contract_package_hash(
+            self.owner,
+            &utils::get_contract_package_hash_key("test_price_feed".to_owned()),
+        );
+        self.test_env.call_contract(
+            Some(self.owner),
+            key_to_contract_package_hash(self.vrf),
+            "set_price_feed",
+            runtime_args! {
+                "price_feed" => price_feed
+            },
+            true,
+        );
+        price_feed
+    }
+
+    /// Sets the latest answer of `price_feed`, updated at the current block.
+    pub fn set_feed_answer(&mut self, price_feed: Key, answer: U256) {
+        self.test_env.call_contract(
+            Some(self.owner),
+            key_to_contract_package_hash(price_feed),
+            "set_latest_round_data",
+            runtime_args! {
+                "answer" => answer
+            },
+            true,
+        );
+    }
+
+    pub fn get_consumer_random_words(&mut self, consumer: Key) -> Vec<U256> {
+        self.test_env
+            .call_view_function(consumer, "get_random_words", runtime_args! {})
+    }
+
+    pub fn hash_of_key(&mut self, test_key: Bytes) -> HashAddr {
+        self.test_env.call_view_function(
+            self.vrf,
+            "hash_of_key",
+            runtime_args! {
+                "public_proving_key" => test_key
+            },
+        )
+    }
+}
+
+pub fn setup() -> VRFFixture {
+    let owner = test_env::env::generate_random_account(SECP256K1_TAG);
+    let sub_owner = test_env::env::generate_random_account(SECP256K1_TAG);
+    let sub_owner_address = Key::from(sub_owner);
+    let consumer = test_env::env::generate_random_account(SECP256K1_TAG);
+    let random = test_env::env::generate_random_account(SECP256K1_TAG);
+    let random_address = Key::from(random);
+    let oracle = test_env::env::generate_random_account(SECP256K1_TAG);
+    let mut test_env = TestEnv::new(&[owner, sub_owner, consumer, random, oracle], 0);
+
+    test_env.deploy_contract(
+        Some(owner),
+        "cep18.wasm",
+        runtime_args! {
+            "name" => "T0",
+            "symbol" => "T0",
+            "decimals" => 18u8,
+            "total_supply" =>
+            U256::from(10_000_000_000_000_000_000_000_000_000_000_u128),
+            "minter_list" => vec![Key::from(owner)],
+            "admin_list" => vec![Key::from(owner)],
+            "enable_mint_burn" => 1u8,
+            "contract_name" => "T0",
+        },
+    );
+
+    let payment_token = test_env.get_contract_package_hash(
+        owner,
+        &utils::get_contract_package_hash_key_cep18("T0".to_owned()),
+    );
+
+    test_env.deploy_contract(
+        Some(owner),
+        "block-hash-store.wasm",
+        runtime_args! {
+            "contract_name" => "block-hash-store"
+        },
+    );
+    let block_hash_store = test_env.get_contract_package_hash(
+        owner,
+        &utils::get_contract_package_hash_key("block-hash-store".to_owned()),
+    );
+
+    let price_feed = null_key();
+
+    test_env.deploy_contract(
         Some(owner),
         "vrf-coordinator.wasm",
         runtime_args! {
             "contract_name" => "vrf",
             "price_feed" => price_feed,
             "block_hash_store" => block_hash_store,
-            "payment_token" => payment_token
+            "payment_token" => payment_token,
+            "is_testing" => true
         },
     );
 
@@ -334,6 +864,8 @@ pub fn setup() -> VRFFixture {
         max_gas_limit: U128::from(100_000_000_000_u128),
         staleness_seconds: 86400,
         gas_after_payment_calculation: U128::from(100_000_000_000_u128),
+        fallback_motes_per_unit_link: U256::from(1_000_000_000_000_u128),
+        request_timeout_seconds: 3600,
     };
 
     let mut fixture = VRFFixture {
@@ -357,16 +889,30 @@ pub fn setup() -> VRFFixture {
         config.max_gas_limit,
         config.staleness_seconds,
         config.gas_after_payment_calculation,
+        config.fallback_motes_per_unit_link,
         Bytes::from(FeeConfig::default().to_bytes().unwrap()),
+        config.request_timeout_seconds,
         true,
     );
+    // a tip confirming requests at REQUEST_BLOCK_HEIGHT with any number of confirmations
+    fixture.set_block_ref(
+        BlockRef {
+            era_id: 0,
+            height: REQUEST_BLOCK_HEIGHT + MAX_REQUEST_CONFIRMATIONS,
+            timestamp: 0,
+        },
+        hex::encode([0u8; 32]),
+    );
 
     fixture
 }
 
 #[cfg(test)]
 mod test_set_config {
-    use casper_types::bytesrepr::{Bytes, ToBytes};
+    use casper_types::{
+        bytesrepr::{Bytes, ToBytes},
+        U256,
+    };
     use common::data_types::FeeConfig;
 
     use super::setup;
@@ -380,7 +926,9 @@ mod test_set_config {
             fixture.config.max_gas_limit,
             fixture.config.staleness_seconds,
             fixture.config.gas_after_payment_calculation,
+            fixture.config.fallback_motes_per_unit_link,
             Bytes::from(FeeConfig::default().to_bytes().unwrap()),
+            fixture.config.request_timeout_seconds,
             false,
         );
 
@@ -400,7 +948,26 @@ mod test_set_config {
             fixture.config.max_gas_limit,
             fixture.config.staleness_seconds,
             fixture.config.gas_after_payment_calculation,
+            fixture.config.fallback_motes_per_unit_link,
+            Bytes::from(FeeConfig::default().to_bytes().unwrap()),
+            fixture.config.request_timeout_seconds,
+            true,
+        );
+    }
+
+    #[test]
+    #[should_panic = "User(10025)"]
+    fn test_zero_fallback_price() {
+        let mut fixture = setup();
+        fixture.set_config(
+            Some(fixture.owner),
+            fixture.config.minimum_request_confirmations,
+            fixture.config.max_gas_limit,
+            fixture.config.staleness_seconds,
+            fixture.config.gas_after_payment_calculation,
+            U256::zero(),
             Bytes::from(FeeConfig::default().to_bytes().unwrap()),
+            fixture.config.request_timeout_seconds,
             true,
         );
     }
@@ -465,6 +1032,77 @@ mod test_cancel_subscription {
     }
 }
 
+#[cfg(test)]
+mod test_on_token_transfer {
+    use casper_types::{
+        bytesrepr::{Bytes, ToBytes},
+        runtime_args, Key, RuntimeArgs, U128, U256,
+    };
+
+    use super::{setup, VRFFixture};
+
+    // the random account stands in for the payment token
+    fn before_each() -> (VRFFixture, u64) {
+        let mut fixture = setup();
+        fixture.test_env.call_contract(
+            Some(fixture.owner),
+            fixture.vrf.into_hash().unwrap().into(),
+            "set_payment_token",
+            runtime_args! {
+                "payment_token" => Key::from(fixture.random)
+            },
+            true,
+        );
+        let sub_id = fixture.create_subscription(&[]);
+        (fixture, sub_id)
+    }
+
+    #[test]
+    fn test_funds_subscription() {
+        let (mut fixture, sub_id) = before_each();
+        let data = Bytes::from(sub_id.to_bytes().unwrap());
+        fixture.on_token_transfer(fixture.random, U256::from(1000), data.clone());
+        fixture.on_token_transfer(fixture.random, U256::from(500), data);
+        assert_eq!(fixture.get_subscription(sub_id).balance, U128::from(1500));
+        let total_balance: U128 =
+            fixture
+                .test_env
+                .call_view_function(fixture.vrf, "get_total_balance", runtime_args! {});
+        assert_eq!(total_balance, U128::from(1500));
+    }
+
+    #[test]
+    #[should_panic = "User(10014)"]
+    fn test_only_payment_token_can_call() {
+        let (mut fixture, sub_id) = before_each();
+        let data = Bytes::from(sub_id.to_bytes().unwrap());
+        fixture.on_token_transfer(fixture.sub_owner, U256::from(1000), data);
+    }
+
+    #[test]
+    #[should_panic = "User(10015)"]
+    fn test_invalid_calldata() {
+        let (mut fixture, _) = before_each();
+        fixture.on_token_transfer(fixture.random, U256::from(1000), Bytes::from(vec![1u8, 0]));
+    }
+
+    #[test]
+    #[should_panic = "User(10013)"]
+    fn test_subscription_must_exist() {
+        let (mut fixture, sub_id) = before_each();
+        let data = Bytes::from((sub_id + 1).to_bytes().unwrap());
+        fixture.on_token_transfer(fixture.random, U256::from(1000), data);
+    }
+
+    #[test]
+    #[should_panic = "User(10065)"]
+    fn test_amount_above_u128_should_fail() {
+        let (mut fixture, sub_id) = before_each();
+        let data = Bytes::from(sub_id.to_bytes().unwrap());
+        fixture.on_token_transfer(fixture.random, U256::from(u128::MAX) + 1, data);
+    }
+}
+
 #[cfg(test)]
 mod test_recover_funds {
 
@@ -584,72 +1222,501 @@ mod test_request_random_words {
 }
 
 #[cfg(test)]
-mod test_key_registration {
-    use casper_types::{
-        bytesrepr::{Bytes, ToBytes},
-        runtime_args, HashAddr, RuntimeArgs, U256,
-    };
+mod test_subscription_limits {
+    use casper_types::{HashAddr, Key, U128};
+    use common::data_types::SubscriptionLimits;
+    use vrf_prover::Prover;
 
-    use super::{setup, VRFFixture};
+    use super::{setup, TestRequest, VRFFixture, REQUEST_BLOCK_HASH};
 
-    fn before_each() -> (VRFFixture, Bytes, HashAddr) {
+    const KEY_HASH: HashAddr = [1u8; 32];
+    const WINDOW_SECONDS: u64 = 3600;
+    // payment of a request with a callback gas limit of 1000 under the fixture's config
+    const REQUEST_PAYMENT: u128 = 100_000_001_000_000_000;
+    // payment of a fulfilled pull request, which pays no callback gas
+    const PULL_PAYMENT: u128 = 100_000_000_000_000_000;
+
+    fn before_each(limits: SubscriptionLimits) -> (VRFFixture, u64) {
         let mut fixture = setup();
-        let test_key = vec![U256::one(), U256::from(2)];
-        let test_key_bytes = Bytes::from(test_key.to_bytes().unwrap());
-        let kh: HashAddr = fixture.test_env.call_view_function(
-            fixture.vrf,
-            "hash_of_key",
-            runtime_args! {
-                "public_proving_key" => test_key_bytes.clone()
-            },
+        let sub_id = fixture.create_subscription(&[Key::from(fixture.consumer)]);
+        fixture.set_subscription_limits(fixture.sub_owner, sub_id, limits, true);
+        (fixture, sub_id)
+    }
+
+    fn request(fixture: &mut VRFFixture, sub_id: u64, num_words: u64) {
+        fixture.request_random_words(
+            fixture.consumer,
+            KEY_HASH,
+            sub_id,
+            1,
+            1000.into(),
+            num_words,
         );
-        (fixture, test_key_bytes, kh)
     }
 
     #[test]
-    #[should_panic = "User(10023)"]
-    fn test_cannot_reregister_key() {
-        let (mut fixture, test_key, _) = before_each();
-        fixture.register_proving_key(fixture.owner, fixture.sub_owner_address, test_key.clone());
-        fixture.register_proving_key(fixture.owner, fixture.sub_owner_address, test_key.clone());
+    fn test_limits_show_in_view() {
+        let limits = SubscriptionLimits {
+            window_seconds: WINDOW_SECONDS,
+            max_spend_per_window: REQUEST_PAYMENT.into(),
+            max_requests_per_consumer: 2,
+            max_num_words: 3,
+        };
+        let (mut fixture, sub_id) = before_each(limits);
+        assert_eq!(fixture.get_subscription(sub_id).limits, limits);
     }
 
     #[test]
-    #[should_panic = "User(10024)"]
-    fn test_cannot_deregister_unregistered_key() {
-        let (mut fixture, test_key, _) = before_each();
-        fixture.deregister_proving_key(fixture.owner, test_key.clone());
+    fn test_only_owner_can_set() {
+        let (mut fixture, sub_id) = before_each(Default::default());
+        let limits = SubscriptionLimits {
+            max_num_words: 1,
+            ..Default::default()
+        };
+        fixture.set_subscription_limits(fixture.random, sub_id, limits, false);
+        assert_eq!(
+            fixture.get_subscription(sub_id).limits,
+            SubscriptionLimits::default()
+        );
     }
 
     #[test]
-    fn test_can_reregister_after_deregister() {
-        let (mut fixture, test_key, _) = before_each();
-        fixture.register_proving_key(fixture.owner, fixture.sub_owner_address, test_key.clone());
-        fixture.deregister_proving_key(fixture.owner, test_key.clone());
-        fixture.register_proving_key(fixture.owner, fixture.random_address, test_key.clone());
+    #[should_panic = "User(10056)"]
+    fn test_caps_need_a_window() {
+        before_each(SubscriptionLimits {
+            max_requests_per_consumer: 1,
+            ..Default::default()
+        });
+    }
+
+    #[test]
+    #[should_panic = "User(10057)"]
+    fn test_num_words_above_limit() {
+        let (mut fixture, sub_id) = before_each(SubscriptionLimits {
+            max_num_words: 2,
+            ..Default::default()
+        });
+        request(&mut fixture, sub_id, 2);
+        request(&mut fixture, sub_id, 3);
+    }
+
+    #[test]
+    #[should_panic = "User(10058)"]
+    fn test_consumer_request_limit() {
+        let (mut fixture, sub_id) = before_each(SubscriptionLimits {
+            window_seconds: WINDOW_SECONDS,
+            max_requests_per_consumer: 2,
+            ..Default::default()
+        });
+        request(&mut fixture, sub_id, 1);
+        request(&mut fixture, sub_id, 1);
+        request(&mut fixture, sub_id, 1);
+    }
+
+    #[test]
+    fn test_consumer_request_limit_resets_with_window() {
+        let (mut fixture, sub_id) = before_each(SubscriptionLimits {
+            window_seconds: WINDOW_SECONDS,
+            max_requests_per_consumer: 1,
+            ..Default::default()
+        });
+        request(&mut fixture, sub_id, 1);
+        fixture.roll_timestamp(WINDOW_SECONDS);
+        request(&mut fixture, sub_id, 1);
+        assert_eq!(fixture.get_pending_requests(sub_id), 2);
+    }
+
+    /// Registers a proving key and funds the subscription to fulfill requests with it.
+    fn fund_fulfillments(fixture: &mut VRFFixture, sub_id: u64) -> (Prover, HashAddr) {
+        let prover = Prover::new([9u8; 32]).unwrap();
+        let pk = prover.public_key();
+        fixture.register_proving_key(fixture.owner, Key::from(fixture.oracle), pk.clone());
+        fixture.deposit_token(fixture.sub_owner, sub_id, (PULL_PAYMENT * 10).into());
+        let kh = fixture.hash_of_key(pk);
+        (prover, kh)
+    }
+
+    /// Makes a pull request of the consumer and fulfills it, charging `PULL_PAYMENT`.
+    fn request_and_fulfill(fixture: &mut VRFFixture, sub_id: u64, prover: &Prover, kh: HashAddr) {
+        let request = TestRequest {
+            pull: true,
+            callback_gas_limit: 0.into(),
+            ..TestRequest::new(kh, sub_id)
+        };
+        fixture.request_and_fulfill(&request, |event| {
+            prover.generate_proof(event.pre_seed, REQUEST_BLOCK_HASH)
+        });
+    }
+
+    #[test]
+    fn test_requests_are_not_charged_until_fulfilled() {
+        let (mut fixture, sub_id) = before_each(SubscriptionLimits {
+            window_seconds: WINDOW_SECONDS,
+            max_spend_per_window: U128::one(),
+            ..Default::default()
+        });
+        request(&mut fixture, sub_id, 1);
+        request(&mut fixture, sub_id, 1);
+        assert_eq!(fixture.get_pending_requests(sub_id), 2);
+    }
+
+    #[test]
+    #[should_panic = "User(10059)"]
+    fn test_spend_limit() {
+        let (mut fixture, sub_id) = before_each(SubscriptionLimits {
+            window_seconds: WINDOW_SECONDS,
+            max_spend_per_window: U128::from(PULL_PAYMENT * 3 / 2),
+            ..Default::default()
+        });
+        let (prover, kh) = fund_fulfillments(&mut fixture, sub_id);
+        request_and_fulfill(&mut fixture, sub_id, &prover, kh);
+        request_and_fulfill(&mut fixture, sub_id, &prover, kh);
+        request(&mut fixture, sub_id, 1);
+    }
+
+    #[test]
+    fn test_spend_limit_resets_with_window() {
+        let (mut fixture, sub_id) = before_each(SubscriptionLimits {
+            window_seconds: WINDOW_SECONDS,
+            max_spend_per_window: U128::from(PULL_PAYMENT),
+            ..Default::default()
+        });
+        let (prover, kh) = fund_fulfillments(&mut fixture, sub_id);
+        request_and_fulfill(&mut fixture, sub_id, &prover, kh);
+        fixture.roll_timestamp(WINDOW_SECONDS);
+        request_and_fulfill(&mut fixture, sub_id, &prover, kh);
+        assert_eq!(fixture.get_subscription(sub_id).req_count, 2);
     }
 }
 
 #[cfg(test)]
-mod test_fulfill_random_words {
+mod test_cancel_request {
     use casper_types::{
         bytesrepr::{Bytes, ToBytes},
-        Key, U256,
+        Key, U128, U256,
     };
     use common::{
-        data_types::{Proof, RequestCommitment},
-        events::RandomWordsRequested,
+        data_types::{RequestCommitment, RequestStatus},
+        events::{RandomWordsRequestCancelled, RandomWordsRequested},
     };
 
     use super::{setup, VRFFixture};
 
-    fn before_each() -> (VRFFixture, Bytes) {
+    /// Makes a request from the consumer and returns its id and commitment.
+    fn before_each() -> (VRFFixture, u64, U256, Bytes) {
         let mut fixture = setup();
+        let sub_id = fixture.create_subscription(&[Key::from(fixture.consumer)]);
+        fixture.deposit_token(
+            fixture.sub_owner,
+            sub_id,
+            U128::from(1000000000000000000_u128),
+        );
         let test_key = vec![U256::one(), U256::from(2)];
-        let test_key_bytes = Bytes::from(test_key.to_bytes().unwrap());
-        fixture.register_proving_key(
-            fixture.owner,
-            fixture.sub_owner_address,
+        let kh = fixture.hash_of_key(Bytes::from(test_key.to_bytes().unwrap()));
+        let event_length = fixture.test_env.get_event_length(fixture.vrf);
+        fixture.request_random_words(fixture.consumer, kh, sub_id, 1, 1000.into(), 1);
+
+        let event: RandomWordsRequested = fixture
+            .test_env
+            .get_event(fixture.vrf, event_length as usize)
+            .unwrap();
+        let rc = RequestCommitment {
+            block: event.block,
+            request_confirmations: event.minimum_request_confirmations,
+            sub_id,
+            callback_gas_limit: 1000.into(),
+            num_words: 1,
+            sender: fixture.consumer.into(),
+        };
+        (
+            fixture,
+            sub_id,
+            event.request_id,
+            rc.to_bytes().unwrap().into(),
+        )
+    }
+
+    #[test]
+    fn test_consumer_can_cancel_after_timeout() {
+        let (mut fixture, sub_id, request_id, rc) = before_each();
+        fixture.roll_timestamp(fixture.config.request_timeout_seconds);
+        let event_length = fixture.test_env.get_event_length(fixture.vrf);
+        fixture.cancel_request(fixture.consumer, request_id, rc);
+
+        let request = fixture.get_request_commitment(request_id);
+        assert_eq!(request.status, RequestStatus::Cancelled);
+        let event: RandomWordsRequestCancelled = fixture
+            .test_env
+            .get_event(fixture.vrf, event_length as usize)
+            .unwrap();
+        assert_eq!(event.request_id, request_id);
+        assert_eq!(event.sub_id, sub_id);
+        assert_eq!(event.cancelled_by, Key::from(fixture.consumer));
+
+        // the cancelled request no longer blocks the subscription
+        fixture.remove_consumer(fixture.sub_owner, sub_id, Key::from(fixture.consumer));
+    }
+
+    #[test]
+    fn test_sub_owner_can_cancel_after_timeout() {
+        let (mut fixture, sub_id, request_id, rc) = before_each();
+        fixture.roll_timestamp(fixture.config.request_timeout_seconds);
+        fixture.cancel_request(fixture.sub_owner, request_id, rc);
+        fixture.cancel_subscription(fixture.sub_owner, sub_id, fixture.sub_owner_address);
+    }
+
+    #[test]
+    #[should_panic = "User(10054)"]
+    fn test_cannot_cancel_before_timeout() {
+        let (mut fixture, _, request_id, rc) = before_each();
+        fixture.roll_timestamp(fixture.config.request_timeout_seconds - 1);
+        fixture.cancel_request(fixture.consumer, request_id, rc);
+    }
+
+    #[test]
+    #[should_panic = "User(10053)"]
+    fn test_only_consumer_or_sub_owner_can_cancel() {
+        let (mut fixture, _, request_id, rc) = before_each();
+        fixture.roll_timestamp(fixture.config.request_timeout_seconds);
+        fixture.cancel_request(fixture.random, request_id, rc);
+    }
+
+    #[test]
+    #[should_panic = "User(10027)"]
+    fn test_cannot_cancel_twice() {
+        let (mut fixture, _, request_id, rc) = before_each();
+        fixture.roll_timestamp(fixture.config.request_timeout_seconds);
+        fixture.cancel_request(fixture.consumer, request_id, rc.clone());
+        fixture.cancel_request(fixture.consumer, request_id, rc);
+    }
+}
+
+#[cfg(test)]
+mod test_pending_requests {
+    use casper_types::{
+        bytesrepr::{Bytes, ToBytes},
+        HashAddr, Key, U128, U256,
+    };
+    use common::{
+        data_types::{RequestCommitment, RequestStatus},
+        events::RandomWordsRequested,
+    };
+
+    use super::{setup, VRFFixture};
+
+    fn before_each() -> (VRFFixture, u64, HashAddr) {
+        let mut fixture = setup();
+        let sub_id = fixture.create_subscription(&[Key::from(fixture.consumer)]);
+        fixture.deposit_token(
+            fixture.sub_owner,
+            sub_id,
+            U128::from(1000000000000000000_u128),
+        );
+        let test_key = vec![U256::one(), U256::from(2)];
+        let kh = fixture.hash_of_key(Bytes::from(test_key.to_bytes().unwrap()));
+        (fixture, sub_id, kh)
+    }
+
+    /// Makes a request from the consumer and returns its id and commitment.
+    fn request(fixture: &mut VRFFixture, sub_id: u64, kh: HashAddr) -> (U256, Bytes) {
+        let event_length = fixture.test_env.get_event_length(fixture.vrf);
+        fixture.request_random_words(fixture.consumer, kh, sub_id, 1, 1000.into(), 1);
+        let event: RandomWordsRequested = fixture
+            .test_env
+            .get_event(fixture.vrf, event_length as usize)
+            .unwrap();
+        let rc = RequestCommitment {
+            block: event.block,
+            request_confirmations: event.minimum_request_confirmations,
+            sub_id,
+            callback_gas_limit: 1000.into(),
+            num_words: 1,
+            sender: fixture.consumer.into(),
+        };
+        (event.request_id, rc.to_bytes().unwrap().into())
+    }
+
+    #[test]
+    fn test_tracks_every_request() {
+        let (mut fixture, sub_id, kh) = before_each();
+        assert_eq!(fixture.get_pending_requests(sub_id), 0);
+        let (first, first_rc) = request(&mut fixture, sub_id, kh);
+        let (second, second_rc) = request(&mut fixture, sub_id, kh);
+        assert_eq!(fixture.get_pending_requests(sub_id), 2);
+        let request = fixture.get_request_commitment(first);
+        assert_eq!(request.status, RequestStatus::Pending);
+        assert_eq!(request.key_hash, kh);
+        assert_eq!(request.commitment.request_confirmations, 1);
+        assert_eq!(request.commitment.to_bytes().unwrap(), first_rc.to_vec());
+        assert_eq!(
+            fixture.get_request_commitment(U256::from(42)).status,
+            RequestStatus::Unknown
+        );
+
+        fixture.roll_timestamp(fixture.config.request_timeout_seconds);
+        fixture.cancel_request(fixture.consumer, second, second_rc);
+        assert_eq!(fixture.get_pending_requests(sub_id), 1);
+        assert_eq!(
+            fixture.get_request_commitment(first).status,
+            RequestStatus::Pending
+        );
+        fixture.cancel_request(fixture.consumer, first, first_rc);
+        assert_eq!(fixture.get_pending_requests(sub_id), 0);
+        fixture.remove_consumer(fixture.sub_owner, sub_id, Key::from(fixture.consumer));
+    }
+
+    #[test]
+    fn test_owner_cancel_with_requests_in_flight() {
+        let (mut fixture, sub_id, kh) = before_each();
+        let (first, first_rc) = request(&mut fixture, sub_id, kh);
+        request(&mut fixture, sub_id, kh);
+        fixture.owner_cancel_subscription(fixture.owner, sub_id);
+        assert_eq!(fixture.get_pending_requests(sub_id), 0);
+
+        // the requests left behind can still be cancelled without the count going below zero
+        fixture.roll_timestamp(fixture.config.request_timeout_seconds);
+        fixture.cancel_request(fixture.consumer, first, first_rc);
+        assert_eq!(fixture.get_pending_requests(sub_id), 0);
+        assert_eq!(
+            fixture.get_request_commitment(first).status,
+            RequestStatus::Cancelled
+        );
+    }
+
+    #[test]
+    #[should_panic = "User(10017)"]
+    fn test_older_request_blocks_cancel_subscription() {
+        let (mut fixture, sub_id, kh) = before_each();
+        request(&mut fixture, sub_id, kh);
+        let (latest, latest_rc) = request(&mut fixture, sub_id, kh);
+        fixture.roll_timestamp(fixture.config.request_timeout_seconds);
+        fixture.cancel_request(fixture.consumer, latest, latest_rc);
+        fixture.cancel_subscription(fixture.sub_owner, sub_id, fixture.sub_owner_address);
+    }
+}
+
+#[cfg(test)]
+mod test_key_registration {
+    use casper_types::{
+        bytesrepr::{Bytes, ToBytes},
+        runtime_args, HashAddr, RuntimeArgs, U256,
+    };
+
+    use super::{setup, VRFFixture};
+
+    fn before_each() -> (VRFFixture, Bytes, HashAddr) {
+        let mut fixture = setup();
+        let test_key = vec![U256::one(), U256::from(2)];
+        let test_key_bytes = Bytes::from(test_key.to_bytes().unwrap());
+        let kh: HashAddr = fixture.test_env.call_view_function(
+            fixture.vrf,
+            "hash_of_key",
+            runtime_args! {
+                "public_proving_key" => test_key_bytes.clone()
+            },
+        );
+        (fixture, test_key_bytes, kh)
+    }
+
+    #[test]
+    #[should_panic = "User(10023)"]
+    fn test_cannot_reregister_key() {
+        let (mut fixture, test_key, _) = before_each();
+        fixture.register_proving_key(fixture.owner, fixture.sub_owner_address, test_key.clone());
+        fixture.register_proving_key(fixture.owner, fixture.sub_owner_address, test_key);
+    }
+
+    #[test]
+    #[should_panic = "User(10024)"]
+    fn test_cannot_deregister_unregistered_key() {
+        let (mut fixture, test_key, _) = before_each();
+        fixture.deregister_proving_key(fixture.owner, test_key);
+    }
+
+    #[test]
+    #[should_panic = "User(10047)"]
+    fn test_cannot_register_unknown_key_type() {
+        let (mut fixture, test_key, _) = before_each();
+        fixture.register_proving_key_with_type(
+            fixture.owner,
+            fixture.sub_owner_address,
+            test_key,
+            3,
+        );
+    }
+
+    #[test]
+    #[should_panic = "User(10047)"]
+    fn test_cannot_register_mistyped_key_type() {
+        let (mut fixture, test_key, _) = before_each();
+        fixture.test_env.call_contract(
+            Some(fixture.owner),
+            fixture.vrf.into_hash().unwrap().into(),
+            "register_proving_key",
+            runtime_args! {
+                "oracle" => fixture.sub_owner_address,
+                "public_proving_key" => test_key,
+                "key_type" => 1u64,
+            },
+            true,
+        );
+    }
+
+    #[test]
+    fn test_can_reregister_after_deregister() {
+        let (mut fixture, test_key, _) = before_each();
+        fixture.register_proving_key(fixture.owner, fixture.sub_owner_address, test_key.clone());
+        fixture.deregister_proving_key(fixture.owner, test_key.clone());
+        fixture.register_proving_key(fixture.owner, fixture.random_address, test_key);
+    }
+}
+
+#[cfg(test)]
+mod test_fulfill_random_words {
+    use std::convert::TryInto;
+
+    use casper_types::{
+        bytesrepr::{Bytes, ToBytes},
+        HashAddr, Key, U256,
+    };
+    use common::{
+        constants::MAX_REQUEST_CONFIRMATIONS,
+        data_types::{BlockRef, Proof, RequestCommitment},
+        events::RandomWordsRequested,
+        helpers::u256_from_hex,
+    };
+    use vrf_prover::Prover;
+
+    use super::{setup, TestRequest, VRFFixture, REQUEST_BLOCK_HASH, REQUEST_BLOCK_HEIGHT};
+
+    const SK: &str = "0000000000000000000000000000000000000000000000000000000000003039";
+    // public key of the secret key 12345
+    const PK_X: &str = "f01d6b9018ab421dd410404cb869072065522bf85734008f105cf385a023a80f";
+    const PK_Y: &str = "0eba29d0f0c5408ed681984dc525982abefccd9f7ff01dd26da4999cf3f6a295";
+    const G_X: &str = "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+    const G_Y: &str = "483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8";
+    const C: &str = "1111111111111111111111111111111111111111111111111111111111111111";
+    const S: &str = "2222222222222222222222222222222222222222222222222222222222222222";
+    // address of C*pk + S*g
+    const U_WITNESS: &str = "da739170803c8a221601c8c626588d1865c504af701b9f9f520bff1037ca145c";
+    // C*g
+    const C_G_X: &str = "4f355bdcb7cc0af728ef3cceb9615d90684bb5b2ca5f859ab0f0b704075871aa";
+    const C_G_Y: &str = "385b6b1b8ead809ca67454d9683fcf2ba03456d6fe2c4abe2b07f0fbdbb2f1c1";
+
+    fn point(x: &str, y: &str) -> Bytes {
+        vec![u256_from_hex(x), u256_from_hex(y)]
+            .to_bytes()
+            .unwrap()
+            .into()
+    }
+
+    fn before_each() -> (VRFFixture, Bytes) {
+        let mut fixture = setup();
+        let test_key = vec![U256::one(), U256::from(2)];
+        let test_key_bytes = Bytes::from(test_key.to_bytes().unwrap());
+        fixture.register_proving_key(
+            fixture.owner,
+            fixture.sub_owner_address,
             test_key_bytes.clone(),
         );
         (fixture, test_key_bytes)
@@ -672,7 +1739,11 @@ mod test_fulfill_random_words {
         };
         let proof = proof.to_bytes().unwrap().into();
         let rc = RequestCommitment {
-            block_num: 1,
+            block: BlockRef {
+                timestamp: 1,
+                ..Default::default()
+            },
+            request_confirmations: 1,
             sub_id: 1,
             callback_gas_limit: 3.into(),
             num_words: 4,
@@ -700,7 +1771,11 @@ mod test_fulfill_random_words {
         };
         let proof = proof.to_bytes().unwrap().into();
         let rc = RequestCommitment {
-            block_num: 1,
+            block: BlockRef {
+                timestamp: 1,
+                ..Default::default()
+            },
+            request_confirmations: 1,
             sub_id: 1,
             callback_gas_limit: 3.into(),
             num_words: 4,
@@ -739,7 +1814,11 @@ mod test_fulfill_random_words {
         };
         let proof: Bytes = proof.to_bytes().unwrap().into();
         let rc = RequestCommitment {
-            block_num: req_recipt.timestamp + 1,    //wrong block number
+            block: BlockRef {
+                timestamp: req_recipt.block.timestamp + 1,    //wrong block number
+                ..req_recipt.block
+            },
+            request_confirmations: req_recipt.minimum_request_confirmations,
             sub_id,
             callback_gas_limit: 1000.into(),
             num_words: 1,
@@ -749,4 +1828,989 @@ mod test_fulfill_random_words {
         let rc = rc.to_bytes().unwrap().into();
         fixture.fulfill_random_words(fixture.oracle, proof, rc);
     }
+
+    // fulfills a request with gamma = g, c = C and the given s and witnesses
+    fn fulfill_with_witnesses(s: U256, c_gamma_witness: Bytes, s_hash_witness: Bytes) {
+        let (mut fixture, _) = before_each();
+        let pk = point(PK_X, PK_Y);
+        fixture.register_proving_key(fixture.owner, fixture.sub_owner_address, pk.clone());
+        let sub_id = fixture.create_subscription(&[Key::from(fixture.consumer)]);
+        fixture.deposit_token(fixture.sub_owner, sub_id, 1000000000000000000_u128.into());
+        let kh = fixture.hash_of_key(pk.clone());
+        fixture.request_and_fulfill(&TestRequest::new(kh, sub_id), |event| Proof {
+            pk,
+            gamma: point(G_X, G_Y),
+            c: u256_from_hex(C),
+            s,
+            seed: event.pre_seed,
+            u_witness: Key::Hash(hex::decode(U_WITNESS).unwrap().try_into().unwrap()),
+            c_gamma_witness,
+            s_hash_witness,
+            z_inv: U256::from(1),
+        });
+    }
+
+    #[test]
+    #[should_panic = "User(10039)"]
+    fn test_valid_linear_combination_with_generator() {
+        // passes the u_witness check, then fails on the equal c*gamma and s*hash witnesses
+        fulfill_with_witnesses(u256_from_hex(S), point(G_X, G_Y), point(G_X, G_Y));
+    }
+
+    #[test]
+    #[should_panic = "User(10038)"]
+    fn test_tampered_s_should_fail() {
+        fulfill_with_witnesses(u256_from_hex(S) + 1, point(G_X, G_Y), point(G_X, G_Y));
+    }
+
+    fn fulfill_rfc9381(pk: Bytes, key_type: u8, gamma: Bytes) {
+        let (mut fixture, _) = before_each();
+        fixture.register_proving_key_with_type(
+            fixture.owner,
+            fixture.sub_owner_address,
+            pk.clone(),
+            key_type,
+        );
+        let sub_id = fixture.create_subscription(&[Key::from(fixture.consumer)]);
+        fixture.deposit_token(fixture.sub_owner, sub_id, 1000000000000000000_u128.into());
+        let kh = fixture.hash_of_key(pk.clone());
+        let random_address = fixture.random_address;
+        fixture.request_and_fulfill(&TestRequest::new(kh, sub_id), |event| Proof {
+            pk,
+            gamma,
+            c: U256::from(1),
+            s: U256::from(1),
+            seed: event.pre_seed,
+            u_witness: random_address,
+            c_gamma_witness: Bytes::new(),
+            s_hash_witness: Bytes::new(),
+            z_inv: U256::zero(),
+        });
+    }
+
+    #[test]
+    #[should_panic = "User(10044)"]
+    fn test_invalid_rfc9381_proof_should_fail() {
+        // SEC1 compressed public key of the secret key 12345
+        fulfill_rfc9381(
+            hex::decode(format!("03{}", PK_X)).unwrap().into(),
+            1,
+            hex::decode(format!("02{}", G_X)).unwrap().into(),
+        );
+    }
+
+    #[test]
+    #[should_panic = "User(10044)"]
+    fn test_invalid_ed25519_proof_should_fail() {
+        // public key of the RFC 9381 ed25519 test vectors, gamma is the base point
+        fulfill_rfc9381(
+            hex::decode("d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a")
+                .unwrap()
+                .into(),
+            2,
+            hex::decode("5866666666666666666666666666666666666666666666666666666666666666")
+                .unwrap()
+                .into(),
+        );
+    }
+
+    /// Fulfills a request made in block `request_block`, with a proof from `vrf_prover` for
+    /// `proven_block_hash`, after `tamper`.
+    fn fulfill_with_prover_proof(
+        request_block: u64,
+        proven_block_hash: HashAddr,
+        tamper: fn(&mut Proof),
+    ) {
+        let (mut fixture, _) = before_each();
+        let prover = Prover::new(hex::decode(SK).unwrap().try_into().unwrap()).unwrap();
+        let pk = prover.public_key();
+        fixture.register_proving_key(fixture.owner, fixture.sub_owner_address, pk.clone());
+        let sub_id = fixture.create_subscription(&[Key::from(fixture.consumer)]);
+        fixture.deposit_token(fixture.sub_owner, sub_id, 1000000000000000000_u128.into());
+        let kh = fixture.hash_of_key(pk);
+        let request = TestRequest {
+            block_height: request_block,
+            ..TestRequest::new(kh, sub_id)
+        };
+        fixture.request_and_fulfill(&request, |event| {
+            let mut proof = prover.generate_proof(event.pre_seed, proven_block_hash);
+            tamper(&mut proof);
+            proof
+        });
+    }
+
+    #[test]
+    fn test_prover_proof_fulfills_request() {
+        let (mut fixture, _) = before_each();
+        let prover = Prover::new(hex::decode(SK).unwrap().try_into().unwrap()).unwrap();
+        let pk = prover.public_key();
+        fixture.register_proving_key(fixture.owner, fixture.sub_owner_address, pk.clone());
+        let consumer = fixture.deploy_test_consumer();
+        let sub_id = fixture.create_subscription(&[consumer]);
+        fixture.deposit_token(fixture.sub_owner, sub_id, 1000000000000000000_u128.into());
+        let kh = fixture.hash_of_key(pk);
+        let request = TestRequest {
+            requester: Some(consumer),
+            num_words: 2,
+            ..TestRequest::new(kh, sub_id)
+        };
+        let (_, random_words) = fixture.request_and_fulfill(&request, |event| {
+            prover.generate_proof(event.pre_seed, REQUEST_BLOCK_HASH)
+        });
+
+        assert_eq!(fixture.get_consumer_random_words(consumer), random_words);
+        assert_eq!(fixture.get_subscription(sub_id).req_count, 1);
+    }
+
+    #[test]
+    #[should_panic = "User(10043)"]
+    fn test_prover_proof_with_bad_z_inv_should_fail() {
+        // every witness of the generated proof checks out up to the final addition
+        fulfill_with_prover_proof(REQUEST_BLOCK_HEIGHT, REQUEST_BLOCK_HASH, |proof| {
+            proof.z_inv += U256::one()
+        });
+    }
+
+    #[test]
+    #[should_panic = "User(10041)"]
+    fn test_prover_proof_for_other_block_should_fail() {
+        fulfill_with_prover_proof(REQUEST_BLOCK_HEIGHT, [2u8; 32], |_| {});
+    }
+
+    #[test]
+    #[should_panic = "User(10055)"]
+    fn test_not_enough_confirmations_should_fail() {
+        // the request block is the highest one stored, with no confirmation on top of it
+        fulfill_with_prover_proof(
+            REQUEST_BLOCK_HEIGHT + MAX_REQUEST_CONFIRMATIONS + 1,
+            REQUEST_BLOCK_HASH,
+            |_| {},
+        );
+    }
+
+    #[test]
+    #[should_panic = "User(10013)"]
+    fn test_request_of_owner_canceled_subscription_should_fail() {
+        let (mut fixture, _) = before_each();
+        let prover = Prover::new(hex::decode(SK).unwrap().try_into().unwrap()).unwrap();
+        let pk = prover.public_key();
+        fixture.register_proving_key(fixture.owner, fixture.sub_owner_address, pk.clone());
+        let sub_id = fixture.create_subscription(&[Key::from(fixture.consumer)]);
+        fixture.deposit_token(fixture.sub_owner, sub_id, 1000000000000000000_u128.into());
+        let kh = fixture.hash_of_key(pk);
+        let event_length = fixture.test_env.get_event_length(fixture.vrf);
+        fixture.request_random_words(fixture.consumer, kh, sub_id, 1, 1000.into(), 1);
+        let event: RandomWordsRequested = fixture
+            .test_env
+            .get_event(fixture.vrf, event_length as usize)
+            .unwrap();
+        let block = fixture.set_block_hash(event.block.timestamp, hex::encode(REQUEST_BLOCK_HASH));
+        fixture.owner_cancel_subscription(fixture.owner, sub_id);
+
+        let proof = prover.generate_proof(event.pre_seed, REQUEST_BLOCK_HASH);
+        let rc = RequestCommitment {
+            block,
+            request_confirmations: event.minimum_request_confirmations,
+            sub_id,
+            callback_gas_limit: 1000.into(),
+            num_words: 1,
+            sender: fixture.consumer.into(),
+        };
+        fixture.fulfill_random_words(
+            fixture.oracle,
+            proof.to_bytes().unwrap().into(),
+            rc.to_bytes().unwrap().into(),
+        );
+    }
+
+    #[test]
+    #[should_panic = "User(10064)"]
+    fn test_block_ref_other_than_stored_should_fail() {
+        let (mut fixture, _) = before_each();
+        let prover = Prover::new(hex::decode(SK).unwrap().try_into().unwrap()).unwrap();
+        let pk = prover.public_key();
+        fixture.register_proving_key(fixture.owner, fixture.sub_owner_address, pk.clone());
+        let sub_id = fixture.create_subscription(&[Key::from(fixture.consumer)]);
+        fixture.deposit_token(fixture.sub_owner, sub_id, 1000000000000000000_u128.into());
+        let kh = fixture.hash_of_key(pk);
+        let event_length = fixture.test_env.get_event_length(fixture.vrf);
+        fixture.request_random_words(fixture.consumer, kh, sub_id, 1, 1000.into(), 1);
+        let event: RandomWordsRequested = fixture
+            .test_env
+            .get_event(fixture.vrf, event_length as usize)
+            .unwrap();
+        let block = fixture.set_block_hash(event.block.timestamp, hex::encode(REQUEST_BLOCK_HASH));
+
+        // a lower height would need fewer confirmations than the stored block does
+        let proof = prover.generate_proof(event.pre_seed, REQUEST_BLOCK_HASH);
+        let rc = RequestCommitment {
+            block: BlockRef {
+                height: block.height - 1,
+                ..block
+            },
+            request_confirmations: event.minimum_request_confirmations,
+            sub_id,
+            callback_gas_limit: 1000.into(),
+            num_words: 1,
+            sender: fixture.consumer.into(),
+        };
+        fixture.fulfill_random_words(
+            fixture.oracle,
+            proof.to_bytes().unwrap().into(),
+            rc.to_bytes().unwrap().into(),
+        );
+    }
+
+    #[test]
+    #[should_panic = "User(10040)"]
+    fn test_bad_c_gamma_witness_should_fail() {
+        fulfill_with_witnesses(u256_from_hex(S), point(PK_X, PK_Y), point(G_X, G_Y));
+    }
+
+    #[test]
+    #[should_panic = "User(10041)"]
+    fn test_valid_c_gamma_witness_bad_s_hash_witness_should_fail() {
+        // c*gamma is accepted, g is not s times the hashed seed
+        fulfill_with_witnesses(u256_from_hex(S), point(C_G_X, C_G_Y), point(G_X, G_Y));
+    }
+}
+
+#[cfg(test)]
+mod test_oracle_payment {
+    use casper_types::{Key, U128, U256};
+    use common::data_types::{FeeConfig, FeeTier};
+    use vrf_prover::Prover;
+
+    use super::{setup, TestRequest, VRFFixture, REQUEST_BLOCK_HASH};
+
+    const DEPOSIT: u128 = 1_000_000_000_000_000_000;
+    const CALLBACK_GAS_LIMIT: u128 = 1000;
+
+    /// Fulfills a request of a consumer contract, billed at its callback gas limit, and returns
+    /// the subscription and the payment expected for it.
+    fn before_each() -> (VRFFixture, u64, U128) {
+        let mut fixture = setup();
+        let prover = Prover::new([9u8; 32]).unwrap();
+        let pk = prover.public_key();
+        fixture.register_proving_key(fixture.owner, Key::from(fixture.oracle), pk.clone());
+        let consumer = fixture.deploy_test_consumer();
+        let sub_id = fixture.create_subscription(&[consumer]);
+        fixture.deposit_token(fixture.sub_owner, sub_id, DEPOSIT.into());
+        let kh = fixture.hash_of_key(pk);
+        let request = TestRequest {
+            requester: Some(consumer),
+            callback_gas_limit: CALLBACK_GAS_LIMIT.into(),
+            ..TestRequest::new(kh, sub_id)
+        };
+        fixture.request_and_fulfill(&request, |event| {
+            prover.generate_proof(event.pre_seed, REQUEST_BLOCK_HASH)
+        });
+
+        let (_, fee_ppm) = fixture.get_fee_tier(0);
+        let gas = fixture.config.gas_after_payment_calculation.as_u128() + CALLBACK_GAS_LIMIT;
+        let payment = U256::from(DEPOSIT) * U256::from(gas)
+            / fixture.config.fallback_motes_per_unit_link
+            + U256::from(1_000_000_000_000_u64) * U256::from(fee_ppm);
+        (fixture, sub_id, U128::from(payment.as_u128()))
+    }
+
+    #[test]
+    fn test_fulfillment_charges_subscription() {
+        let (mut fixture, sub_id, payment) = before_each();
+        let sub = fixture.get_subscription(sub_id);
+        assert_eq!(sub.balance, U128::from(DEPOSIT) - payment);
+        assert_eq!(sub.req_count, 1);
+        let oracle = Key::from(fixture.oracle);
+        let payment_token = fixture.payment_token;
+        assert_eq!(
+            fixture.get_withdrawable_token(oracle, payment_token),
+            payment
+        );
+        // the oracle's share stays in the coordinator until withdrawn
+        assert_eq!(
+            fixture.get_total_balance(payment_token),
+            U128::from(DEPOSIT)
+        );
+    }
+
+    #[test]
+    fn test_oracle_withdraws_payment() {
+        let (mut fixture, _, payment) = before_each();
+        let oracle = Key::from(fixture.oracle);
+        let recipient = fixture.random_address;
+        let payment_token = fixture.payment_token;
+        let recipient_balance = fixture.balance_of(payment_token, recipient);
+        fixture.oracle_withdraw(fixture.oracle, recipient, payment);
+        assert_eq!(
+            fixture.get_withdrawable_token(oracle, payment_token),
+            U128::zero()
+        );
+        assert_eq!(
+            fixture.get_total_balance(payment_token),
+            U128::from(DEPOSIT) - payment
+        );
+        assert_eq!(
+            fixture.balance_of(payment_token, recipient),
+            recipient_balance + payment
+        );
+    }
+
+    #[test]
+    fn test_skipped_callback_pays_no_flat_fee() {
+        let mut fixture = setup();
+        fixture.set_fee_config(FeeConfig {
+            tiers: vec![FeeTier {
+                reqs_threshold: 0,
+                fulfillment_flat_fee_link_ppm: 1000,
+            }],
+        });
+        let prover = Prover::new([9u8; 32]).unwrap();
+        let pk = prover.public_key();
+        fixture.register_proving_key(fixture.owner, Key::from(fixture.oracle), pk.clone());
+        let consumer = fixture.deploy_test_consumer();
+        let sub_id = fixture.create_subscription(&[consumer]);
+        fixture.deposit_token(fixture.sub_owner, sub_id, DEPOSIT.into());
+        let kh = fixture.hash_of_key(pk);
+        let request = TestRequest {
+            requester: Some(consumer),
+            callback_gas_limit: CALLBACK_GAS_LIMIT.into(),
+            skip_callback: true,
+            ..TestRequest::new(kh, sub_id)
+        };
+        fixture.request_and_fulfill(&request, |event| {
+            prover.generate_proof(event.pre_seed, REQUEST_BLOCK_HASH)
+        });
+
+        let payment = U256::from(DEPOSIT)
+            * U256::from(fixture.config.gas_after_payment_calculation.as_u128())
+            / fixture.config.fallback_motes_per_unit_link;
+        let payment = U128::from(payment.as_u128());
+        assert_eq!(
+            fixture.get_subscription(sub_id).balance,
+            U128::from(DEPOSIT) - payment
+        );
+        let oracle = Key::from(fixture.oracle);
+        let payment_token = fixture.payment_token;
+        assert_eq!(
+            fixture.get_withdrawable_token(oracle, payment_token),
+            payment
+        );
+    }
+
+    #[test]
+    #[should_panic = "User(10026)"]
+    fn test_callback_gas_used_over_limit_should_fail() {
+        let mut fixture = setup();
+        let prover = Prover::new([9u8; 32]).unwrap();
+        let pk = prover.public_key();
+        fixture.register_proving_key(fixture.owner, Key::from(fixture.oracle), pk.clone());
+        let consumer = fixture.deploy_test_consumer();
+        let sub_id = fixture.create_subscription(&[consumer]);
+        fixture.deposit_token(fixture.sub_owner, sub_id, DEPOSIT.into());
+        let kh = fixture.hash_of_key(pk);
+        let request = TestRequest {
+            requester: Some(consumer),
+            callback_gas_limit: CALLBACK_GAS_LIMIT.into(),
+            callback_gas_used: Some((CALLBACK_GAS_LIMIT + 1).into()),
+            ..TestRequest::new(kh, sub_id)
+        };
+        fixture.request_and_fulfill(&request, |event| {
+            prover.generate_proof(event.pre_seed, REQUEST_BLOCK_HASH)
+        });
+    }
+
+    #[test]
+    #[should_panic = "User(10011)"]
+    fn test_withdraw_twice_should_fail() {
+        let (mut fixture, _, payment) = before_each();
+        let recipient = fixture.random_address;
+        fixture.oracle_withdraw(fixture.oracle, recipient, payment);
+        fixture.oracle_withdraw(fixture.oracle, recipient, payment);
+    }
+}
+
+#[cfg(test)]
+mod test_price_feed {
+    use casper_types::{Key, U128, U256};
+    use vrf_prover::Prover;
+
+    use super::{setup, TestRequest, VRFFixture, REQUEST_BLOCK_HASH};
+
+    const DEPOSIT: u128 = 1_000_000_000_000_000_000;
+    // the feed prices the token at twice the fallback price
+    const FEED_MOTES_PER_UNIT: u128 = 2_000_000_000_000;
+
+    fn before_each() -> (VRFFixture, Key) {
+        let mut fixture = setup();
+        let price_feed = fixture.deploy_test_price_feed();
+        (fixture, price_feed)
+    }
+
+    /// Fulfills a pull request, which pays no callback gas, and returns what it cost the
+    /// subscription.
+    fn fulfillment_payment(fixture: &mut VRFFixture) -> U128 {
+        let prover = Prover::new([9u8; 32]).unwrap();
+        let pk = prover.public_key();
+        fixture.register_proving_key(fixture.owner, Key::from(fixture.oracle), pk.clone());
+        let sub_id = fixture.create_subscription(&[Key::from(fixture.consumer)]);
+        fixture.deposit_token(fixture.sub_owner, sub_id, DEPOSIT.into());
+        let kh = fixture.hash_of_key(pk);
+        let request = TestRequest {
+            pull: true,
+            callback_gas_limit: 0.into(),
+            ..TestRequest::new(kh, sub_id)
+        };
+        fixture.request_and_fulfill(&request, |event| {
+            prover.generate_proof(event.pre_seed, REQUEST_BLOCK_HASH)
+        });
+        U128::from(DEPOSIT) - fixture.get_subscription(sub_id).balance
+    }
+
+    /// Payment for a fulfillment without callback gas at `motes_per_unit`.
+    fn expected_payment(fixture: &mut VRFFixture, motes_per_unit: U256) -> U128 {
+        let (_, fee_ppm) = fixture.get_fee_tier(0);
+        let gas = U256::from(fixture.config.gas_after_payment_calculation.as_u128());
+        let payment = U256::from(DEPOSIT) * gas / motes_per_unit
+            + U256::from(1_000_000_000_000_u64) * U256::from(fee_ppm);
+        U128::from(payment.as_u128())
+    }
+
+    #[test]
+    fn test_fresh_answer_prices_payment() {
+        let (mut fixture, price_feed) = before_each();
+        fixture.set_feed_answer(price_feed, FEED_MOTES_PER_UNIT.into());
+        let payment = fulfillment_payment(&mut fixture);
+        assert_eq!(
+            payment,
+            expected_payment(&mut fixture, FEED_MOTES_PER_UNIT.into())
+        );
+    }
+
+    #[test]
+    fn test_stale_answer_falls_back() {
+        let (mut fixture, price_feed) = before_each();
+        fixture.set_feed_answer(price_feed, FEED_MOTES_PER_UNIT.into());
+        fixture.roll_timestamp(fixture.config.staleness_seconds + 1);
+        let payment = fulfillment_payment(&mut fixture);
+        let fallback = fixture.config.fallback_motes_per_unit_link;
+        assert_eq!(payment, expected_payment(&mut fixture, fallback));
+    }
+
+    #[test]
+    #[should_panic = "User(10025)"]
+    fn test_zero_answer_should_fail() {
+        let (mut fixture, price_feed) = before_each();
+        fixture.set_feed_answer(price_feed, U256::zero());
+        fulfillment_payment(&mut fixture);
+    }
+}
+
+#[cfg(test)]
+mod test_pull_requests {
+    use casper_types::{Key, U256};
+    use vrf_prover::Prover;
+
+    use super::{setup, TestRequest, VRFFixture, REQUEST_BLOCK_HASH};
+
+    /// Requests two words in pull mode from the consumer account and fulfills them, returning
+    /// the request id and the words.
+    fn request_and_fulfill(fixture: &mut VRFFixture) -> (U256, Vec<U256>) {
+        let prover = Prover::new([9u8; 32]).unwrap();
+        let pk = prover.public_key();
+        fixture.register_proving_key(fixture.owner, Key::from(fixture.oracle), pk.clone());
+        let sub_id = fixture.create_subscription(&[Key::from(fixture.consumer)]);
+        fixture.deposit_token(fixture.sub_owner, sub_id, 1000000000000000000_u128.into());
+        let kh = fixture.hash_of_key(pk);
+        let request = TestRequest {
+            pull: true,
+            callback_gas_limit: 0.into(),
+            num_words: 2,
+            ..TestRequest::new(kh, sub_id)
+        };
+        let (event, random_words) = fixture.request_and_fulfill(&request, |event| {
+            prover.generate_proof(event.pre_seed, REQUEST_BLOCK_HASH)
+        });
+        (event.request_id, random_words)
+    }
+
+    #[test]
+    fn test_account_reads_and_claims_words() {
+        let mut fixture = setup();
+        let (request_id, random_words) = request_and_fulfill(&mut fixture);
+        assert_eq!(fixture.get_random_words(request_id), random_words);
+
+        fixture.claim_random_words(fixture.consumer, request_id, true);
+        assert!(fixture.get_random_words(request_id).is_empty());
+    }
+
+    #[test]
+    #[should_panic = "User(10052)"]
+    fn test_cannot_claim_twice() {
+        let mut fixture = setup();
+        let (request_id, _) = request_and_fulfill(&mut fixture);
+        fixture.claim_random_words(fixture.consumer, request_id, true);
+        fixture.claim_random_words(fixture.consumer, request_id, true);
+    }
+
+    #[test]
+    #[should_panic = "User(10051)"]
+    fn test_only_requester_can_claim() {
+        let mut fixture = setup();
+        let (request_id, _) = request_and_fulfill(&mut fixture);
+        fixture.claim_random_words(fixture.random, request_id, true);
+    }
+}
+
+#[cfg(test)]
+mod test_block_hash_store {
+    use common::{constants::MAX_REQUEST_CONFIRMATIONS, data_types::BlockRef};
+
+    use super::{setup, REQUEST_BLOCK_HEIGHT};
+
+    #[test]
+    fn test_stores_block_ref_with_hash() {
+        let mut fixture = setup();
+        assert_eq!(fixture.get_block_ref(1_700_000_000), BlockRef::default());
+        fixture.set_block_hash(1_700_000_000, hex::encode([1u8; 32]));
+        assert_eq!(
+            fixture.get_block_ref(1_700_000_000),
+            BlockRef {
+                era_id: 0,
+                height: REQUEST_BLOCK_HEIGHT,
+                timestamp: 1_700_000_000,
+            }
+        );
+    }
+
+    #[test]
+    fn test_tracks_highest_block() {
+        let mut fixture = setup();
+        let tip = fixture.get_latest_block_ref();
+        assert_eq!(tip.height, REQUEST_BLOCK_HEIGHT + MAX_REQUEST_CONFIRMATIONS);
+
+        let block = BlockRef {
+            era_id: 1,
+            height: tip.height + 1,
+            timestamp: 1_700_000_000,
+        };
+        fixture.set_block_ref(block, hex::encode([1u8; 32]));
+        assert_eq!(fixture.get_latest_block_ref(), block);
+        // storing an older block keeps the tip
+        fixture.set_block_hash(1_600_000_000, hex::encode([2u8; 32]));
+        assert_eq!(fixture.get_latest_block_ref(), block);
+    }
+}
+
+#[cfg(test)]
+mod test_native_payment {
+    use casper_types::{Key, U128, U512};
+    use vrf_prover::Prover;
+
+    use super::{setup, TestRequest, VRFFixture, REQUEST_BLOCK_HASH};
+
+    const DEPOSIT: u64 = 150_000_000_000;
+
+    fn before_each() -> (VRFFixture, u64) {
+        let mut fixture = setup();
+        let sub_id = fixture.create_subscription(&[Key::from(fixture.consumer)]);
+        fixture.deposit_cspr(fixture.sub_owner, sub_id, DEPOSIT.into());
+        (fixture, sub_id)
+    }
+
+    /// Motes paid for a fulfillment without callback gas and flat fee.
+    fn native_payment(fixture: &VRFFixture) -> U512 {
+        U512::from(fixture.config.gas_after_payment_calculation.as_u128())
+    }
+
+    /// Requests one word in pull mode from the consumer account and fulfills it.
+    pub(super) fn request_and_fulfill(fixture: &mut VRFFixture, sub_id: u64) {
+        let prover = Prover::new([9u8; 32]).unwrap();
+        let pk = prover.public_key();
+        fixture.register_proving_key(fixture.owner, Key::from(fixture.oracle), pk.clone());
+        let kh = fixture.hash_of_key(pk);
+        let request = TestRequest {
+            pull: true,
+            callback_gas_limit: 0.into(),
+            ..TestRequest::new(kh, sub_id)
+        };
+        fixture.request_and_fulfill(&request, |event| {
+            prover.generate_proof(event.pre_seed, REQUEST_BLOCK_HASH)
+        });
+    }
+
+    #[test]
+    fn test_deposit_funds_native_balance() {
+        let (mut fixture, sub_id) = before_each();
+        fixture.deposit_cspr(fixture.random, sub_id, DEPOSIT.into());
+        let sub = fixture.get_subscription(sub_id);
+        assert_eq!(sub.native_balance, U512::from(DEPOSIT) * 2);
+        assert_eq!(sub.balance, U128::zero());
+        assert_eq!(fixture.get_total_native_balance(), U512::from(DEPOSIT) * 2);
+    }
+
+    #[test]
+    fn test_fulfillment_paid_in_cspr() {
+        let (mut fixture, sub_id) = before_each();
+        request_and_fulfill(&mut fixture, sub_id);
+        let payment = native_payment(&fixture);
+        let sub = fixture.get_subscription(sub_id);
+        assert_eq!(sub.native_balance, U512::from(DEPOSIT) - payment);
+        assert_eq!(sub.balance, U128::zero());
+        let oracle = Key::from(fixture.oracle);
+        assert_eq!(fixture.get_withdrawable_cspr(oracle), payment);
+        // the oracle's share stays in the coordinator until withdrawn
+        assert_eq!(fixture.get_total_native_balance(), U512::from(DEPOSIT));
+    }
+
+    #[test]
+    fn test_token_balance_pays_first() {
+        let (mut fixture, sub_id) = before_each();
+        let balance = U128::from(1_000_000_000_000_000_000_u128);
+        fixture.deposit_token(fixture.sub_owner, sub_id, balance);
+        request_and_fulfill(&mut fixture, sub_id);
+        let sub = fixture.get_subscription(sub_id);
+        assert_eq!(sub.native_balance, U512::from(DEPOSIT));
+        assert!(sub.balance < balance);
+        let oracle = Key::from(fixture.oracle);
+        assert_eq!(fixture.get_withdrawable_cspr(oracle), U512::zero());
+    }
+
+    #[test]
+    fn test_oracle_withdraws_cspr() {
+        let (mut fixture, sub_id) = before_each();
+        request_and_fulfill(&mut fixture, sub_id);
+        let payment = native_payment(&fixture);
+        let oracle = Key::from(fixture.oracle);
+        fixture.oracle_withdraw_cspr(fixture.oracle, oracle, payment);
+        assert_eq!(fixture.get_withdrawable_cspr(oracle), U512::zero());
+        assert_eq!(
+            fixture.get_total_native_balance(),
+            U512::from(DEPOSIT) - payment
+        );
+    }
+
+    #[test]
+    #[should_panic = "User(10060)"]
+    fn test_withdraw_to_contract_should_fail() {
+        let (mut fixture, sub_id) = before_each();
+        request_and_fulfill(&mut fixture, sub_id);
+        let payment = native_payment(&fixture);
+        fixture.oracle_withdraw_cspr(fixture.oracle, fixture.vrf, payment);
+    }
+
+    #[test]
+    #[should_panic = "User(10011)"]
+    fn test_withdraw_more_than_earned_should_fail() {
+        let (mut fixture, sub_id) = before_each();
+        request_and_fulfill(&mut fixture, sub_id);
+        let payment = native_payment(&fixture);
+        let oracle = Key::from(fixture.oracle);
+        fixture.oracle_withdraw_cspr(fixture.oracle, oracle, payment + 1);
+    }
+
+    #[test]
+    fn test_cancel_refunds_cspr() {
+        let (mut fixture, sub_id) = before_each();
+        fixture.cancel_subscription(fixture.sub_owner, sub_id, fixture.sub_owner_address);
+        assert_eq!(fixture.get_total_native_balance(), U512::zero());
+    }
+}
+
+#[cfg(test)]
+mod test_payment_tokens {
+    use casper_types::{
+        bytesrepr::{Bytes, ToBytes},
+        runtime_args, Key, RuntimeArgs, U128, U256,
+    };
+    use common::data_types::FeeConfig;
+
+    use super::{setup, test_native_payment::request_and_fulfill, VRFFixture};
+    use crate::utils;
+
+    const DEPOSIT: u128 = 1_000_000_000_000_000_000;
+    // a token worth ten times the coordinator's payment token
+    const FALLBACK_MOTES_PER_UNIT: u128 = 10_000_000_000_000;
+
+    /// Deploys a second CEP-18 token, accepts it and gives the sub owner some of it.
+    fn before_each() -> (VRFFixture, Key) {
+        let mut fixture = setup();
+        fixture.test_env.deploy_contract(
+            Some(fixture.owner),
+            "cep18.wasm",
+            runtime_args! {
+                "name" => "T1",
+                "symbol" => "T1",
+                "decimals" => 18u8,
+                "total_supply" => U256::from(DEPOSIT) * 10,
+                "minter_list" => vec![Key::from(fixture.owner)],
+                "admin_list" => vec![Key::from(fixture.owner)],
+                "enable_mint_burn" => 1u8,
+                "contract_name" => "T1",
+            },
+        );
+        let token = fixture.test_env.get_contract_package_hash(
+            fixture.owner,
+            &utils::get_contract_package_hash_key_cep18("T1".to_owned()),
+        );
+        fixture.set_payment_token_config(
+            fixture.owner,
+            token,
+            FALLBACK_MOTES_PER_UNIT.into(),
+            FeeConfig::default(),
+        );
+        fixture.test_env.transfer(
+            token,
+            fixture.owner,
+            fixture.sub_owner_address,
+            U256::from(DEPOSIT),
+        );
+        (fixture, token)
+    }
+
+    fn deposit(fixture: &mut VRFFixture, token: Key, sub_id: u64, amount: U128) {
+        fixture
+            .test_env
+            .approve(token, fixture.sub_owner, fixture.vrf, U256::MAX);
+        fixture.test_env.call_contract(
+            Some(fixture.sub_owner),
+            fixture.vrf.into_hash().unwrap().into(),
+            "deposit_token",
+            runtime_args! {
+                "sub_id" => sub_id,
+                "amount" => amount
+            },
+            true,
+        );
+    }
+
+    #[test]
+    fn test_subscription_pays_in_accepted_token() {
+        let (mut fixture, token) = before_each();
+        let sub_id = fixture.create_subscription_with_token(&[], Some(token));
+        deposit(&mut fixture, token, sub_id, DEPOSIT.into());
+        let sub = fixture.get_subscription(sub_id);
+        assert_eq!(sub.payment_token, token);
+        assert_eq!(sub.balance, U128::from(DEPOSIT));
+        assert_eq!(fixture.get_total_balance(token), U128::from(DEPOSIT));
+        let payment_token = fixture.payment_token;
+        assert_eq!(fixture.get_total_balance(payment_token), U128::zero());
+
+        fixture.cancel_subscription(fixture.sub_owner, sub_id, fixture.random_address);
+        assert_eq!(fixture.get_total_balance(token), U128::zero());
+        assert_eq!(
+            fixture.balance_of(token, fixture.random_address),
+            U128::from(DEPOSIT)
+        );
+    }
+
+    #[test]
+    fn test_fulfillment_paid_in_token() {
+        let (mut fixture, token) = before_each();
+        let sub_id =
+            fixture.create_subscription_with_token(&[Key::from(fixture.consumer)], Some(token));
+        deposit(&mut fixture, token, sub_id, DEPOSIT.into());
+        request_and_fulfill(&mut fixture, sub_id);
+
+        // no callback gas and flat fee, the gas after payment calculation at the token price
+        let payment = U128::from(
+            1_000_000_000_000_000_000_u128 * fixture.config.gas_after_payment_calculation.as_u128()
+                / FALLBACK_MOTES_PER_UNIT,
+        );
+        assert_eq!(
+            fixture.get_subscription(sub_id).balance,
+            U128::from(DEPOSIT) - payment
+        );
+        let oracle = Key::from(fixture.oracle);
+        assert_eq!(fixture.get_withdrawable_token(oracle, token), payment);
+        let payment_token = fixture.payment_token;
+        assert_eq!(
+            fixture.get_withdrawable_token(oracle, payment_token),
+            U128::zero()
+        );
+    }
+
+    #[test]
+    #[should_panic = "User(10061)"]
+    fn test_token_not_accepted_should_fail() {
+        let (mut fixture, _) = before_each();
+        let random = fixture.random_address;
+        fixture.create_subscription_with_token(&[], Some(random));
+    }
+
+    #[test]
+    #[should_panic = "User(10061)"]
+    fn test_removed_token_should_fail() {
+        let (mut fixture, token) = before_each();
+        fixture.remove_payment_token(fixture.owner, token);
+        fixture.create_subscription_with_token(&[], Some(token));
+    }
+
+    #[test]
+    fn test_removed_token_keeps_subscriptions() {
+        let (mut fixture, token) = before_each();
+        let sub_id = fixture.create_subscription_with_token(&[], Some(token));
+        fixture.remove_payment_token(fixture.owner, token);
+        deposit(&mut fixture, token, sub_id, DEPOSIT.into());
+        assert_eq!(
+            fixture.get_subscription(sub_id).balance,
+            U128::from(DEPOSIT)
+        );
+    }
+
+    #[test]
+    #[should_panic = "User(10002)"]
+    fn test_only_owner_can_accept_tokens() {
+        let (mut fixture, token) = before_each();
+        fixture.set_payment_token_config(
+            fixture.random,
+            token,
+            FALLBACK_MOTES_PER_UNIT.into(),
+            FeeConfig::default(),
+        );
+    }
+
+    #[test]
+    #[should_panic = "User(10062)"]
+    fn test_on_token_transfer_of_other_token_should_fail() {
+        let (mut fixture, token) = before_each();
+        let sub_id = fixture.create_subscription_with_token(&[], Some(token));
+        // the random account stands in for the coordinator's payment token
+        fixture.test_env.call_contract(
+            Some(fixture.owner),
+            fixture.vrf.into_hash().unwrap().into(),
+            "set_payment_token",
+            runtime_args! {
+                "payment_token" => Key::from(fixture.random)
+            },
+            true,
+        );
+        let data = Bytes::from(sub_id.to_bytes().unwrap());
+        fixture.on_token_transfer(fixture.random, U256::from(1000), data);
+    }
+
+    /// Accepts the random account as a token, standing in for it, and returns a subscription
+    /// paying in it with the on_token_transfer data funding that subscription.
+    fn random_account_token(fixture: &mut VRFFixture) -> (u64, Bytes) {
+        let token = Key::from(fixture.random);
+        fixture.set_payment_token_config(
+            fixture.owner,
+            token,
+            FALLBACK_MOTES_PER_UNIT.into(),
+            FeeConfig::default(),
+        );
+        let sub_id = fixture.create_subscription_with_token(&[], Some(token));
+        (sub_id, Bytes::from(sub_id.to_bytes().unwrap()))
+    }
+
+    #[test]
+    fn test_on_token_transfer_of_accepted_token() {
+        let (mut fixture, _) = before_each();
+        let (sub_id, data) = random_account_token(&mut fixture);
+        fixture.on_token_transfer(fixture.random, U256::from(1000), data);
+        assert_eq!(fixture.get_subscription(sub_id).balance, U128::from(1000));
+    }
+
+    #[test]
+    #[should_panic = "User(10014)"]
+    fn test_on_token_transfer_of_removed_token_should_fail() {
+        let (mut fixture, _) = before_each();
+        let (_, data) = random_account_token(&mut fixture);
+        fixture.remove_payment_token(fixture.owner, Key::from(fixture.random));
+        fixture.on_token_transfer(fixture.random, U256::from(1000), data);
+    }
+
+    #[test]
+    fn test_default_token_subscription_keeps_its_token() {
+        let (mut fixture, _) = before_each();
+        let sub_id = fixture.create_subscription(&[]);
+        assert_eq!(
+            fixture.get_subscription(sub_id).payment_token,
+            fixture.payment_token
+        );
+        fixture.test_env.call_contract(
+            Some(fixture.owner),
+            fixture.vrf.into_hash().unwrap().into(),
+            "set_payment_token",
+            runtime_args! {
+                "payment_token" => Key::from(fixture.random)
+            },
+            true,
+        );
+        assert_eq!(
+            fixture.get_subscription(sub_id).payment_token,
+            fixture.payment_token
+        );
+        let payment_token = fixture.payment_token;
+        deposit(&mut fixture, payment_token, sub_id, DEPOSIT.into());
+        assert_eq!(
+            fixture.get_subscription(sub_id).balance,
+            U128::from(DEPOSIT)
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_fee_tiers {
+    use common::{
+        constants::MAX_FEE_TIERS,
+        data_types::{FeeConfig, FeeTier},
+    };
+
+    use super::setup;
+
+    /// Tiers every ten requests, each a hundred ppm cheaper than the previous one.
+    fn fee_config(tiers: u64) -> FeeConfig {
+        FeeConfig {
+            tiers: (0..tiers)
+                .map(|i| FeeTier {
+                    reqs_threshold: i * 10,
+                    fulfillment_flat_fee_link_ppm: 10_000 - i * 100,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_get_fee_tier() {
+        let mut fixture = setup();
+        fixture.set_fee_config(fee_config(7));
+        assert_eq!(fixture.get_fee_tier(0), (0, 10_000));
+        assert_eq!(fixture.get_fee_tier(9), (0, 10_000));
+        assert_eq!(fixture.get_fee_tier(10), (1, 9900));
+        assert_eq!(fixture.get_fee_tier(35), (3, 9700));
+        assert_eq!(fixture.get_fee_tier(60), (6, 9400));
+        // counts above u32::MAX are not truncated
+        assert_eq!(fixture.get_fee_tier(5_000_000_000), (6, 9400));
+    }
+
+    #[test]
+    fn test_no_tiers_charge_no_fee() {
+        let mut fixture = setup();
+        assert_eq!(fixture.get_fee_tier(100), (0, 0));
+    }
+
+    #[test]
+    #[should_panic = "User(10063)"]
+    fn test_first_tier_must_start_at_zero() {
+        let mut fixture = setup();
+        let mut config = fee_config(3);
+        config.tiers.remove(0);
+        fixture.set_fee_config(config);
+    }
+
+    #[test]
+    #[should_panic = "User(10063)"]
+    fn test_thresholds_must_increase() {
+        let mut fixture = setup();
+        let mut config = fee_config(3);
+        config.tiers[2].reqs_threshold = config.tiers[1].reqs_threshold;
+        fixture.set_fee_config(config);
+    }
+
+    #[test]
+    #[should_panic = "User(10063)"]
+    fn test_too_many_tiers() {
+        let mut fixture = setup();
+        fixture.set_fee_config(fee_config(MAX_FEE_TIERS as u64 + 1));
+    }
 }
@@ -0,0 +1,470 @@
+use casper_types::{
+    bytesrepr::{Bytes, ToBytes},
+    runtime_args, HashAddr, Key, RuntimeArgs, U128, U256,
+};
+use common::{
+    data_types::{RequestCommitment, WrapperConfig},
+    events::RandomWordsRequested,
+};
+use vrf_prover::Prover;
+
+use crate::{
+    utils::{self, key_to_contract_package_hash},
+    vrf::{setup, VRFFixture},
+};
+
+const SK: [u8; 32] = [5u8; 32];
+const WRAPPER_GAS_OVERHEAD: u128 = 1_000_000_000;
+const WRAPPER_PREMIUM_PERCENTAGE: u8 = 10;
+const MAX_NUM_WORDS: u64 = 10;
+// callback gas limit coin-flip requests with
+const CALLBACK_GAS_LIMIT: u128 = 100_000_000;
+
+pub struct WrapperFixture {
+    pub vrf: VRFFixture,
+    pub prover: Prover,
+    pub key_hash: HashAddr,
+    pub wrapper: Key,
+}
+
+pub fn setup_wrapper() -> WrapperFixture {
+    let mut vrf = setup();
+    let prover = Prover::new(SK).unwrap();
+    let pk = prover.public_key();
+    vrf.register_proving_key(vrf.owner, Key::from(vrf.oracle), pk.clone());
+    let key_hash = vrf.hash_of_key(pk);
+
+    vrf.test_env.deploy_contract(
+        Some(vrf.owner),
+        "vrf-wrapper.wasm",
+        runtime_args! {
+            "contract_name" => "vrf-wrapper",
+            "vrf_coordinator" => vrf.vrf,
+            "key_hash" => key_hash,
+            "wrapper_gas_overhead" => U128::from(WRAPPER_GAS_OVERHEAD),
+            "wrapper_premium_percentage" => WRAPPER_PREMIUM_PERCENTAGE,
+            "max_num_words" => MAX_NUM_WORDS
+        },
+    );
+    let wrapper = vrf.test_env.get_contract_package_hash(
+        vrf.owner,
+        &utils::get_contract_package_hash_key("vrf-wrapper".to_owned()),
+    );
+
+    WrapperFixture {
+        vrf,
+        prover,
+        key_hash,
+        wrapper,
+    }
+}
+
+impl WrapperFixture {
+    pub fn get_sub_id(&mut self) -> u64 {
+        self.vrf
+            .test_env
+            .call_view_function(self.wrapper, "get_sub_id", runtime_args! {})
+    }
+
+    pub fn get_config(&mut self) -> WrapperConfig {
+        self.vrf
+            .test_env
+            .call_view_function(self.wrapper, "get_config", runtime_args! {})
+    }
+
+    pub fn calculate_request_price(&mut self, callback_gas_limit: U128) -> U128 {
+        self.vrf.test_env.call_view_function(
+            self.wrapper,
+            "calculate_request_price",
+            runtime_args! {
+                "callback_gas_limit" => callback_gas_limit
+            },
+        )
+    }
+
+    pub fn set_config(&mut self, caller: Key, max_num_words: u64, success: bool) {
+        self.vrf.test_env.call_contract(
+            Some(caller.into_account().unwrap()),
+            key_to_contract_package_hash(self.wrapper),
+            "set_config",
+            runtime_args! {
+                "key_hash" => self.key_hash,
+                "wrapper_gas_overhead" => U128::from(WRAPPER_GAS_OVERHEAD),
+                "wrapper_premium_percentage" => WRAPPER_PREMIUM_PERCENTAGE,
+                "max_num_words" => max_num_words
+            },
+            success,
+        );
+    }
+
+    /// Deploys coin-flip on top of the wrapper and funds it with `balance` payment tokens.
+    pub fn deploy_coin_flip(&mut self, balance: U256) -> Key {
+        self.vrf.test_env.deploy_contract(
+            Some(self.vrf.owner),
+            "coin-flip.wasm",
+            runtime_args! {
+                "contract_name" => "coin-flip",
+                "vrf_wrapper" => self.wrapper
+            },
+        );
+        let coin_flip = self.vrf.test_env.get_contract_package_hash(
+            self.vrf.owner,
+            &utils::get_contract_package_hash_key("coin-flip".to_owned()),
+        );
+        self.vrf
+            .test_env
+            .transfer(self.vrf.payment_token, self.vrf.owner, coin_flip, balance);
+        coin_flip
+    }
+
+    pub fn withdraw(&mut self, caller: Key, to: Key, success: bool) {
+        self.vrf.test_env.call_contract(
+            Some(caller.into_account().unwrap()),
+            key_to_contract_package_hash(self.wrapper),
+            "withdraw",
+            runtime_args! {
+                "to" => to
+            },
+            success,
+        );
+    }
+
+    /// Flips through `coin_flip` from the consumer account and fulfills the request.
+    pub fn flip_and_fulfill(&mut self, coin_flip: Key) -> Vec<U256> {
+        self.vrf.test_env.call_contract(
+            Some(self.vrf.consumer),
+            key_to_contract_package_hash(coin_flip),
+            "flip",
+            runtime_args! {},
+            true,
+        );
+        let event_index = self.vrf.test_env.get_event_length(self.vrf.vrf) - 1;
+        self.fulfill(event_index as usize)
+    }
+
+    pub fn forward_random_words(&mut self, caller: Key, request_id: U256, success: bool) {
+        self.vrf.test_env.call_contract(
+            Some(caller.into_account().unwrap()),
+            key_to_contract_package_hash(self.wrapper),
+            "forward_random_words",
+            runtime_args! {
+                "request_id" => request_id
+            },
+            success,
+        );
+    }
+
+    /// Fulfills the request the wrapper made with event `event_index` as the oracle would.
+    pub fn fulfill(&mut self, event_index: usize) -> Vec<U256> {
+        self.fulfill_with(event_index, false)
+    }
+
+    /// Fulfills the request of event `event_index`, leaving the words in the coordinator when
+    /// `skip_callback` is set.
+    pub fn fulfill_with(&mut self, event_index: usize, skip_callback: bool) -> Vec<U256> {
+        let event: RandomWordsRequested = self
+            .vrf
+            .test_env
+            .get_event(self.vrf.vrf, event_index)
+            .unwrap();
+        assert_eq!(event.sender, self.wrapper);
+
+        let block_hash: HashAddr = [1u8; 32];
+        let block = self
+            .vrf
+            .set_block_hash(event.block.timestamp, hex::encode(block_hash));
+        let proof = self.prover.generate_proof(event.pre_seed, block_hash);
+        let random_words =
+            vrf_prover::random_words(vrf_prover::random_value(&proof), event.num_words as u32);
+        let rc = RequestCommitment {
+            block,
+            request_confirmations: event.minimum_request_confirmations,
+            sub_id: event.sub_id,
+            callback_gas_limit: event.callback_cas_limit,
+            num_words: event.num_words as u32,
+            sender: self.wrapper,
+        };
+        self.vrf.fulfill_random_words_with(
+            self.vrf.oracle,
+            Bytes::from(proof.to_bytes().unwrap()),
+            Bytes::from(rc.to_bytes().unwrap()),
+            skip_callback,
+            None,
+        );
+        random_words
+    }
+}
+
+/// Coordinator payment for `gas` with the fixture's config and zero flat fees.
+fn payment(fixture: &WrapperFixture, gas: u128) -> U256 {
+    let config = fixture.vrf.config;
+    let gas = U256::from(config.gas_after_payment_calculation.as_u128()) + U256::from(gas);
+    U256::from(1_000_000_000_000_000_000_u128) * gas / config.fallback_motes_per_unit_link
+}
+
+fn expected_price(fixture: &WrapperFixture, callback_gas_limit: u128) -> U128 {
+    let price = payment(fixture, callback_gas_limit + WRAPPER_GAS_OVERHEAD)
+        * U256::from(100 + WRAPPER_PREMIUM_PERCENTAGE as u64)
+        / U256::from(100);
+    U128::from(price.as_u128())
+}
+
+#[cfg(test)]
+mod test_wrapper {
+    use casper_types::{runtime_args, Key, RuntimeArgs, U128, U256};
+    use common::{
+        data_types::{FeeConfig, FeeTier},
+        events::RandomWordsRequested,
+    };
+
+    use super::{
+        expected_price, payment, setup_wrapper, WrapperFixture, CALLBACK_GAS_LIMIT, MAX_NUM_WORDS,
+        WRAPPER_GAS_OVERHEAD,
+    };
+    use crate::utils::key_to_contract_package_hash;
+
+    const HEADS: u8 = 1;
+    const TAILS: u8 = 2;
+    const FLIP_IN_PROGRESS: u8 = 3;
+
+    fn get_result(fixture: &mut WrapperFixture, coin_flip: Key, player: Key) -> u8 {
+        fixture.vrf.test_env.call_view_function(
+            coin_flip,
+            "get_result",
+            runtime_args! {
+                "player" => player
+            },
+        )
+    }
+
+    #[test]
+    fn test_wrapper_owns_subscription() {
+        let mut fixture = setup_wrapper();
+        let sub_id = fixture.get_sub_id();
+        let sub = fixture.vrf.get_subscription(sub_id);
+        assert_eq!(sub.owner, fixture.wrapper);
+        assert_eq!(sub.consumers, vec![fixture.wrapper]);
+        assert_eq!(sub.balance, U128::zero());
+    }
+
+    #[test]
+    fn test_calculate_request_price() {
+        let mut fixture = setup_wrapper();
+        let price = fixture.calculate_request_price(CALLBACK_GAS_LIMIT.into());
+        assert_eq!(price, expected_price(&fixture, CALLBACK_GAS_LIMIT));
+        assert!(price > fixture.calculate_request_price(0.into()));
+    }
+
+    #[test]
+    fn test_price_follows_coordinator_feed() {
+        let mut fixture = setup_wrapper();
+        let price_feed = fixture.vrf.deploy_test_price_feed();
+        let motes_per_unit = fixture.vrf.config.fallback_motes_per_unit_link * 2;
+        fixture.vrf.set_feed_answer(price_feed, motes_per_unit);
+        // the quote is priced as the coordinator bills, at the feed answer
+        fixture.vrf.config.fallback_motes_per_unit_link = motes_per_unit;
+        assert_eq!(
+            fixture.calculate_request_price(CALLBACK_GAS_LIMIT.into()),
+            expected_price(&fixture, CALLBACK_GAS_LIMIT)
+        );
+    }
+
+    #[test]
+    #[should_panic = "User(10025)"]
+    fn test_zero_feed_answer_should_fail() {
+        let mut fixture = setup_wrapper();
+        let price_feed = fixture.vrf.deploy_test_price_feed();
+        fixture.vrf.set_feed_answer(price_feed, U256::zero());
+        fixture.calculate_request_price(CALLBACK_GAS_LIMIT.into());
+    }
+
+    #[test]
+    fn test_flip_through_wrapper() {
+        let mut fixture = setup_wrapper();
+        let balance = U256::from(1_000_000_000_000_000_000_u128);
+        let coin_flip = fixture.deploy_coin_flip(balance);
+        let player = Key::from(fixture.vrf.consumer);
+        let price = expected_price(&fixture, CALLBACK_GAS_LIMIT);
+
+        let event_length = fixture.vrf.test_env.get_event_length(fixture.vrf.vrf);
+        fixture.vrf.test_env.call_contract(
+            Some(fixture.vrf.consumer),
+            key_to_contract_package_hash(coin_flip),
+            "flip",
+            runtime_args! {},
+            true,
+        );
+        assert_eq!(
+            get_result(&mut fixture, coin_flip, player),
+            FLIP_IN_PROGRESS
+        );
+        let payment_token = fixture.vrf.payment_token;
+        assert_eq!(
+            fixture.vrf.balance_of(payment_token, coin_flip),
+            U128::from((balance - U256::from(price.as_u128())).as_u128())
+        );
+        let sub_id = fixture.get_sub_id();
+        assert_eq!(fixture.vrf.get_subscription(sub_id).balance, price);
+
+        // the coordinator event is the last one, after the deposit of the price
+        let event_index = fixture.vrf.test_env.get_event_length(fixture.vrf.vrf) - 1;
+        assert!(event_index > event_length);
+        let random_words = fixture.fulfill(event_index as usize);
+
+        let expected = if (random_words[0] % 2).is_zero() {
+            HEADS
+        } else {
+            TAILS
+        };
+        assert_eq!(get_result(&mut fixture, coin_flip, player), expected);
+        // the premium is left in the wrapper's subscription
+        let paid = payment(&fixture, CALLBACK_GAS_LIMIT + WRAPPER_GAS_OVERHEAD);
+        assert_eq!(
+            fixture.vrf.get_subscription(sub_id).balance,
+            U128::from(price.as_u128() - paid.as_u128())
+        );
+    }
+
+    #[test]
+    fn test_forward_words_of_skipped_callback() {
+        let mut fixture = setup_wrapper();
+        let coin_flip = fixture.deploy_coin_flip(U256::from(1_000_000_000_000_000_000_u128));
+        let player = Key::from(fixture.vrf.consumer);
+        fixture.vrf.test_env.call_contract(
+            Some(fixture.vrf.consumer),
+            key_to_contract_package_hash(coin_flip),
+            "flip",
+            runtime_args! {},
+            true,
+        );
+        let event_index = fixture.vrf.test_env.get_event_length(fixture.vrf.vrf) - 1;
+        let event: RandomWordsRequested = fixture
+            .vrf
+            .test_env
+            .get_event(fixture.vrf.vrf, event_index as usize)
+            .unwrap();
+        let random_words = fixture.fulfill_with(event_index as usize, true);
+        assert_eq!(
+            get_result(&mut fixture, coin_flip, player),
+            FLIP_IN_PROGRESS
+        );
+
+        let random = Key::from(fixture.vrf.random);
+        fixture.forward_random_words(random, event.request_id, true);
+        let expected = if (random_words[0] % 2).is_zero() {
+            HEADS
+        } else {
+            TAILS
+        };
+        assert_eq!(get_result(&mut fixture, coin_flip, player), expected);
+        // the words were handed over, there is nothing left to forward
+        fixture.forward_random_words(random, event.request_id, false);
+    }
+
+    #[test]
+    #[should_panic = "User(10049)"]
+    fn test_account_cannot_request() {
+        let mut fixture = setup_wrapper();
+        fixture.vrf.test_env.call_contract(
+            Some(fixture.vrf.consumer),
+            key_to_contract_package_hash(fixture.wrapper),
+            "request_random_words",
+            runtime_args! {
+                "callback_gas_limit" => U128::from(CALLBACK_GAS_LIMIT),
+                "request_confirmations" => 3u64,
+                "num_words" => 1u64
+            },
+            true,
+        );
+    }
+
+    #[test]
+    #[should_panic = "User(10022)"]
+    fn test_num_words_too_big() {
+        let mut fixture = setup_wrapper();
+        let owner = Key::from(fixture.vrf.owner);
+        fixture.set_config(owner, 0, true);
+        let coin_flip = fixture.deploy_coin_flip(U256::from(1_000_000_000_000_000_000_u128));
+        fixture.vrf.test_env.call_contract(
+            Some(fixture.vrf.consumer),
+            key_to_contract_package_hash(coin_flip),
+            "flip",
+            runtime_args! {},
+            true,
+        );
+    }
+
+    #[test]
+    fn test_price_uses_subscription_fee_tier() {
+        let mut fixture = setup_wrapper();
+        fixture.vrf.set_fee_config(FeeConfig {
+            tiers: vec![
+                FeeTier {
+                    reqs_threshold: 0,
+                    fulfillment_flat_fee_link_ppm: 1000,
+                },
+                FeeTier {
+                    reqs_threshold: 1,
+                    fulfillment_flat_fee_link_ppm: 500,
+                },
+            ],
+        });
+        let flat_fee = |ppm: u128| U128::from(1_000_000_000_000_u128 * ppm);
+        let price = expected_price(&fixture, CALLBACK_GAS_LIMIT);
+        assert_eq!(
+            fixture.calculate_request_price(CALLBACK_GAS_LIMIT.into()),
+            price + flat_fee(1000)
+        );
+
+        let coin_flip = fixture.deploy_coin_flip(U256::from(1_000_000_000_000_000_000_u128));
+        fixture.flip_and_fulfill(coin_flip);
+        assert_eq!(
+            fixture.calculate_request_price(CALLBACK_GAS_LIMIT.into()),
+            price + flat_fee(500)
+        );
+    }
+
+    #[test]
+    fn test_owner_withdraws_subscription_balance() {
+        let mut fixture = setup_wrapper();
+        let coin_flip = fixture.deploy_coin_flip(U256::from(1_000_000_000_000_000_000_u128));
+        fixture.flip_and_fulfill(coin_flip);
+        let sub_id = fixture.get_sub_id();
+        let balance = fixture.vrf.get_subscription(sub_id).balance;
+        assert!(!balance.is_zero());
+
+        let owner = Key::from(fixture.vrf.owner);
+        let to = fixture.vrf.random_address;
+        let payment_token = fixture.vrf.payment_token;
+        let to_balance = fixture.vrf.balance_of(payment_token, to);
+        fixture.withdraw(owner, to, true);
+        assert_eq!(
+            fixture.vrf.balance_of(payment_token, to),
+            to_balance + balance
+        );
+        // requests go on from a new subscription
+        let new_sub_id = fixture.get_sub_id();
+        assert_ne!(new_sub_id, sub_id);
+        let sub = fixture.vrf.get_subscription(new_sub_id);
+        assert_eq!(sub.owner, fixture.wrapper);
+        assert_eq!(sub.consumers, vec![fixture.wrapper]);
+        fixture.flip_and_fulfill(coin_flip);
+    }
+
+    #[test]
+    fn test_only_owner_can_withdraw() {
+        let mut fixture = setup_wrapper();
+        let sub_id = fixture.get_sub_id();
+        let random = Key::from(fixture.vrf.random);
+        fixture.withdraw(random, random, false);
+        assert_eq!(fixture.get_sub_id(), sub_id);
+    }
+
+    #[test]
+    fn test_only_owner_can_set_config() {
+        let mut fixture = setup_wrapper();
+        let random = Key::from(fixture.vrf.random);
+        fixture.set_config(random, 1, false);
+        assert_eq!(fixture.get_config().max_num_words, MAX_NUM_WORDS);
+    }
+}
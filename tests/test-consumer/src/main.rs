@@ -0,0 +1,102 @@
+#![no_std]
+#![no_main]
+
+extern crate alloc;
+
+use alloc::{format, string::String, vec, vec::Vec};
+use casper_contract::{
+    contract_api::{runtime, storage},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{
+    contracts::NamedKeys, runtime_args, CLType, CLTyped, CLValue, EntryPoint, EntryPointAccess,
+    EntryPointType, EntryPoints, HashAddr, Key, Parameter, RuntimeArgs, U128, U256,
+};
+use common::helpers;
+
+const TEST_CONSUMER: &str = "test_consumer";
+const VRF_COORDINATOR: &str = "vrf_coordinator";
+const RANDOM_WORDS: &str = "random_words";
+
+#[no_mangle]
+extern "C" fn request_random_words() {
+    let vrf_coordinator: Key = helpers::get_key(VRF_COORDINATOR).unwrap_or_revert();
+    let key_hash: HashAddr = runtime::get_named_arg("key_hash");
+    let sub_id: u64 = runtime::get_named_arg("sub_id");
+    let request_confirmations: u64 = runtime::get_named_arg("request_confirmations");
+    let callback_gas_limit: U128 = runtime::get_named_arg("callback_gas_limit");
+    let num_words: u64 = runtime::get_named_arg("num_words");
+    runtime::call_versioned_contract::<U256>(
+        vrf_coordinator.into_hash().unwrap_or_revert().into(),
+        None,
+        "request_random_words",
+        runtime_args! {
+            "key_hash" => key_hash,
+            "sub_id" => sub_id,
+            "request_confirmations" => request_confirmations,
+            "callback_gas_limit" => callback_gas_limit,
+            "num_words" => num_words
+        },
+    );
+}
+
+#[no_mangle]
+extern "C" fn raw_fulfill_random_words() {
+    let random_words: Vec<U256> = runtime::get_named_arg(RANDOM_WORDS);
+    helpers::set_key(RANDOM_WORDS, random_words);
+}
+
+#[no_mangle]
+extern "C" fn get_random_words() {
+    let random_words: Vec<U256> = helpers::get_key(RANDOM_WORDS).unwrap_or_default();
+    runtime::ret(CLValue::from_t(random_words).unwrap_or_revert())
+}
+
+#[no_mangle]
+pub extern "C" fn call() {
+    let vrf_coordinator: Key = runtime::get_named_arg(VRF_COORDINATOR);
+    let mut named_keys = NamedKeys::new();
+    named_keys.insert(
+        String::from(VRF_COORDINATOR),
+        storage::new_uref(vrf_coordinator).into(),
+    );
+
+    let mut entry_points = EntryPoints::new();
+    entry_points.add_entry_point(EntryPoint::new(
+        String::from("request_random_words"),
+        vec![
+            Parameter::new("key_hash", HashAddr::cl_type()),
+            Parameter::new("sub_id", CLType::U64),
+            Parameter::new("request_confirmations", CLType::U64),
+            Parameter::new("callback_gas_limit", CLType::U128),
+            Parameter::new("num_words", CLType::U64),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        String::from("raw_fulfill_random_words"),
+        vec![
+            Parameter::new("request_id", CLType::U256),
+            Parameter::new(RANDOM_WORDS, Vec::<U256>::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        String::from("get_random_words"),
+        vec![],
+        Vec::<U256>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    storage::new_contract(
+        entry_points,
+        Some(named_keys),
+        Some(format!("{}_package_hash", TEST_CONSUMER)),
+        None,
+    );
+}
@@ -0,0 +1,206 @@
+//! In-memory Casper network the integration tests deploy and call contracts on.
+//!
+//! Wasm files are looked up by name in `wasm/` under the working directory, where
+//! `make build-all-contracts` copies them, and in the workspace `target` directory.
+use casper_engine_test_support::{
+    ExecuteRequestBuilder, InMemoryWasmTestBuilder, DEFAULT_ACCOUNT_ADDR,
+    PRODUCTION_RUN_GENESIS_REQUEST,
+};
+use casper_types::{
+    account::AccountHash,
+    bytesrepr::{Bytes, FromBytes, ToBytes},
+    runtime_args, ContractHash, ContractPackageHash, Key, PublicKey, RuntimeArgs,
+    SecretKey, StoredValue, ED25519_TAG, U256, U512,
+};
+use rand::Rng;
+
+/// Motes each account of a [`TestEnv`] starts with.
+pub const ACCOUNT_BALANCE: u64 = 10_000_000_000_000_000;
+/// Session reading the return value of an entry point into the caller's `result` named key.
+pub const GET_SESSION_WASM: &str = "get-session.wasm";
+
+/// Generates the account hash of a fresh key pair of the algorithm of `tag`.
+pub fn generate_random_account(tag: u8) -> AccountHash {
+    let bytes: [u8; 32] = rand::thread_rng().gen();
+    let secret_key = if tag == ED25519_TAG {
+        SecretKey::ed25519_from_bytes(bytes)
+    } else {
+        SecretKey::secp256k1_from_bytes(bytes)
+    }
+    .unwrap();
+    PublicKey::from(&secret_key).to_account_hash()
+}
+
+pub struct TestEnv {
+    builder: InMemoryWasmTestBuilder,
+    block_time: u64,
+}
+
+impl TestEnv {
+    /// Runs genesis and funds `accounts` with [`ACCOUNT_BALANCE`] motes each. Every deploy is
+    /// executed at `block_time`.
+    pub fn new(accounts: &[AccountHash], block_time: u64) -> Self {
+        let mut builder = InMemoryWasmTestBuilder::default();
+        builder.run_genesis(&PRODUCTION_RUN_GENESIS_REQUEST).commit();
+        let mut env = Self {
+            builder,
+            block_time,
+        };
+        for account in accounts {
+            let request = ExecuteRequestBuilder::transfer(
+                *DEFAULT_ACCOUNT_ADDR,
+                runtime_args! {
+                    "amount" => U512::from(ACCOUNT_BALANCE),
+                    "target" => *account,
+                    "id" => Option::<u64>::None
+                },
+            )
+            .with_block_time(env.block_time)
+            .build();
+            env.builder.exec(request).expect_success().commit();
+        }
+        env
+    }
+
+    /// Runs session `wasm` from `sender`, the genesis account when `None`, and expects it to
+    /// succeed.
+    pub fn deploy_contract(&mut self, sender: Option<AccountHash>, wasm: &str, args: RuntimeArgs) {
+        let sender = sender.unwrap_or(*DEFAULT_ACCOUNT_ADDR);
+        let request = ExecuteRequestBuilder::standard(sender, wasm, args)
+            .with_block_time(self.block_time)
+            .build();
+        self.builder.exec(request).expect_success().commit();
+    }
+
+    /// Calls `entry_point` of the latest version of `package` from `sender`. Panics with the
+    /// execution error when the outcome is not the `success` expected.
+    pub fn call_contract(
+        &mut self,
+        sender: Option<AccountHash>,
+        package: ContractPackageHash,
+        entry_point: &str,
+        args: RuntimeArgs,
+        success: bool,
+    ) {
+        let sender = sender.unwrap_or(*DEFAULT_ACCOUNT_ADDR);
+        let request = ExecuteRequestBuilder::versioned_contract_call_by_hash(
+            sender,
+            package,
+            None,
+            entry_point,
+            args,
+        )
+        .with_block_time(self.block_time)
+        .build();
+        self.builder.exec(request);
+        if success {
+            self.builder.expect_success();
+        } else {
+            self.builder.expect_failure();
+        }
+        self.builder.commit();
+    }
+
+    /// Returns what `entry_point` of `package` returns for `args`, read through
+    /// [`GET_SESSION_WASM`] run by the genesis account, which stores the serialized value.
+    pub fn call_view_function<T: FromBytes>(
+        &mut self,
+        package: Key,
+        entry_point: &str,
+        args: RuntimeArgs,
+    ) -> T {
+        self.deploy_contract(
+            None,
+            GET_SESSION_WASM,
+            runtime_args! {
+                "contract_package_hash" => package,
+                "entry_point" => entry_point,
+                "args" => Bytes::from(args.to_bytes().unwrap())
+            },
+        );
+        let value = self
+            .builder
+            .query(None, Key::from(*DEFAULT_ACCOUNT_ADDR), &["result".to_owned()])
+            .unwrap();
+        let bytes: Bytes = match value {
+            StoredValue::CLValue(value) => value.into_t().unwrap(),
+            _ => panic!("result of {} is not a CLValue", entry_point),
+        };
+        T::from_bytes(&bytes).unwrap().0
+    }
+
+    /// Returns the key `account` stored under `name`.
+    pub fn get_contract_package_hash(&self, account: AccountHash, name: &str) -> Key {
+        *self
+            .builder
+            .get_expected_account(account)
+            .named_keys()
+            .get(name)
+            .unwrap_or_else(|| panic!("{} has no named key {}", account, name))
+    }
+
+    /// Transfers `amount` CEP-18 `token` from `from` to `to`.
+    pub fn transfer(&mut self, token: Key, from: AccountHash, to: Key, amount: U256) {
+        self.call_contract(
+            Some(from),
+            token.into_hash().unwrap().into(),
+            "transfer",
+            runtime_args! {
+                "recipient" => to,
+                "amount" => amount
+            },
+            true,
+        );
+    }
+
+    /// Approves `spender` to transfer `amount` CEP-18 `token` of `owner`.
+    pub fn approve(&mut self, token: Key, owner: AccountHash, spender: Key, amount: U256) {
+        self.call_contract(
+            Some(owner),
+            token.into_hash().unwrap().into(),
+            "approve",
+            runtime_args! {
+                "spender" => spender,
+                "amount" => amount
+            },
+            true,
+        );
+    }
+
+    /// Number of events the latest version of `package` emitted.
+    pub fn get_event_length(&mut self, package: Key) -> u32 {
+        let length = self.contract_named_key(package, "__events_length");
+        match self.builder.query(None, length, &[]).unwrap() {
+            StoredValue::CLValue(value) => value.into_t().unwrap(),
+            _ => panic!("__events_length is not a CLValue"),
+        }
+    }
+
+    /// Event `index` emitted by the latest version of `package`.
+    pub fn get_event<T: FromBytes>(&mut self, package: Key, index: usize) -> Option<T> {
+        let events = self.contract_named_key(package, "__events");
+        let event = Key::dictionary(*events.as_uref()?, index.to_string().as_bytes());
+        let bytes: Bytes = match self.builder.query(None, event, &[]).ok()? {
+            StoredValue::CLValue(value) => value.into_t().ok()?,
+            _ => return None,
+        };
+        T::from_bytes(&bytes).ok().map(|(event, _)| event)
+    }
+
+    fn contract_named_key(&self, package: Key, name: &str) -> Key {
+        let package_hash = ContractPackageHash::new(package.into_hash().unwrap());
+        let contract_hash: ContractHash = self
+            .builder
+            .get_contract_package(package_hash)
+            .unwrap()
+            .current_contract_hash()
+            .unwrap();
+        *self
+            .builder
+            .get_contract(contract_hash)
+            .unwrap()
+            .named_keys()
+            .get(name)
+            .unwrap_or_else(|| panic!("contract has no named key {}", name))
+    }
+}
@@ -0,0 +1,63 @@
+#![no_std]
+#![no_main]
+
+extern crate alloc;
+
+use alloc::{format, string::String, vec};
+use casper_contract::{
+    contract_api::{runtime, storage},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{
+    contracts::NamedKeys, CLType, CLTyped, CLValue, EntryPoint, EntryPointAccess, EntryPointType,
+    EntryPoints, Parameter, U256,
+};
+use common::helpers::{self, current_block_timestamp};
+
+const TEST_PRICE_FEED: &str = "test_price_feed";
+const ANSWER: &str = "answer";
+const UPDATED_AT: &str = "updated_at";
+
+#[no_mangle]
+extern "C" fn set_latest_round_data() {
+    let answer: U256 = runtime::get_named_arg(ANSWER);
+    helpers::set_key(ANSWER, answer);
+    helpers::set_key(UPDATED_AT, current_block_timestamp());
+}
+
+#[no_mangle]
+extern "C" fn latest_round_data() {
+    let answer: U256 = helpers::get_key(ANSWER).unwrap_or_default();
+    let updated_at: u64 = helpers::get_key(UPDATED_AT).unwrap_or_default();
+    runtime::ret(CLValue::from_t((answer, updated_at)).unwrap_or_revert())
+}
+
+#[no_mangle]
+pub extern "C" fn call() {
+    let mut named_keys = NamedKeys::new();
+    named_keys.insert(String::from(ANSWER), storage::new_uref(U256::zero()).into());
+    named_keys.insert(String::from(UPDATED_AT), storage::new_uref(0u64).into());
+
+    let mut entry_points = EntryPoints::new();
+    entry_points.add_entry_point(EntryPoint::new(
+        String::from("set_latest_round_data"),
+        vec![Parameter::new(ANSWER, CLType::U256)],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        String::from("latest_round_data"),
+        vec![],
+        <(U256, u64)>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    storage::new_contract(
+        entry_points,
+        Some(named_keys),
+        Some(format!("{}_package_hash", TEST_PRICE_FEED)),
+        None,
+    );
+}
@@ -0,0 +1,172 @@
+//! Base for contracts consuming randomness from the VRF coordinator, the counterpart of
+//! Chainlink's `VRFConsumerBaseV2`.
+//!
+//! A consumer stores the coordinator package with [`init`] on installation, adds
+//! [`entry_points`] to its own and exposes its hook with [`raw_fulfill_random_words!`]:
+//!
+//! ```ignore
+//! fn fulfill_random_words(request_id: U256, random_words: Vec<U256>) {
+//!     // use the random words
+//! }
+//!
+//! vrf_consumer::raw_fulfill_random_words!(fulfill_random_words);
+//! ```
+//!
+//! The coordinator stays locked while it calls the hook, so the hook cannot request more words.
+//! If the hook reverts, the oracle settles the request without calling it and the words are
+//! left in the coordinator under `get_random_words(request_id)`, to be taken with
+//! [`claim_random_words`]. Contracts that would rather not be called back at all request with
+//! [`request_random_words_to_claim`].
+//!
+//! Contracts without a subscription store the direct funding wrapper with [`init`] instead of
+//! the coordinator and request with [`request_randomness`].
+#![no_std]
+
+extern crate alloc;
+
+use alloc::{string::String, vec, vec::Vec};
+use casper_contract::{
+    contract_api::{runtime, storage},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{
+    CLType, CLTyped, EntryPoint, EntryPointAccess, EntryPointType, HashAddr, Key, Parameter, U128,
+    U256,
+};
+use common::{
+    erc20_helpers,
+    error::Error,
+    helpers::{self, require},
+    interfaces,
+};
+
+pub const VRF_COORDINATOR_KEY_NAME: &str = "vrf_coordinator";
+pub const RAW_FULFILL_RANDOM_WORDS_ENTRY_POINT_NAME: &str = "raw_fulfill_random_words";
+
+// Runtime argument names
+pub const ARG_REQUEST_ID: &str = "request_id";
+pub const ARG_RANDOM_WORDS: &str = "random_words";
+
+/// Stores the package hash of the coordinator allowed to fulfill requests.
+pub fn init(vrf_coordinator: Key) {
+    runtime::put_key(
+        VRF_COORDINATOR_KEY_NAME,
+        storage::new_uref(vrf_coordinator).into(),
+    );
+}
+
+pub fn vrf_coordinator() -> Key {
+    helpers::get_key(VRF_COORDINATOR_KEY_NAME).unwrap_or_revert()
+}
+
+pub fn only_vrf_coordinator() {
+    require(
+        helpers::get_immediate_caller_key() == vrf_coordinator(),
+        Error::OnlyCoordinatorCanFulfill,
+    );
+}
+
+/// Body of the `raw_fulfill_random_words` entry point: checks the caller is the coordinator
+/// and hands the words to `fulfill_random_words`. Use [`raw_fulfill_random_words!`] to
+/// declare the entry point.
+pub fn raw_fulfill_random_words(fulfill_random_words: fn(U256, Vec<U256>)) {
+    only_vrf_coordinator();
+    let request_id: U256 = runtime::get_named_arg(ARG_REQUEST_ID);
+    let random_words: Vec<U256> = runtime::get_named_arg(ARG_RANDOM_WORDS);
+    fulfill_random_words(request_id, random_words);
+}
+
+/// Declares the `raw_fulfill_random_words` entry point the coordinator calls, forwarding
+/// the words of requests it fulfills to `$fulfill_random_words`.
+#[macro_export]
+macro_rules! raw_fulfill_random_words {
+    ($fulfill_random_words: path) => {
+        #[no_mangle]
+        pub extern "C" fn raw_fulfill_random_words() {
+            $crate::raw_fulfill_random_words($fulfill_random_words);
+        }
+    };
+}
+
+/// Requests `num_words` random words from the stored coordinator, paid by subscription
+/// `sub_id` that this contract must be a consumer of. Returns the request id the words will
+/// be delivered with.
+pub fn request_random_words(
+    key_hash: HashAddr,
+    sub_id: u64,
+    request_confirmations: u64,
+    callback_gas_limit: U128,
+    num_words: u64,
+) -> U256 {
+    interfaces::request_random_words(
+        vrf_coordinator(),
+        key_hash,
+        sub_id,
+        request_confirmations,
+        callback_gas_limit,
+        num_words,
+        false,
+    )
+}
+
+/// Requests like [`request_random_words`], but the coordinator keeps the words until they are
+/// taken with [`claim_random_words`] instead of calling `raw_fulfill_random_words`.
+pub fn request_random_words_to_claim(
+    key_hash: HashAddr,
+    sub_id: u64,
+    request_confirmations: u64,
+    callback_gas_limit: U128,
+    num_words: u64,
+) -> U256 {
+    interfaces::request_random_words(
+        vrf_coordinator(),
+        key_hash,
+        sub_id,
+        request_confirmations,
+        callback_gas_limit,
+        num_words,
+        true,
+    )
+}
+
+/// Takes the words of a fulfilled request made with [`request_random_words_to_claim`] or
+/// fulfilled without calling back this contract.
+pub fn claim_random_words(request_id: U256) -> Vec<U256> {
+    interfaces::claim_random_words(vrf_coordinator(), request_id)
+}
+
+/// Requests `num_words` random words through the direct funding wrapper stored in place of
+/// the coordinator, paying its quoted price from this contract's payment token balance.
+/// Returns the request id the words will be delivered with.
+pub fn request_randomness(
+    callback_gas_limit: U128,
+    request_confirmations: u64,
+    num_words: u64,
+) -> U256 {
+    let vrf_wrapper = vrf_coordinator();
+    let request_price = interfaces::calculate_request_price(vrf_wrapper, callback_gas_limit);
+    erc20_helpers::approve(
+        interfaces::get_payment_token(vrf_wrapper),
+        vrf_wrapper,
+        request_price,
+    );
+    interfaces::wrapper_request_random_words(
+        vrf_wrapper,
+        callback_gas_limit,
+        request_confirmations,
+        num_words,
+    )
+}
+
+pub fn entry_points() -> Vec<EntryPoint> {
+    vec![EntryPoint::new(
+        String::from(RAW_FULFILL_RANDOM_WORDS_ENTRY_POINT_NAME),
+        vec![
+            Parameter::new(ARG_REQUEST_ID, CLType::U256),
+            Parameter::new(ARG_RANDOM_WORDS, Vec::<U256>::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    )]
+}
@@ -98,6 +98,14 @@ pub(crate) fn default() -> EntryPoints {
         EntryPointType::Contract,
     ));
 
+    entry_points.add_entry_point(EntryPoint::new(
+        String::from("get_subscription_limits"),
+        vec![Parameter::new("sub_id", CLType::U64)],
+        CLType::Any,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
     entry_points.add_entry_point(EntryPoint::new(
         String::from("get_current_sub_id"),
         vec![],
@@ -108,16 +116,24 @@ pub(crate) fn default() -> EntryPoints {
 
     entry_points.add_entry_point(EntryPoint::new(
         String::from("get_total_balance"),
-        vec![],
+        vec![Parameter::new("payment_token", CLType::Key)],
         CLType::U128,
         EntryPointAccess::Public,
         EntryPointType::Contract,
     ));
 
     entry_points.add_entry_point(EntryPoint::new(
-        String::from("get_proving_key"),
+        String::from("get_total_native_balance"),
         vec![],
-        CLType::Key,
+        CLType::U512,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        String::from("get_proving_key"),
+        vec![Parameter::new("key_hash", CLType::ByteArray(32))],
+        CLType::Any,
         EntryPointAccess::Public,
         EntryPointType::Contract,
     ));
@@ -132,12 +148,23 @@ pub(crate) fn default() -> EntryPoints {
 
     entry_points.add_entry_point(EntryPoint::new(
         String::from("get_withdrawable_token"),
-        vec![Parameter::new("oracle", CLType::Key)],
+        vec![
+            Parameter::new("oracle", CLType::Key),
+            Parameter::new("payment_token", CLType::Key),
+        ],
         CLType::U128,
         EntryPointAccess::Public,
         EntryPointType::Contract,
     ));
 
+    entry_points.add_entry_point(EntryPoint::new(
+        String::from("get_withdrawable_cspr"),
+        vec![Parameter::new("oracle", CLType::Key)],
+        CLType::U512,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
     entry_points.add_entry_point(EntryPoint::new(
         String::from("get_request_commitment"),
         vec![Parameter::new("request_id", CLType::U256)],
@@ -146,6 +173,22 @@ pub(crate) fn default() -> EntryPoints {
         EntryPointType::Contract,
     ));
 
+    entry_points.add_entry_point(EntryPoint::new(
+        String::from("get_pending_requests"),
+        vec![Parameter::new("sub_id", CLType::U64)],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        String::from("get_random_words"),
+        vec![Parameter::new("request_id", CLType::U256)],
+        CLType::List(Box::new(CLType::U256)),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
     entry_points.add_entry_point(EntryPoint::new(
         String::from("get_config"),
         vec![],
@@ -162,11 +205,28 @@ pub(crate) fn default() -> EntryPoints {
         EntryPointType::Contract,
     ));
 
+    entry_points.add_entry_point(EntryPoint::new(
+        String::from("get_payment_token_config"),
+        vec![Parameter::new("payment_token", CLType::Key)],
+        CLType::Any,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        String::from("get_payment_tokens"),
+        vec![],
+        CLType::List(Box::new(CLType::Key)),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
     entry_points.add_entry_point(EntryPoint::new(
         String::from("register_proving_key"),
         vec![
             Parameter::new("oracle", CLType::Key),
             Parameter::new("public_proving_key", CLType::List(Box::new(CLType::U8))),
+            Parameter::new("key_type", CLType::U8),
         ],
         CLType::Unit,
         EntryPointAccess::Public,
@@ -191,7 +251,9 @@ pub(crate) fn default() -> EntryPoints {
             Parameter::new("max_gas_limit", CLType::U128),
             Parameter::new("staleness_seconds", CLType::U64),
             Parameter::new("gas_after_payment_calculation", CLType::U128),
+            Parameter::new("fallback_motes_per_unit_link", CLType::U256),
             Parameter::new("fee_config_bytes", CLType::List(Box::new(CLType::U8))),
+            Parameter::new("request_timeout_seconds", CLType::U64),
         ],
         CLType::Unit,
         EntryPointAccess::Public,
@@ -208,7 +270,31 @@ pub(crate) fn default() -> EntryPoints {
 
     entry_points.add_entry_point(EntryPoint::new(
         String::from("recover_funds"),
-        vec![Parameter::new("to", CLType::Key)],
+        vec![
+            Parameter::new("to", CLType::Key),
+            Parameter::new("payment_token", CLType::Key),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        String::from("set_payment_token_config"),
+        vec![
+            Parameter::new("payment_token", CLType::Key),
+            Parameter::new("price_feed", CLType::Key),
+            Parameter::new("fallback_motes_per_unit_link", CLType::U256),
+            Parameter::new("fee_config_bytes", CLType::List(Box::new(CLType::U8))),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        String::from("remove_payment_token"),
+        vec![Parameter::new("payment_token", CLType::Key)],
         CLType::Unit,
         EntryPointAccess::Public,
         EntryPointType::Contract,
@@ -216,8 +302,19 @@ pub(crate) fn default() -> EntryPoints {
 
     entry_points.add_entry_point(EntryPoint::new(
         String::from("get_fee_tier"),
-        vec![Parameter::new("req_count", CLType::U64)],
-        CLType::U64,
+        vec![
+            Parameter::new("req_count", CLType::U64),
+            Parameter::new("payment_token", CLType::Key),
+        ],
+        CLType::Tuple2([Box::new(CLType::U32), Box::new(CLType::U64)]),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        String::from("get_motes_per_unit_link"),
+        vec![Parameter::new("payment_token", CLType::Key)],
+        CLType::U256,
         EntryPointAccess::Public,
         EntryPointType::Contract,
     ));
@@ -246,19 +343,63 @@ pub(crate) fn default() -> EntryPoints {
             Parameter::new("request_confirmations", CLType::U64),
             Parameter::new("callback_gas_limit", CLType::U128),
             Parameter::new("num_words", CLType::U64),
+            Parameter::new("pull", CLType::Bool),
         ],
         CLType::U256,
         EntryPointAccess::Public,
         EntryPointType::Contract,
     ));
 
+    entry_points.add_entry_point(EntryPoint::new(
+        String::from("cancel_request"),
+        vec![
+            Parameter::new("request_id", CLType::U256),
+            Parameter::new("rc", CLType::List(Box::new(CLType::U8))),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        String::from("claim_random_words"),
+        vec![Parameter::new("request_id", CLType::U256)],
+        CLType::List(Box::new(CLType::U256)),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
     entry_points.add_entry_point(EntryPoint::new(
         String::from("fulfill_random_words"),
         vec![
             Parameter::new("proof", CLType::List(Box::new(CLType::U8))),
             Parameter::new("rc", CLType::List(Box::new(CLType::U8))),
+            Parameter::new("skip_callback", CLType::Bool),
+            Parameter::new("callback_gas_used", CLType::U128),
         ],
-        CLType::U128,
+        CLType::Tuple2([Box::new(CLType::U128), Box::new(CLType::U512)]),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        String::from("fulfill_random_words_batch"),
+        vec![Parameter::new(
+            "requests",
+            CLType::List(Box::new(CLType::Tuple3([
+                Box::new(CLType::List(Box::new(CLType::U8))),
+                Box::new(CLType::List(Box::new(CLType::U8))),
+                Box::new(CLType::Tuple2([
+                    Box::new(CLType::Bool),
+                    Box::new(CLType::Option(Box::new(CLType::U128))),
+                ])),
+            ]))),
+        )],
+        CLType::List(Box::new(CLType::Tuple3([
+            Box::new(CLType::U256),
+            Box::new(CLType::U128),
+            Box::new(CLType::U512),
+        ]))),
         EntryPointAccess::Public,
         EntryPointType::Contract,
     ));
@@ -284,9 +425,32 @@ pub(crate) fn default() -> EntryPoints {
         EntryPointType::Contract,
     ));
 
+    entry_points.add_entry_point(EntryPoint::new(
+        String::from("on_token_transfer"),
+        vec![
+            Parameter::new("amount", CLType::U256),
+            Parameter::new("data", CLType::List(Box::new(CLType::U8))),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        String::from("deposit_cspr"),
+        vec![
+            Parameter::new("sub_id", CLType::U64),
+            Parameter::new("purse", CLType::URef),
+            Parameter::new("amount", CLType::U512),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
     entry_points.add_entry_point(EntryPoint::new(
         String::from("create_subscription"),
-        vec![],
+        vec![Parameter::new("payment_token", CLType::Key)],
         CLType::U64,
         EntryPointAccess::Public,
         EntryPointType::Contract,
@@ -316,6 +480,18 @@ pub(crate) fn default() -> EntryPoints {
         vec![
             Parameter::new("amount", CLType::U128),
             Parameter::new("recipient", CLType::Key),
+            Parameter::new("payment_token", CLType::Key),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        String::from("oracle_withdraw_cspr"),
+        vec![
+            Parameter::new("amount", CLType::U512),
+            Parameter::new("recipient", CLType::Key),
         ],
         CLType::Unit,
         EntryPointAccess::Public,
@@ -344,6 +520,20 @@ pub(crate) fn default() -> EntryPoints {
         EntryPointType::Contract,
     ));
 
+    entry_points.add_entry_point(EntryPoint::new(
+        String::from("set_subscription_limits"),
+        vec![
+            Parameter::new("sub_id", CLType::U64),
+            Parameter::new("window_seconds", CLType::U64),
+            Parameter::new("max_spend_per_window", CLType::U128),
+            Parameter::new("max_requests_per_consumer", CLType::U64),
+            Parameter::new("max_num_words", CLType::U64),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
     entry_points.add_entry_point(EntryPoint::new(
         String::from("cancel_subscription"),
         vec![
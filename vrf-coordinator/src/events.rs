@@ -13,20 +13,25 @@ use casper_types::bytesrepr::ToBytes;
 use casper_types::Key;
 use casper_types::U128;
 use casper_types::U256;
-use common::data_types::FeeConfig;
+use casper_types::U512;
+use common::data_types::{FeeConfig, SubscriptionLimits};
+use common::events::{
+    RandomWordsFulfillmentFailed, RandomWordsRequestCancelled, RandomWordsRequested,
+};
 use common::helpers::current_block_timestamp;
-use common::events::RandomWordsRequested;
 #[derive(Event, Debug, PartialEq, Eq)]
 pub struct SubscriptionCreated {
     sub_id: u64,
     owner: Key,
+    payment_token: Key,
     timestamp: u64,
 }
 impl SubscriptionCreated {
-    pub fn new(sub_id: u64, owner: Key) -> Self {
+    pub fn new(sub_id: u64, owner: Key, payment_token: Key) -> Self {
         Self {
             sub_id,
             owner,
+            payment_token,
             timestamp: current_block_timestamp(),
         }
     }
@@ -50,6 +55,24 @@ impl SubscriptionFunded {
     }
 }
 
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct SubscriptionFundedWithCspr {
+    sub_id: u64,
+    old_balance: U512,
+    new_balance: U512,
+    timestamp: u64,
+}
+impl SubscriptionFundedWithCspr {
+    pub fn new(sub_id: u64, old_balance: U512, new_balance: U512) -> Self {
+        Self {
+            sub_id,
+            old_balance,
+            new_balance,
+            timestamp: current_block_timestamp(),
+        }
+    }
+}
+
 #[derive(Event, Debug, PartialEq, Eq)]
 pub struct SubscriptionConsumerAdded {
     sub_id: u64,
@@ -87,14 +110,16 @@ pub struct SubscriptionCanceled {
     sub_id: u64,
     to: Key,
     amount: U128,
+    native_amount: U512,
     timestamp: u64,
 }
 impl SubscriptionCanceled {
-    pub fn new(sub_id: u64, to: Key, amount: U128) -> Self {
+    pub fn new(sub_id: u64, to: Key, amount: U128, native_amount: U512) -> Self {
         Self {
             sub_id,
             to,
             amount,
+            native_amount,
             timestamp: current_block_timestamp(),
         }
     }
@@ -140,13 +165,15 @@ impl SubscriptionOwnerTransferred {
 pub struct ProvingKeyRegistered {
     key_hash: String,
     oracle: Key,
+    key_type: u8,
     timestamp: u64,
 }
 impl ProvingKeyRegistered {
-    pub fn new(key_hash: String, oracle: Key) -> Self {
+    pub fn new(key_hash: String, oracle: Key, key_type: u8) -> Self {
         Self {
             key_hash,
             oracle,
+            key_type,
             timestamp: current_block_timestamp(),
         }
     }
@@ -172,16 +199,26 @@ impl ProvingKeyDeregistered {
 pub struct RandomWordsFulfilled {
     request_id: U256,
     output_seed: U256,
+    /// Payment in payment token units, zero when the subscription paid in CSPR.
     payment: U128,
+    /// Payment in motes, zero when the subscription paid in its payment token.
+    native_payment: U512,
     success: bool,
     timestamp: u64,
 }
 impl RandomWordsFulfilled {
-    pub fn new(request_id: U256, output_seed: U256, payment: U128, success: bool) -> Self {
+    pub fn new(
+        request_id: U256,
+        output_seed: U256,
+        payment: U128,
+        native_payment: U512,
+        success: bool,
+    ) -> Self {
         Self {
             request_id,
             output_seed,
             payment,
+            native_payment,
             success,
             timestamp: current_block_timestamp(),
         }
@@ -194,7 +231,9 @@ pub struct ConfigSet {
     max_gas_limit: U128,
     stateless_seconds: u64,
     gas_after_payment_calculation: U128,
+    fallback_motes_per_unit_link: U256,
     fee_config: Bytes,
+    request_timeout_seconds: u64,
     timestamp: u64,
 }
 impl ConfigSet {
@@ -203,14 +242,40 @@ impl ConfigSet {
         max_gas_limit: U128,
         stateless_seconds: u64,
         gas_after_payment_calculation: U128,
+        fallback_motes_per_unit_link: U256,
         fee_config: FeeConfig,
+        request_timeout_seconds: u64,
     ) -> Self {
         Self {
             minimum_request_confirmations,
             max_gas_limit,
             stateless_seconds,
             gas_after_payment_calculation,
+            fallback_motes_per_unit_link,
             fee_config: fee_config.to_bytes().unwrap().into_bytes().unwrap().into(),
+            request_timeout_seconds,
+            timestamp: current_block_timestamp(),
+        }
+    }
+}
+
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct SubscriptionLimitsSet {
+    sub_id: u64,
+    window_seconds: u64,
+    max_spend_per_window: U128,
+    max_requests_per_consumer: u64,
+    max_num_words: u64,
+    timestamp: u64,
+}
+impl SubscriptionLimitsSet {
+    pub fn new(sub_id: u64, limits: SubscriptionLimits) -> Self {
+        Self {
+            sub_id,
+            window_seconds: limits.window_seconds,
+            max_spend_per_window: limits.max_spend_per_window,
+            max_requests_per_consumer: limits.max_requests_per_consumer,
+            max_num_words: limits.max_num_words,
             timestamp: current_block_timestamp(),
         }
     }
@@ -219,14 +284,57 @@ impl ConfigSet {
 #[derive(Event, Debug, PartialEq, Eq)]
 pub struct FundsRecovered {
     to: Key,
+    payment_token: Key,
     amount: U128,
+    native_amount: U512,
     timestamp: u64,
 }
 impl FundsRecovered {
-    pub fn new(to: Key, amount: U128) -> Self {
+    pub fn new(to: Key, payment_token: Key, amount: U128, native_amount: U512) -> Self {
         Self {
             to,
+            payment_token,
             amount,
+            native_amount,
+            timestamp: current_block_timestamp(),
+        }
+    }
+}
+
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct PaymentTokenConfigSet {
+    payment_token: Key,
+    price_feed: Key,
+    fallback_motes_per_unit_link: U256,
+    fee_config: Bytes,
+    timestamp: u64,
+}
+impl PaymentTokenConfigSet {
+    pub fn new(
+        payment_token: Key,
+        price_feed: Key,
+        fallback_motes_per_unit_link: U256,
+        fee_config: FeeConfig,
+    ) -> Self {
+        Self {
+            payment_token,
+            price_feed,
+            fallback_motes_per_unit_link,
+            fee_config: fee_config.to_bytes().unwrap().into_bytes().unwrap().into(),
+            timestamp: current_block_timestamp(),
+        }
+    }
+}
+
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct PaymentTokenRemoved {
+    payment_token: Key,
+    timestamp: u64,
+}
+impl PaymentTokenRemoved {
+    pub fn new(payment_token: Key) -> Self {
+        Self {
+            payment_token,
             timestamp: current_block_timestamp(),
         }
     }
@@ -244,8 +352,14 @@ pub fn init_events() {
         .with::<ProvingKeyDeregistered>()
         .with::<RandomWordsRequested>()
         .with::<RandomWordsFulfilled>()
+        .with::<RandomWordsFulfillmentFailed>()
+        .with::<RandomWordsRequestCancelled>()
         .with::<ConfigSet>()
+        .with::<SubscriptionLimitsSet>()
         .with::<FundsRecovered>()
-        .with::<SubscriptionFunded>();
+        .with::<SubscriptionFunded>()
+        .with::<SubscriptionFundedWithCspr>()
+        .with::<PaymentTokenConfigSet>()
+        .with::<PaymentTokenRemoved>();
     casper_event_standard::init(schemas);
 }
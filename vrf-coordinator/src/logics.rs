@@ -1,19 +1,25 @@
 use core::convert::TryInto;
 
-use alloc::{borrow::ToOwned, string::ToString, vec, vec::Vec, format};
+use alloc::{string::ToString, vec, vec::Vec, format};
 use casper_contract::{
-    contract_api::runtime::{self, revert},
+    contract_api::{
+        runtime::{self, revert},
+        system,
+    },
     unwrap_or_revert::UnwrapOrRevert,
 };
 use casper_types::{
     bytesrepr::{Bytes, FromBytes},
-    CLValue, HashAddr, Key, U128, U256,
+    CLValue, HashAddr, Key, URef, U128, U256, U512,
 };
 use common::{
-    constants::{MAX_CONSUMERS, MAX_NUM_WORDS, MAX_REQUEST_CONFIRMATIONS},
+    constants::{
+        GAS_FOR_CALL_EXACT_CHECK, MAX_CONSUMERS, MAX_NUM_WORDS, MAX_REQUEST_CONFIRMATIONS,
+    },
     data_types::{
-        Config, FeeConfig, Proof, RequestCommitment, Subscription, SubscriptionConfig,
-        SubscriptionView,
+        BlockRef, Config, FeeConfig, PaymentTokenConfig, Proof, ProvingKey, ProvingKeyType,
+        RequestCommitment, RequestRecord, RequestStatus, Subscription, SubscriptionConfig,
+        SubscriptionLimits, SubscriptionView, WindowUsage,
     },
     erc20_helpers,
     error::Error,
@@ -21,21 +27,33 @@ use common::{
         self, current_block_timestamp, get_immediate_caller_key, get_self_key, null_key,
         to_vec_string, u256_from_hash,
     },
-    interfaces::{call_raw_fulfill_random_words, get_block_hash},
+    interfaces::{
+        call_raw_fulfill_random_words, get_block_hash, get_block_ref, get_latest_block_ref,
+        get_latest_round_data,
+    },
     lock::{lock_contract, unlock_contract, when_not_locked},
     owner::only_owner,
+    payment,
 };
 
 use crate::{
     checks::only_sub_owner,
     events::{self, ConfigSet},
     store::{
-        self, read_block_hash_store, read_config, read_consumer, read_current_sub_id,
-        read_fee_config, read_payment_token, read_proving_key, read_proving_key_hashes,
-        read_request_commitment, read_subscription, read_subscription_config, read_total_balance,
-        read_withdrawable_token, save_config, save_consumer, save_current_sub_id, save_fee_config,
-        save_proving_key, save_proving_key_hashes, save_request_commitment, save_subscription,
-        save_subscription_config, save_total_balance, save_withdrawable_token,
+        self, contract_purse, read_block_hash_store, read_config, read_consumer,
+        read_consumer_usage, read_current_sub_id, read_fee_config, read_payment_token,
+        read_payment_token_config, read_payment_tokens, read_pending_request_count,
+        read_price_feed, read_proving_key, read_proving_key_hashes, read_random_words,
+        read_random_words_recipient, read_request, read_request_commitment, read_subscription,
+        read_subscription_config, read_subscription_limits, read_subscription_usage,
+        read_total_balance, read_total_native_balance, read_withdrawable_cspr,
+        read_withdrawable_token, save_config, save_consumer, save_consumer_usage,
+        save_current_sub_id, save_fee_config, save_payment_token_config, save_payment_tokens,
+        save_pending_request_count, save_proving_key, save_proving_key_hashes, save_random_words,
+        save_random_words_recipient, save_request, save_request_commitment, save_subscription,
+        save_subscription_config, save_subscription_limits, save_subscription_usage,
+        save_total_balance, save_total_native_balance, save_withdrawable_cspr,
+        save_withdrawable_token,
     },
     vrf,
 };
@@ -57,8 +75,11 @@ pub extern "C" fn get_subscription_view() {
     runtime::ret(
         CLValue::from_t(SubscriptionView {
             balance: s.balance,
+            native_balance: s.native_balance,
             req_count: s.req_count,
             owner: s_config.owner,
+            limits: read_subscription_limits(&sub_id),
+            payment_token: s_config.payment_token,
             consumers: s_config.consumers,
         })
         .unwrap_or_revert(),
@@ -69,21 +90,33 @@ pub extern "C" fn get_subscription_view() {
  * @notice Registers a proving key to an oracle.
  * @param oracle address of the oracle
  * @param publicProvingKey key that oracle can use to submit vrf fulfillments
+ * @param keyType optional ProvingKeyType selecting the proof format, defaults to Secp256k1Witness
  */
 #[no_mangle]
 pub extern "C" fn register_proving_key() {
     only_owner();
     let (oracle, public_proving_key): (Key, Bytes) =
         helpers::get_named_args_2(to_vec_string(&["oracle", "public_proving_key"]));
+    let key_type = helpers::get_optional_named_arg_with_user_errors::<u8>(
+        "key_type",
+        Error::InvalidProvingKeyType,
+    )
+    .unwrap_or(ProvingKeyType::Secp256k1Witness as u8);
+    let key_type =
+        ProvingKeyType::from_u8(key_type).unwrap_or_revert_with(Error::InvalidProvingKeyType);
     let kh = _hash_of_key(&public_proving_key.to_vec());
-    if read_proving_key(&kh) != null_key() {
+    if read_proving_key(&kh).oracle != null_key() {
         revert(Error::ProvingKeyAlreadyRegistered);
     }
-    save_proving_key(&kh, &oracle);
+    save_proving_key(&kh, &ProvingKey { oracle, key_type });
     let mut current_proving_key_hashes = read_proving_key_hashes();
     current_proving_key_hashes.push(kh);
     save_proving_key_hashes(current_proving_key_hashes);
-    casper_event_standard::emit(events::ProvingKeyRegistered::new(hex::encode(kh), oracle));
+    casper_event_standard::emit(events::ProvingKeyRegistered::new(
+        hex::encode(kh),
+        oracle,
+        key_type as u8,
+    ));
 }
 
 #[no_mangle]
@@ -91,11 +124,11 @@ pub extern "C" fn deregister_proving_key() {
     only_owner();
     let public_proving_key: Bytes = runtime::get_named_arg("public_proving_key");
     let kh = _hash_of_key(&public_proving_key.to_vec());
-    let oracle = read_proving_key(&kh);
+    let oracle = read_proving_key(&kh).oracle;
     if oracle == null_key() {
         revert(Error::NoSuchProvingKey);
     }
-    save_proving_key(&kh, &null_key());
+    save_proving_key(&kh, &ProvingKey::default());
     let mut current_proving_key_hashes = read_proving_key_hashes();
     let count = current_proving_key_hashes.len();
     for i in 0..count {
@@ -116,7 +149,10 @@ pub extern "C" fn deregister_proving_key() {
  * @param maxGasLimit global max for request gas limit
  * @param stalenessSeconds if the eth/link feed is more stale then this, use the fallback price
  * @param gasAfterPaymentCalculation gas used in doing accounting after completing the gas measurement
- * @param feeConfig fee tier configuration
+ * @param fallbackMotesPerUnitLink fallback price of one payment token in motes
+ * @param feeConfig serialized FeeConfig, whose tiers must be ordered as FeeConfig describes
+ * @param requestTimeoutSeconds seconds after which an unfulfilled request can be cancelled
+ * @dev a zero request timeout disables cancelling
  */
 #[no_mangle]
 pub extern "C" fn set_config() {
@@ -126,26 +162,33 @@ pub extern "C" fn set_config() {
         max_gas_limit,
         staleness_seconds,
         gas_after_payment_calculation,
+        fallback_motes_per_unit_link,
         fee_config_bytes,
-    ) = helpers::get_named_args_5::<u64, U128, u64, U128, Bytes>(to_vec_string(&[
+        request_timeout_seconds,
+    ) = helpers::get_named_args_7::<u64, U128, u64, U128, U256, Bytes, u64>(to_vec_string(&[
         "minimum_request_confirmations",
         "max_gas_limit",
         "staleness_seconds",
         "gas_after_payment_calculation",
+        "fallback_motes_per_unit_link",
         "fee_config_bytes",
+        "request_timeout_seconds",
     ]));
     if minimum_request_confirmations > MAX_REQUEST_CONFIRMATIONS {
         revert(Error::InvalidRequestConfirmations);
     }
+    if fallback_motes_per_unit_link.is_zero() {
+        revert(Error::InvalidLinkWeiPrice);
+    }
 
-    let fee_config = FeeConfig::from_bytes(&fee_config_bytes)
-        .unwrap_or_revert_with(Error::FailedToDecodeInputBytes)
-        .0;
+    let fee_config = _decode_fee_config(&fee_config_bytes);
     save_config(Config {
         minimum_request_confirmations,
         max_gas_limit,
         staleness_seconds,
         gas_after_payment_calculation,
+        fallback_motes_per_unit_link,
+        request_timeout_seconds,
     });
     save_fee_config(fee_config.clone());
     casper_event_standard::emit(ConfigSet::new(
@@ -153,14 +196,17 @@ pub extern "C" fn set_config() {
         max_gas_limit,
         staleness_seconds,
         gas_after_payment_calculation,
+        fallback_motes_per_unit_link,
         fee_config,
+        request_timeout_seconds,
     ));
 }
 
 /**
  * @notice Owner cancel subscription, sends remaining link directly to the subscription owner.
  * @param subId subscription id
- * @dev notably can be called even if there are pending requests, outstanding ones may fail onchain
+ * @dev notably can be called even if there are pending requests, outstanding ones fail onchain
+ * @dev with InvalidSubscription and can still be cancelled with cancel_request
  */
 #[no_mangle]
 pub extern "C" fn owner_cancel_subscription() {
@@ -173,27 +219,148 @@ pub extern "C" fn owner_cancel_subscription() {
     _cancel_subscription_helper(sub_id, subscription_config.owner);
 }
 
+/**
+ * @notice Sends tokens and CSPR the coordinator holds above its balances to `to`
+ * @param to recipient of the excess, an account if there is CSPR to recover
+ * @param payment_token optional, token to recover, defaults to payment_token
+ * @dev reverts with BalanceInvariantViolated if either asset is short of its balances
+ */
 #[no_mangle]
 pub extern "C" fn recover_funds() {
     only_owner();
     let to: Key = runtime::get_named_arg("to");
-    let payment_token = read_payment_token();
+    let payment_token = _payment_token_arg();
     let external_balance = erc20_helpers::get_balance(payment_token, get_self_key());
-    let internal_balance = read_total_balance();
-    if internal_balance > external_balance {
+    let internal_balance = read_total_balance(&payment_token);
+    let external_native_balance = system::get_purse_balance(contract_purse()).unwrap_or_revert();
+    let internal_native_balance = read_total_native_balance();
+    if internal_balance > external_balance || internal_native_balance > external_native_balance {
         revert(Error::BalanceInvariantViolated);
     }
-    if internal_balance < external_balance {
-        let amount = external_balance - internal_balance;
+    let amount = external_balance - internal_balance;
+    let native_amount = external_native_balance - internal_native_balance;
+    if !amount.is_zero() {
         erc20_helpers::transfer(payment_token, to, amount);
-        casper_event_standard::emit(events::FundsRecovered::new(to, amount));
+    }
+    if !native_amount.is_zero() {
+        _transfer_cspr(to, native_amount);
+    }
+    if !amount.is_zero() || !native_amount.is_zero() {
+        casper_event_standard::emit(events::FundsRecovered::new(
+            to,
+            payment_token,
+            amount,
+            native_amount,
+        ));
     }
 }
 
+/**
+ * @notice Index and flat fee of the fee tier of a subscription
+ * @param req_count requests the subscription made
+ * @param payment_token optional, token the subscription pays in, defaults to payment_token
+ * @return (tier index, flat fee in millionths of a token)
+ */
 #[no_mangle]
 pub extern "C" fn get_fee_tier() {
     let req_count: u64 = runtime::get_named_arg("req_count");
-    runtime::ret(CLValue::from_t(_get_fee_tier(req_count)).unwrap_or_revert())
+    let token_config = _payment_token_config(_payment_token_arg(), &read_config());
+    runtime::ret(CLValue::from_t(token_config.fee_config.fee_tier(req_count)).unwrap_or_revert())
+}
+
+/**
+ * @notice Price of one payment token in motes that fulfillments are billed at
+ * @param payment_token optional, token to price, defaults to payment_token
+ * @dev reverts when the feed answers zero, as the fulfillment payment would
+ */
+#[no_mangle]
+pub extern "C" fn get_motes_per_unit_link() {
+    let config = read_config();
+    let token_config = _payment_token_config(_payment_token_arg(), &config);
+    let motes_per_unit_link = _get_feed_data(&config, &token_config);
+    if motes_per_unit_link.is_zero() {
+        revert(Error::InvalidLinkWeiPrice);
+    }
+    runtime::ret(CLValue::from_t(motes_per_unit_link).unwrap_or_revert())
+}
+
+#[no_mangle]
+pub extern "C" fn get_total_balance() {
+    runtime::ret(CLValue::from_t(read_total_balance(&_payment_token_arg())).unwrap_or_revert())
+}
+
+#[no_mangle]
+pub extern "C" fn get_withdrawable_token() {
+    let oracle: Key = runtime::get_named_arg("oracle");
+    runtime::ret(
+        CLValue::from_t(read_withdrawable_token(&oracle, &_payment_token_arg())).unwrap_or_revert(),
+    )
+}
+
+/**
+ * @notice Accepts a payment token for new subscriptions or updates its terms
+ * @param payment_token CEP-18 token to accept
+ * @param price_feed feed of the token price in motes, the null key to use the fallback price
+ * @param fallback_motes_per_unit_link price of one token in motes when the feed is unset or stale
+ * @param fee_config_bytes serialized FeeConfig of subscriptions paying in the token
+ * @dev the coordinator's own payment_token is priced by set_config and cannot be configured here
+ */
+#[no_mangle]
+pub extern "C" fn set_payment_token_config() {
+    only_owner();
+    let (payment_token, price_feed, fallback_motes_per_unit_link, fee_config_bytes) =
+        helpers::get_named_args_4::<Key, Key, U256, Bytes>(to_vec_string(&[
+            "payment_token",
+            "price_feed",
+            "fallback_motes_per_unit_link",
+            "fee_config_bytes",
+        ]));
+    if payment_token == null_key() || payment_token == read_payment_token() {
+        revert(Error::InvalidKey);
+    }
+    if fallback_motes_per_unit_link.is_zero() {
+        revert(Error::InvalidLinkWeiPrice);
+    }
+    let fee_config = _decode_fee_config(&fee_config_bytes);
+
+    let mut payment_tokens = read_payment_tokens();
+    if !payment_tokens.contains(&payment_token) {
+        payment_tokens.push(payment_token);
+        save_payment_tokens(payment_tokens);
+    }
+    save_payment_token_config(
+        &payment_token,
+        &PaymentTokenConfig {
+            accepted: true,
+            price_feed,
+            fallback_motes_per_unit_link,
+            fee_config: fee_config.clone(),
+        },
+    );
+    casper_event_standard::emit(events::PaymentTokenConfigSet::new(
+        payment_token,
+        price_feed,
+        fallback_motes_per_unit_link,
+        fee_config,
+    ));
+}
+
+/**
+ * @notice Stops accepting a payment token for new subscriptions and on_token_transfer funding
+ * @param payment_token token accepted with set_payment_token_config
+ * @dev subscriptions paying in the token keep its terms, so their balances stay usable
+ */
+#[no_mangle]
+pub extern "C" fn remove_payment_token() {
+    only_owner();
+    let payment_token: Key = runtime::get_named_arg("payment_token");
+    let mut token_config = read_payment_token_config(&payment_token);
+    if !token_config.accepted {
+        revert(Error::PaymentTokenNotAccepted);
+    }
+    token_config.accepted = false;
+    save_payment_token_config(&payment_token, &token_config);
+    casper_event_standard::emit(events::PaymentTokenRemoved::new(payment_token));
 }
 
 #[no_mangle]
@@ -221,6 +388,17 @@ pub extern "C" fn get_commitment() {
     runtime::ret(CLValue::from_t(read_request_commitment(&request_id)).unwrap_or_revert())
 }
 
+/**
+ * @notice Request a set of random words
+ * @param key_hash proving key the request is for
+ * @param sub_id subscription paying for the request, the caller must be one of its consumers
+ * @param request_confirmations blocks the oracle waits before fulfilling
+ * @param callback_gas_limit gas charged for the consumer callback
+ * @param num_words number of random words requested
+ * @param pull optional, stores the words for get_random_words and claim_random_words instead
+ * @param of calling raw_fulfill_random_words, defaults to false
+ * @return request id
+ */
 #[no_mangle]
 pub extern "C" fn request_random_words() {
     when_not_locked();
@@ -234,6 +412,11 @@ pub extern "C" fn request_random_words() {
             "callback_gas_limit",
             "num_words",
         ]));
+    let pull = helpers::get_optional_named_arg_with_user_errors::<bool>(
+        "pull",
+        Error::FailedToDecodeInputBytes,
+    )
+    .unwrap_or_default();
     let subscription_config = read_subscription_config(&sub_id);
     if subscription_config.owner == null_key() {
         revert(Error::InvalidSubscription);
@@ -255,20 +438,33 @@ pub extern "C" fn request_random_words() {
     if num_words > MAX_NUM_WORDS {
         revert(Error::NumWordsTooBig);
     }
+    _use_subscription_limits(sub_id, caller, num_words);
     let nonce = current_nonce + 1;
     let (request_id, pre_seed) = _compute_request_id(key_hash, caller, sub_id, nonce);
     helpers::log_msg(&format!("request_random_words request_id {:?}", &request_id.to_string()));
-    // save block timestamp instead of block height as there is no way to get block height from contract
-    save_request_commitment(
+    // contracts cannot read the block height, requests are anchored to the block timestamp
+    // num_words is committed as u32, the type of RequestCommitment.num_words checked on fulfillment
+    let commitment = RequestCommitment {
+        block: BlockRef {
+            timestamp: current_block_timestamp(),
+            ..Default::default()
+        },
+        request_confirmations,
+        sub_id,
+        callback_gas_limit,
+        num_words: num_words as u32,
+        sender: caller,
+    };
+    let block = commitment.block;
+    save_request_commitment(&request_id, &_commitment_of(&request_id, &commitment));
+    save_request(
         &request_id,
-        &runtime::blake2b(helpers::encode_6(
-            &request_id,
-            &current_block_timestamp(),
-            &sub_id,
-            &callback_gas_limit,
-            &num_words,
-            &caller,
-        )),
+        &RequestRecord {
+            key_hash,
+            pre_seed,
+            commitment,
+            status: RequestStatus::Pending,
+        },
     );
     casper_event_standard::emit(common::events::RandomWordsRequested::new(
         key_hash,
@@ -279,25 +475,261 @@ pub extern "C" fn request_random_words() {
         callback_gas_limit,
         num_words,
         caller,
+        block,
     ));
     save_consumer(&caller, &sub_id, &nonce);
+    _add_pending_request(sub_id);
+    if pull {
+        save_random_words_recipient(&request_id, &caller);
+    }
 
     unlock_contract();
 
     runtime::ret(CLValue::from_t(request_id).unwrap_or_revert())
 }
 
+/**
+ * @notice Fulfills a request and pays the oracle from the subscription
+ * @param proof serialized Proof
+ * @param rc serialized RequestCommitment of the request
+ * @param skip_callback optional, stores the words for get_random_words instead of calling the
+ * @param consumer, defaults to false
+ * @param callback_gas_used optional, gas the oracle measured for the consumer callback, which
+ * @param is then billed instead of the request's callback_gas_limit
+ * @return payment charged to the subscription in payment token units and in motes, only one
+ * @return of them is non-zero
+ * @dev a reverting consumer reverts the fulfillment, so the oracle retries with skip_callback
+ * @dev to settle the request, RandomWordsFulfilled then reports success = false
+ * @dev the oracle is trusted to skip only callbacks that failed, which the coordinator cannot
+ * @dev check: a skipped callback bills gas_after_payment_calculation without the flat fee, so
+ * @dev skipping never pays more than calling, and the consumer can still read the words
+ * @dev a pull request bills gas_after_payment_calculation and the fee only
+ * @dev a callback measured above its callback_gas_limit is not made, the oracle must skip it
+ * @dev callback_gas_used is trusted from the oracle of the proving key, contracts cannot read
+ * @dev the gas a call used: an oracle reporting more than it measured bills the subscription
+ * @dev at most the callback_gas_limit the consumer committed to
+ */
 #[no_mangle]
 pub extern "C" fn fulfill_random_words() {
     when_not_locked();
     lock_contract();
     let proof: Bytes = runtime::get_named_arg("proof");
     let rc: Bytes = runtime::get_named_arg("rc");
+    let skip_callback = helpers::get_optional_named_arg_with_user_errors::<bool>(
+        "skip_callback",
+        Error::FailedToDecodeInputBytes,
+    )
+    .unwrap_or_default();
+    let callback_gas_used = helpers::get_optional_named_arg_with_user_errors::<U128>(
+        "callback_gas_used",
+        Error::FailedToDecodeInputBytes,
+    );
 
     let proof = Proof::from_bytes(proof.as_slice()).unwrap().0;
     let rc = RequestCommitment::from_bytes(rc.as_slice()).unwrap().0;
 
-    let (key_hash, request_id, randomness) = _get_randomness_from_proof(&proof, &rc);
+    let (_, payment, native_payment) =
+        _fulfill_random_words(&proof, &rc, skip_callback, callback_gas_used).unwrap_or_revert();
+
+    unlock_contract();
+
+    runtime::ret(CLValue::from_t((payment, native_payment)).unwrap_or_revert())
+}
+
+/**
+ * @notice Fulfills several requests in one deploy
+ * @param requests (proof, rc, (skip_callback, callback_gas_used)) items, the arguments
+ * @param requests fulfill_random_words takes for each request
+ * @return request ids, token and native payments of the fulfilled requests
+ * @dev an item that fails verification or payment is skipped and reported with
+ * @dev RandomWordsFulfillmentFailed, a reverting consumer callback still reverts the batch
+ */
+#[no_mangle]
+pub extern "C" fn fulfill_random_words_batch() {
+    when_not_locked();
+    lock_contract();
+    let requests: Vec<(Bytes, Bytes, (bool, Option<U128>))> = runtime::get_named_arg("requests");
+
+    let mut fulfilled: Vec<(U256, U128, U512)> = vec![];
+    for (index, (proof, rc, (skip_callback, callback_gas_used))) in requests.iter().enumerate() {
+        let (proof, rc) = match (
+            Proof::from_bytes(proof.as_slice()),
+            RequestCommitment::from_bytes(rc.as_slice()),
+        ) {
+            (Ok((proof, _)), Ok((rc, _))) => (proof, rc),
+            _ => {
+                casper_event_standard::emit(common::events::RandomWordsFulfillmentFailed::new(
+                    index as u32,
+                    U256::zero(),
+                    Error::FailedToDecodeInputBytes,
+                ));
+                continue;
+            }
+        };
+        match _fulfill_random_words(&proof, &rc, *skip_callback, *callback_gas_used) {
+            Ok(item) => fulfilled.push(item),
+            Err(e) => {
+                let (_, request_id) = _request_id_of_proof(&proof);
+                casper_event_standard::emit(common::events::RandomWordsFulfillmentFailed::new(
+                    index as u32,
+                    request_id,
+                    e,
+                ));
+            }
+        }
+    }
+
+    unlock_contract();
+
+    runtime::ret(CLValue::from_t(fulfilled).unwrap_or_revert())
+}
+
+/**
+ * @notice Cancels a request the oracle did not fulfill within the request timeout
+ * @param request_id id of the request
+ * @param rc serialized RequestCommitment of the request
+ * @dev callable by the requesting consumer or the subscription owner, nothing was charged
+ * @dev for the request so the subscription balance is left as is
+ */
+#[no_mangle]
+pub extern "C" fn cancel_request() {
+    when_not_locked();
+    let (request_id, rc): (U256, Bytes) =
+        helpers::get_named_args_2(to_vec_string(&["request_id", "rc"]));
+    let rc = RequestCommitment::from_bytes(rc.as_slice())
+        .unwrap_or_revert_with(Error::FailedToDecodeInputBytes)
+        .0;
+    let commitment = read_request_commitment(&request_id);
+    if commitment == HashAddr::default() {
+        revert(Error::NoCorrespondingRequest);
+    }
+    if commitment != _commitment_of(&request_id, &rc) {
+        revert(Error::IncorrectCommitment);
+    }
+    let caller = get_immediate_caller_key();
+    if caller != rc.sender && caller != read_subscription_config(&rc.sub_id).owner {
+        revert(Error::OnlyConsumerOrSubOwner);
+    }
+    let request_timeout_seconds = read_config().request_timeout_seconds;
+    if request_timeout_seconds == 0
+        || current_block_timestamp() < rc.block.timestamp + request_timeout_seconds
+    {
+        revert(Error::RequestNotExpired);
+    }
+
+    save_request_commitment(&request_id, &Default::default());
+    _remove_pending_request(rc.sub_id);
+    _set_request_status(&request_id, RequestStatus::Cancelled);
+    casper_event_standard::emit(common::events::RandomWordsRequestCancelled::new(
+        request_id, rc.sub_id, caller,
+    ));
+}
+
+/**
+ * @notice Hands the stored words of a fulfilled request to its requester
+ * @param request_id id of a pull request or of a request fulfilled without its callback
+ * @return the random words, which are removed from the coordinator
+ */
+#[no_mangle]
+pub extern "C" fn claim_random_words() {
+    when_not_locked();
+    let request_id: U256 = runtime::get_named_arg("request_id");
+    if read_random_words_recipient(&request_id) != get_immediate_caller_key() {
+        revert(Error::NotRandomWordsRecipient);
+    }
+    let random_words = read_random_words(&request_id);
+    if random_words.is_empty() {
+        revert(Error::RandomWordsNotFulfilled);
+    }
+    save_random_words(&request_id, &vec![]);
+    runtime::ret(CLValue::from_t(random_words).unwrap_or_revert())
+}
+
+/**
+ * @notice Verifies a fulfillment, delivers its words to the consumer and charges the subscription
+ * @param skip_callback stores the words instead of calling the consumer
+ * @param callback_gas_used gas the oracle measured for the consumer callback, if it did
+ * @return request id, payment in payment token units and payment in motes, the subscription
+ * @return pays in one of them so the other is zero
+ * @dev every check runs before state is written, so an error leaves no change behind
+ */
+fn _fulfill_random_words(
+    proof: &Proof,
+    rc: &RequestCommitment,
+    skip_callback: bool,
+    callback_gas_used: Option<U128>,
+) -> Result<(U256, U128, U512), Error> {
+    let verified = _get_randomness_from_proof(proof, rc)?;
+    _settle_fulfillment(verified, rc, skip_callback, callback_gas_used)
+}
+
+/// Pays for and delivers the random words of a verified proof. Kept apart from the proof
+/// verification so that the curve arithmetic runs under a shallow call stack, the deploy
+/// fails past 200 stack values.
+#[inline(never)]
+fn _settle_fulfillment(
+    (key_hash, request_id, randomness): (HashAddr, U256, U256),
+    rc: &RequestCommitment,
+    skip_callback: bool,
+    callback_gas_used: Option<U128>,
+) -> Result<(U256, U128, U512), Error> {
+    let pull = read_random_words_recipient(&request_id) != null_key();
+    // Casper cannot catch a revert of the consumer, so a failed callback is only
+    // settled by a retry that skips it
+    let success = !skip_callback;
+    let call_consumer = success && !pull;
+    if call_consumer && callback_gas_used.map_or(false, |gas| gas > rc.callback_gas_limit) {
+        return Err(Error::InsufficientGasForConsumer);
+    }
+
+    // the owner may cancel a subscription with requests in flight, which then have no one to
+    // pay for them
+    let subscription_config = read_subscription_config(&rc.sub_id);
+    if subscription_config.owner == null_key() {
+        return Err(Error::InvalidSubscription);
+    }
+    // the coordinator is locked during the consumer call, so the subscription cannot change
+    let mut subscription = read_subscription(&rc.sub_id);
+    let req_count = subscription.req_count;
+    let config = read_config();
+    let payment_token = subscription_config.payment_token;
+    let token_config = _payment_token_config(payment_token, &config);
+    // a skipped or pull fulfillment makes no callback, so it pays no callback gas
+    let gas_used = if call_consumer {
+        _callback_gas_used(rc, &config, callback_gas_used)
+    } else {
+        U128::zero()
+    };
+    // the coordinator cannot tell a failing callback from one the oracle chose to skip, so a
+    // skipped fulfillment only covers the gas and earns the oracle no flat fee
+    let fee_ppm = if success {
+        token_config.fee_config.fee_tier(req_count).1
+    } else {
+        0
+    };
+    let motes_per_unit_link = _get_feed_data(&config, &token_config);
+    let payment = _calculate_payment_amount(
+        gas_used,
+        config.gas_after_payment_calculation,
+        fee_ppm,
+        motes_per_unit_link,
+    )?;
+    // the payment token pays while it can, CSPR covers the rest of the fulfillments
+    let paid_in_cspr = subscription.balance < payment;
+    let native_payment = if paid_in_cspr {
+        let native_payment = _calculate_native_payment_amount(
+            gas_used,
+            config.gas_after_payment_calculation,
+            fee_ppm,
+            motes_per_unit_link,
+        );
+        if subscription.native_balance < native_payment {
+            return Err(Error::InsufficientBalance);
+        }
+        native_payment
+    } else {
+        U512::zero()
+    };
 
     let mut random_words: Vec<U256> = vec![];
     for i in 0..rc.num_words {
@@ -308,35 +740,49 @@ pub extern "C" fn fulfill_random_words() {
     }
 
     save_request_commitment(&request_id, &Default::default());
+    _remove_pending_request(rc.sub_id);
 
-    let success = true;
-    call_raw_fulfill_random_words(rc.sender, request_id, &random_words);
+    _set_request_status(
+        &request_id,
+        if success {
+            RequestStatus::Fulfilled
+        } else {
+            RequestStatus::Failed
+        },
+    );
+    if call_consumer {
+        call_raw_fulfill_random_words(rc.sender, request_id, &random_words);
+    } else {
+        save_random_words(&request_id, &random_words);
+        save_random_words_recipient(&request_id, &rc.sender);
+    }
 
     // update after consumer call
-    let mut subscription = read_subscription(&rc.sub_id);
     subscription.req_count += 1;
-
-    // handle payment
-    let payment = _calculate_payment_amount();
-
-    if subscription.balance < payment {
-        revert(Error::InsufficientBalance);
-    }
-
-    subscription.balance -= payment;
-    let proving_key = read_proving_key(&key_hash);
-    let mut withdrawnable_token = read_withdrawable_token(&proving_key);
-    withdrawnable_token += payment;
-    save_withdrawable_token(&proving_key, &withdrawnable_token);
+    // CSPR payments count at their payment token price
+    _count_subscription_spend(rc.sub_id, payment);
+    let oracle = read_proving_key(&key_hash).oracle;
+    let payment = if paid_in_cspr {
+        subscription.native_balance -= native_payment;
+        save_withdrawable_cspr(&oracle, &(read_withdrawable_cspr(&oracle) + native_payment));
+        U128::zero()
+    } else {
+        subscription.balance -= payment;
+        let mut withdrawnable_token = read_withdrawable_token(&oracle, &payment_token);
+        withdrawnable_token += payment;
+        save_withdrawable_token(&oracle, &payment_token, &withdrawnable_token);
+        payment
+    };
     save_subscription(&rc.sub_id, &subscription);
 
     casper_event_standard::emit(events::RandomWordsFulfilled::new(
-        request_id, randomness, payment, success,
+        request_id,
+        randomness,
+        payment,
+        native_payment,
+        success,
     ));
-
-    unlock_contract();
-
-    runtime::ret(CLValue::from_t(payment).unwrap_or_revert())
+    Ok((request_id, payment, native_payment))
 }
 
 #[no_mangle]
@@ -352,31 +798,119 @@ pub extern "C" fn deposit_token() {
     if subscription_config.owner == null_key() {
         revert(Error::InvalidSubscription);
     }
+    let payment_token = subscription_config.payment_token;
+    erc20_helpers::transfer_from(payment_token, caller, helpers::get_self_key(), amount);
+    _fund_subscription(sub_id, payment_token, amount);
+    unlock_contract();
+}
+
+/**
+ * @notice Funds a subscription in a single step, called by the payment token after it
+ * @notice transferred tokens to the coordinator
+ * @param amount tokens transferred
+ * @param data serialized u64 id of the subscription to fund
+ * @dev only an accepted payment token can call it, the tokens are the coordinator's by then,
+ * @dev and it must be the token the subscription pays in, balances are U128 so amount must fit
+ * @dev one; the sender the token passes along is not read, anyone may fund any subscription
+ */
+#[no_mangle]
+pub extern "C" fn on_token_transfer() {
+    when_not_locked();
+    lock_contract();
+    let payment_token = get_immediate_caller_key();
+    if !_payment_token_config(payment_token, &read_config()).accepted {
+        revert(Error::OnlyCallableFromLink);
+    }
+    let (amount, data): (U256, Bytes) =
+        helpers::get_named_args_2(to_vec_string(&["amount", "data"]));
+    let sub_id = match u64::from_bytes(data.as_slice()) {
+        Ok((sub_id, remainder)) if remainder.is_empty() => sub_id,
+        _ => revert(Error::InvalidCalldata),
+    };
+    let subscription_config = read_subscription_config(&sub_id);
+    if subscription_config.owner == null_key() {
+        revert(Error::InvalidSubscription);
+    }
+    if subscription_config.payment_token != payment_token {
+        revert(Error::WrongPaymentToken);
+    }
+    if amount > U256::from(u128::MAX) {
+        revert(Error::AmountTooLarge);
+    }
+    _fund_subscription(sub_id, payment_token, U128::from(amount.as_u128()));
+    unlock_contract();
+}
+
+/// Credits `amount` of `payment_token` the coordinator received to subscription `sub_id`.
+fn _fund_subscription(sub_id: u64, payment_token: Key, amount: U128) {
     let mut subscription = read_subscription(&sub_id);
     let old_balance = subscription.balance;
-    erc20_helpers::transfer_from(
-        read_payment_token(),
-        caller,
-        helpers::get_self_key(),
-        amount,
-    );
     subscription.balance += amount;
     save_subscription(&sub_id, &subscription);
-    save_total_balance(read_total_balance() + amount);
+    save_total_balance(
+        &payment_token,
+        &(read_total_balance(&payment_token) + amount),
+    );
 
     casper_event_standard::emit(events::SubscriptionFunded::new(
         sub_id,
         old_balance,
         subscription.balance,
     ));
+}
+
+/**
+ * @notice Funds a subscription with CSPR
+ * @param sub_id subscription to fund
+ * @param purse purse of the caller the motes are taken from
+ * @param amount motes to deposit
+ * @dev CSPR is kept apart from the payment token balance and pays the fulfillments that
+ * @dev balance cannot cover, the caller must pass a purse it can withdraw from
+ */
+#[no_mangle]
+pub extern "C" fn deposit_cspr() {
+    when_not_locked();
+    lock_contract();
+    let (sub_id, purse, amount): (u64, URef, U512) =
+        helpers::get_named_args_3(to_vec_string(&["sub_id", "purse", "amount"]));
+    if read_subscription_config(&sub_id).owner == null_key() {
+        revert(Error::InvalidSubscription);
+    }
+    system::transfer_from_purse_to_purse(purse, contract_purse(), amount, None).unwrap_or_revert();
+
+    let mut subscription = read_subscription(&sub_id);
+    let old_balance = subscription.native_balance;
+    subscription.native_balance += amount;
+    save_subscription(&sub_id, &subscription);
+    save_total_native_balance(read_total_native_balance() + amount);
+
+    casper_event_standard::emit(events::SubscriptionFundedWithCspr::new(
+        sub_id,
+        old_balance,
+        subscription.native_balance,
+    ));
     unlock_contract();
 }
 
+/**
+ * @notice Creates a subscription owned by the caller
+ * @param payment_token optional, token the subscription pays in, defaults to payment_token
+ * @return id of the subscription
+ * @dev any other token must be accepted with set_payment_token_config
+ */
 #[no_mangle]
 pub extern "C" fn create_subscription() {
     when_not_locked();
     lock_contract();
 
+    // the token is stored as is, replacing the coordinator's payment_token later leaves the
+    // subscription paying in the token it was created with
+    let payment_token = _payment_token_arg();
+    if payment_token != read_payment_token() && !read_payment_token_config(&payment_token).accepted
+    {
+        revert(Error::PaymentTokenNotAccepted);
+    }
+
     let mut sub_id = read_current_sub_id();
     sub_id += 1;
     save_current_sub_id(sub_id);
@@ -387,6 +921,7 @@ pub extern "C" fn create_subscription() {
         &sub_id,
         &Subscription {
             balance: 0.into(),
+            native_balance: U512::zero(),
             req_count: 0,
         },
     );
@@ -397,12 +932,14 @@ pub extern "C" fn create_subscription() {
             owner: helpers::get_immediate_caller_key(),
             requested_owner: null_key(),
             consumers,
+            payment_token,
         },
     );
 
     casper_event_standard::emit(events::SubscriptionCreated::new(
         sub_id,
         helpers::get_immediate_caller_key(),
+        payment_token,
     ));
 
     unlock_contract();
@@ -454,6 +991,12 @@ pub extern "C" fn accept_subscription_owner_transfer() {
     ));
 }
 
+/**
+ * @notice Withdraws payment tokens the oracle earned
+ * @param recipient receiver of the tokens
+ * @param amount tokens to withdraw
+ * @param payment_token optional, token to withdraw, defaults to payment_token
+ */
 #[no_mangle]
 pub extern "C" fn oracle_withdraw() {
     when_not_locked();
@@ -461,17 +1004,50 @@ pub extern "C" fn oracle_withdraw() {
 
     let amount: U128 = runtime::get_named_arg("amount");
     let recipient: Key = runtime::get_named_arg("recipient");
+    let payment_token = _payment_token_arg();
 
     let caller = helpers::get_immediate_caller_key();
-    let mut withdrawnable_token = read_withdrawable_token(&caller);
+    let mut withdrawnable_token = read_withdrawable_token(&caller, &payment_token);
     if withdrawnable_token < amount {
         revert(Error::InsufficientBalance);
     }
 
     withdrawnable_token -= amount;
-    save_total_balance(read_total_balance() - amount);
+    save_withdrawable_token(&caller, &payment_token, &withdrawnable_token);
+    save_total_balance(
+        &payment_token,
+        &(read_total_balance(&payment_token) - amount),
+    );
 
-    erc20_helpers::transfer(read_payment_token(), recipient, amount);
+    erc20_helpers::transfer(payment_token, recipient, amount);
+
+    unlock_contract();
+}
+
+/**
+ * @notice Withdraws CSPR the oracle earned from subscriptions paying in CSPR
+ * @param recipient account receiving the motes
+ * @param amount motes to withdraw
+ */
+#[no_mangle]
+pub extern "C" fn oracle_withdraw_cspr() {
+    when_not_locked();
+    lock_contract();
+
+    let amount: U512 = runtime::get_named_arg("amount");
+    let recipient: Key = runtime::get_named_arg("recipient");
+
+    let caller = helpers::get_immediate_caller_key();
+    let mut withdrawable_cspr = read_withdrawable_cspr(&caller);
+    if withdrawable_cspr < amount {
+        revert(Error::InsufficientBalance);
+    }
+
+    withdrawable_cspr -= amount;
+    save_withdrawable_cspr(&caller, &withdrawable_cspr);
+    save_total_native_balance(read_total_native_balance() - amount);
+
+    _transfer_cspr(recipient, amount);
 
     unlock_contract();
 }
@@ -535,6 +1111,41 @@ pub extern "C" fn add_consumer() {
     unlock_contract();
 }
 
+/**
+ * @notice Sets the limits on the use of a subscription, zero leaves a limit unset
+ * @param sub_id subscription to limit, the caller must be its owner
+ * @param window_seconds length of the windows spend and requests are counted in
+ * @param max_spend_per_window payment fulfillments may charge in one window, requests are refused
+ * @param once it is reached
+ * @param max_requests_per_consumer requests each consumer may make in one window
+ * @param max_num_words words one request may ask for
+ */
+#[no_mangle]
+pub extern "C" fn set_subscription_limits() {
+    let sub_id: u64 = runtime::get_named_arg("sub_id");
+    only_sub_owner(sub_id);
+    when_not_locked();
+    let (window_seconds, max_spend_per_window, max_requests_per_consumer, max_num_words) =
+        helpers::get_named_args_4::<u64, U128, u64, u64>(to_vec_string(&[
+            "window_seconds",
+            "max_spend_per_window",
+            "max_requests_per_consumer",
+            "max_num_words",
+        ]));
+    let limits = SubscriptionLimits {
+        window_seconds,
+        max_spend_per_window,
+        max_requests_per_consumer,
+        max_num_words,
+    };
+    // spend and requests are only counted within a window
+    if window_seconds == 0 && (!max_spend_per_window.is_zero() || max_requests_per_consumer > 0) {
+        revert(Error::InvalidSubscriptionLimits);
+    }
+    save_subscription_limits(&sub_id, &limits);
+    casper_event_standard::emit(events::SubscriptionLimitsSet::new(sub_id, limits));
+}
+
 #[no_mangle]
 pub extern "C" fn cancel_subscription() {
     let sub_id: u64 = runtime::get_named_arg("sub_id");
@@ -561,16 +1172,40 @@ fn _cancel_subscription_helper(sub_id: u64, to: Key) {
     let sub_config = read_subscription_config(&sub_id);
     let sub = read_subscription(&sub_id);
     let balance = sub.balance;
+    let native_balance = sub.native_balance;
     let consumer_len = sub_config.consumers.len();
     for i in 0..consumer_len {
         save_consumer(&sub_config.consumers[i], &sub_id, &Default::default());
     }
     save_subscription_config(&sub_id, &Default::default());
     save_subscription(&sub_id, &Default::default());
-    let total_balance = read_total_balance();
-    save_total_balance(total_balance - balance);
-    erc20_helpers::transfer(read_payment_token(), to, balance);
-    casper_event_standard::emit(events::SubscriptionCanceled::new(sub_id, to, balance));
+    save_pending_request_count(&sub_id, &0);
+    save_subscription_limits(&sub_id, &Default::default());
+    save_subscription_usage(&sub_id, &Default::default());
+    let payment_token = sub_config.payment_token;
+    let total_balance = read_total_balance(&payment_token);
+    save_total_balance(&payment_token, &(total_balance - balance));
+    erc20_helpers::transfer(payment_token, to, balance);
+    if !native_balance.is_zero() {
+        save_total_native_balance(read_total_native_balance() - native_balance);
+        _transfer_cspr(to, native_balance);
+    }
+    casper_event_standard::emit(events::SubscriptionCanceled::new(
+        sub_id,
+        to,
+        balance,
+        native_balance,
+    ));
+}
+
+/// Sends `amount` motes of the contract purse to account `to`, purses of contracts cannot be
+/// reached from a key.
+fn _transfer_cspr(to: Key, amount: U512) {
+    let account = to
+        .into_account()
+        .unwrap_or_revert_with(Error::CsprRecipientMustBeAccount);
+    system::transfer_from_purse_to_account(contract_purse(), account, amount, None)
+        .unwrap_or_revert();
 }
 
 fn _compute_request_id(kh: HashAddr, sender: Key, sub_id: u64, nonce: u64) -> (U256, U256) {
@@ -582,12 +1217,60 @@ fn _compute_request_id(kh: HashAddr, sender: Key, sub_id: u64, nonce: u64) -> (U
     )
 }
 
-fn _get_randomness_from_proof(proof: &Proof, rc: &RequestCommitment) -> (HashAddr, U256, U256) {
-    helpers::log_msg("_get_randomness_from_proof");
+/// Key hash of the proof's key and id of the request it is for.
+fn _request_id_of_proof(proof: &Proof) -> (HashAddr, U256) {
     let key_hash = _hash_of_key(&proof.pk.to_vec());
-    let oracle = read_proving_key(&key_hash);
-    if oracle == null_key() {
-        revert(Error::NoSuchProvingKey);
+    let request_id = u256_from_hash(runtime::blake2b(helpers::encode_2(
+        &key_hash,
+        &proof.seed.to_string(),
+    )));
+    (key_hash, request_id)
+}
+
+/// Commitment stored for request `request_id` when it was made with `rc`.
+fn _commitment_of(request_id: &U256, rc: &RequestCommitment) -> HashAddr {
+    runtime::blake2b(helpers::encode_7(
+        request_id,
+        &rc.block.timestamp,
+        &rc.request_confirmations,
+        &rc.sub_id,
+        &rc.callback_gas_limit,
+        &rc.num_words,
+        &rc.sender,
+    ))
+}
+
+#[inline(never)]
+fn _get_randomness_from_proof(
+    proof: &Proof,
+    rc: &RequestCommitment,
+) -> Result<(HashAddr, U256, U256), Error> {
+    let (key_hash, request_id, key_type, actual_seed) = _get_seed_of_proof(proof, rc)?;
+    let randomness = match key_type {
+        ProvingKeyType::Secp256k1Witness => vrf::random_value_from_vrf_proof(proof, actual_seed)?,
+        ProvingKeyType::Secp256k1Rfc9381 => {
+            ecvrf::secp256k1::random_value_from_vrf_proof(proof, actual_seed)?
+        }
+        ProvingKeyType::Ed25519Rfc9381 => {
+            ecvrf::edwards25519::random_value_from_vrf_proof(proof, actual_seed)?
+        }
+    };
+    Ok((key_hash, request_id, randomness))
+}
+
+/// Key hash, request id, key type and the seed the proof must be for, once the request of
+/// `proof` is checked against `rc`. Out of line so that its frame is not under the proof
+/// verification.
+#[inline(never)]
+fn _get_seed_of_proof(
+    proof: &Proof,
+    rc: &RequestCommitment,
+) -> Result<(HashAddr, U256, ProvingKeyType, U256), Error> {
+    helpers::log_msg("_get_randomness_from_proof");
+    let (key_hash, request_id) = _request_id_of_proof(proof);
+    let proving_key = read_proving_key(&key_hash);
+    if proving_key.oracle == null_key() {
+        return Err(Error::NoSuchProvingKey);
     }
 
     helpers::log_msg(
@@ -596,29 +1279,28 @@ fn _get_randomness_from_proof(proof: &Proof, rc: &RequestCommitment) -> (HashAdd
             .to_string(),
     );
     helpers::log_msg(&format!("_get_randomness_from_proof kh {:?}, pre_seed {:?}", &hex::encode(key_hash), &proof.seed.to_string()));
-    let request_id = u256_from_hash(runtime::blake2b(helpers::encode_2(&key_hash, &proof.seed.to_string())));
     helpers::log_msg(&format!("_get_randomness_from_proof request_id {:?}", &request_id.to_string()));
     let commitment = read_request_commitment(&request_id);
     if commitment == HashAddr::default() {
-        revert(Error::NoCorrespondingRequest);
+        return Err(Error::NoCorrespondingRequest);
     }
 
-    if commitment
-        != runtime::blake2b(helpers::encode_6(
-            &request_id,
-            &rc.block_num,
-            &rc.sub_id,
-            &rc.callback_gas_limit,
-            &rc.num_words,
-            &rc.sender,
-        ))
-    {
-        revert(Error::IncorrectCommitment);
+    if commitment != _commitment_of(&request_id, rc) {
+        return Err(Error::IncorrectCommitment);
     }
 
-    let block_hash = get_block_hash(read_block_hash_store(), rc.block_num);
+    let block_hash_store = read_block_hash_store();
+    let block_hash = get_block_hash(block_hash_store, rc.block.timestamp);
     if block_hash.is_empty() {
-        revert(Error::BlockhashNotInStore);
+        return Err(Error::BlockhashNotInStore);
+    }
+    // the request only committed to the timestamp, the era and height must be the stored ones
+    if get_block_ref(block_hash_store, rc.block.timestamp) != rc.block {
+        return Err(Error::IncorrectBlockRef);
+    }
+    // the store must have seen request_confirmations blocks on top of the request block
+    if get_latest_block_ref(block_hash_store).height < rc.block.height + rc.request_confirmations {
+        return Err(Error::NotEnoughConfirmations);
     }
 
     let block_hash = hex::decode(block_hash).unwrap();
@@ -628,47 +1310,207 @@ fn _get_randomness_from_proof(proof: &Proof, rc: &RequestCommitment) -> (HashAdd
         &block_hash,
     )));
 
-    let randomness = vrf::random_value_from_vrf_proof(proof, actual_seed);
-    (key_hash, request_id, randomness)
-}
-
-fn _get_fee_tier(req_count: u64) -> u64 {
-    let req_count = req_count as u32;
-    let fc = read_fee_config();
-    if req_count <= fc.reqs_for_tier2 {
-        fc.fulfillment_flat_fee_link_ppm_tier1
-    } else if fc.reqs_for_tier2 < req_count && req_count <= fc.reqs_for_tier3 {
-        fc.fulfillment_flat_fee_link_ppm_tier2
-    } else if fc.reqs_for_tier3 < req_count && req_count <= fc.reqs_for_tier4 {
-        fc.fulfillment_flat_fee_link_ppm_tier3
-    } else if fc.reqs_for_tier4 < req_count && req_count <= fc.reqs_for_tier4 {
-        fc.fulfillment_flat_fee_link_ppm_tier4
-    } else {
-        fc.fulfillment_flat_fee_link_ppm_tier5
+    Ok((key_hash, request_id, proving_key.key_type, actual_seed))
+}
+
+/// Fee tiers in `fee_config_bytes`, which must be a valid FeeConfig.
+fn _decode_fee_config(fee_config_bytes: &Bytes) -> FeeConfig {
+    let fee_config = FeeConfig::from_bytes(fee_config_bytes)
+        .unwrap_or_revert_with(Error::FailedToDecodeInputBytes)
+        .0;
+    if !fee_config.is_valid() {
+        revert(Error::InvalidFeeConfig);
     }
+    fee_config
 }
 
-fn _calculate_payment_amount() -> U128 {
-    // TODO: compute payment amount
-    0.into()
+/**
+ * @notice Gas billed for the consumer callback of a fulfillment
+ * @param callback_gas_used gas the oracle measured for the callback, if it did
+ * @dev contracts cannot read the remaining gas of a deploy, so the callback is billed at the
+ * @dev measured gas plus GAS_FOR_CALL_EXACT_CHECK for the call itself, or at the gas limit
+ * @dev committed by the request when nothing was measured, capped by that limit and the
+ * @dev current max_gas_limit
+ * @dev the measured gas is trusted from the registered oracle and cannot be checked on-chain,
+ * @dev the cap bounds what a dishonest oracle can bill to what the consumer committed to pay
+ */
+fn _callback_gas_used(
+    rc: &RequestCommitment,
+    config: &Config,
+    callback_gas_used: Option<U128>,
+) -> U128 {
+    let gas_limit = core::cmp::min(rc.callback_gas_limit, config.max_gas_limit);
+    match callback_gas_used {
+        Some(gas_used) => core::cmp::min(
+            gas_used.saturating_add(U128::from(GAS_FOR_CALL_EXACT_CHECK)),
+            gas_limit,
+        ),
+        None => gas_limit,
+    }
 }
 
-pub fn pending_request_exists(sub_id: u64) -> bool {
-    let sub_config = read_subscription_config(&sub_id);
-    let proving_key_hashes = read_proving_key_hashes();
-    for i in 0..sub_config.consumers.len() {
-        for item in &proving_key_hashes {
-            let (req_id, _) = _compute_request_id(
-                item.to_owned(),
-                sub_config.consumers[i],
-                sub_id,
-                read_consumer(&sub_config.consumers[i], &sub_id),
-            );
-
-            if read_request_commitment(&req_id) != HashAddr::default() {
-                return true;
-            }
+/**
+ * @notice Price of one payment token in motes
+ * @dev uses the fallback price when no feed is set or the feed is more stale than
+ * @dev staleness_seconds, a fresh zero answer is returned as is
+ */
+// inlined into its callers so that fulfillments keep within the 200 stack values of a deploy
+#[inline(always)]
+fn _get_feed_data(config: &Config, token_config: &PaymentTokenConfig) -> U256 {
+    if token_config.price_feed == null_key() {
+        return token_config.fallback_motes_per_unit_link;
+    }
+    // a zero answer is a broken feed rather than a missing one, the payment then fails with
+    // InvalidLinkWeiPrice instead of being priced at the fallback
+    let (motes_per_unit_link, updated_at) = get_latest_round_data(token_config.price_feed);
+    let staleness = current_block_timestamp().saturating_sub(updated_at);
+    if config.staleness_seconds > 0 && config.staleness_seconds < staleness {
+        return token_config.fallback_motes_per_unit_link;
+    }
+    motes_per_unit_link
+}
+
+/// Terms of `payment_token`, those of set_config for the coordinator's payment_token.
+fn _payment_token_config(payment_token: Key, config: &Config) -> PaymentTokenConfig {
+    if payment_token != read_payment_token() {
+        return read_payment_token_config(&payment_token);
+    }
+    PaymentTokenConfig {
+        accepted: true,
+        price_feed: read_price_feed(),
+        fallback_motes_per_unit_link: config.fallback_motes_per_unit_link,
+        fee_config: read_fee_config(),
+    }
+}
+
+/// Optional `payment_token` argument, the coordinator's payment_token when it is omitted.
+fn _payment_token_arg() -> Key {
+    helpers::get_optional_named_arg_with_user_errors::<Key>(
+        "payment_token",
+        Error::FailedToDecodeInputBytes,
+    )
+    .unwrap_or_else(read_payment_token)
+}
+
+/**
+ * @notice Computes the payment owed to the oracle for a fulfillment
+ * @param gas_used gas spent on the consumer callback
+ * @param gas_after_payment_calculation gas used in doing accounting after the payment calculation
+ * @param fulfillment_flat_fee_link_ppm flat fee of the subscription's tier, in millionths of a token
+ * @param motes_per_unit_link price of one payment token (10^18 units) in motes
+ * @dev the amount and its errors come from common::payment
+ */
+fn _calculate_payment_amount(
+    gas_used: U128,
+    gas_after_payment_calculation: U128,
+    fulfillment_flat_fee_link_ppm: u64,
+    motes_per_unit_link: U256,
+) -> Result<U128, Error> {
+    payment::calculate_payment_amount(
+        gas_used,
+        gas_after_payment_calculation,
+        fulfillment_flat_fee_link_ppm,
+        motes_per_unit_link,
+    )
+}
+
+/**
+ * @notice Computes the payment in motes owed to the oracle for a fulfillment paid in CSPR
+ * @dev same terms as _calculate_payment_amount, the flat fee is converted to motes at
+ * @dev motes_per_unit_link
+ */
+fn _calculate_native_payment_amount(
+    gas_used: U128,
+    gas_after_payment_calculation: U128,
+    fulfillment_flat_fee_link_ppm: u64,
+    motes_per_unit_link: U256,
+) -> U512 {
+    let gas = U512::from(gas_after_payment_calculation.as_u128()) + U512::from(gas_used.as_u128());
+    let fee = U512::from(1_000_000_000_000_u64)
+        * U512::from(fulfillment_flat_fee_link_ppm)
+        * helpers::u256_to_u512(motes_per_unit_link)
+        / U512::from(1_000_000_000_000_000_000_u64);
+    gas + fee
+}
+
+/**
+ * @notice Counts a request of consumer to subscription sub_id against the subscription limits
+ * @dev the payment of a request is only known once it is fulfilled, where it is counted by
+ * @dev _count_subscription_spend, so requests made before the spend limit is reached can
+ * @dev still take the window past it
+ */
+fn _use_subscription_limits(sub_id: u64, consumer: Key, num_words: u64) {
+    let limits = read_subscription_limits(&sub_id);
+    if limits.max_num_words > 0 && num_words > limits.max_num_words {
+        revert(Error::NumWordsAboveSubscriptionLimit);
+    }
+    if limits.window_seconds == 0 {
+        return;
+    }
+    let window = current_block_timestamp() / limits.window_seconds;
+
+    if limits.max_requests_per_consumer > 0 {
+        let mut usage = _window_usage(read_consumer_usage(&consumer, &sub_id), window);
+        if usage.requests >= limits.max_requests_per_consumer {
+            revert(Error::ConsumerRequestLimitExceeded);
+        }
+        usage.requests += 1;
+        save_consumer_usage(&consumer, &sub_id, &usage);
+    }
+
+    if !limits.max_spend_per_window.is_zero()
+        && _window_usage(read_subscription_usage(&sub_id), window).spent
+            >= limits.max_spend_per_window
+    {
+        revert(Error::SpendLimitExceeded);
+    }
+}
+
+/// Adds `payment`, in payment token units, of a fulfillment of subscription `sub_id` to the
+/// spend of the current window.
+fn _count_subscription_spend(sub_id: u64, payment: U128) {
+    let limits = read_subscription_limits(&sub_id);
+    if limits.window_seconds == 0 || limits.max_spend_per_window.is_zero() {
+        return;
+    }
+    let window = current_block_timestamp() / limits.window_seconds;
+    let mut usage = _window_usage(read_subscription_usage(&sub_id), window);
+    usage.spent = usage.spent.saturating_add(payment);
+    save_subscription_usage(&sub_id, &usage);
+}
+
+/// `usage` if it was counted in `window`, an empty usage of `window` otherwise.
+fn _window_usage(usage: WindowUsage, window: u64) -> WindowUsage {
+    if usage.window == window {
+        usage
+    } else {
+        WindowUsage {
+            window,
+            ..Default::default()
         }
     }
-    false
+}
+
+pub fn pending_request_exists(sub_id: u64) -> bool {
+    read_pending_request_count(&sub_id) > 0
+}
+
+/// Counts a new request of subscription `sub_id`, its status is tracked in its RequestRecord.
+fn _add_pending_request(sub_id: u64) {
+    save_pending_request_count(&sub_id, &(read_pending_request_count(&sub_id) + 1));
+}
+
+fn _set_request_status(request_id: &U256, status: RequestStatus) {
+    let mut request = read_request(request_id);
+    request.status = status;
+    save_request(request_id, &request);
+}
+
+/// Uncounts a request of subscription `sub_id`. The count is already zero for requests still in
+/// flight when the owner canceled the subscription.
+fn _remove_pending_request(sub_id: u64) {
+    save_pending_request_count(
+        &sub_id,
+        &read_pending_request_count(&sub_id).saturating_sub(1),
+    );
 }
@@ -8,7 +8,6 @@ mod entry_points;
 pub mod checks;
 pub mod events;
 pub mod logics;
-pub mod signature;
 pub mod store;
 pub mod vrf;
 
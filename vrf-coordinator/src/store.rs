@@ -1,10 +1,13 @@
 use alloc::{vec, vec::Vec};
-use casper_contract::contract_api::{runtime, storage};
+use casper_contract::contract_api::{runtime, storage, system};
 use casper_contract::unwrap_or_revert::UnwrapOrRevert;
-use casper_types::{CLValue, HashAddr, Key, U128, U256};
+use casper_types::{CLValue, HashAddr, Key, URef, U128, U256, U512};
 use common::error::Error;
 use common::{
-    data_types::{Config, FeeConfig, Subscription, SubscriptionConfig},
+    data_types::{
+        Config, FeeConfig, PaymentTokenConfig, ProvingKey, RequestRecord, Subscription,
+        SubscriptionConfig, SubscriptionLimits, WindowUsage,
+    },
     get_set, get_set_dict, get_set_nested_dict, get_set_no_set, helpers,
 };
 
@@ -16,14 +19,34 @@ pub fn initialize(payment_token: Key, block_hash_store: Key, price_feed: Key) {
     storage::new_dictionary("subscription_configs")
         .unwrap_or_revert_with(Error::FailedToCreateDictionary);
     storage::new_dictionary("subscriptions").unwrap_or_revert_with(Error::FailedToCreateDictionary);
+    storage::new_dictionary("subscription_limits")
+        .unwrap_or_revert_with(Error::FailedToCreateDictionary);
+    storage::new_dictionary("subscription_usage")
+        .unwrap_or_revert_with(Error::FailedToCreateDictionary);
+    storage::new_dictionary("consumer_usage")
+        .unwrap_or_revert_with(Error::FailedToCreateDictionary);
     save_current_sub_id(0);
-    save_total_balance(0.into());
+    storage::new_dictionary("payment_token_configs")
+        .unwrap_or_revert_with(Error::FailedToCreateDictionary);
+    save_payment_tokens(vec![]);
+    storage::new_dictionary("total_balances")
+        .unwrap_or_revert_with(Error::FailedToCreateDictionary);
+    save_total_native_balance(U512::zero());
+    contract_purse();
     storage::new_dictionary("proving_keys").unwrap_or_revert_with(Error::FailedToCreateDictionary);
     save_proving_key_hashes(vec![]);
     storage::new_dictionary("withdrawable_tokens")
         .unwrap_or_revert_with(Error::FailedToCreateDictionary);
+    storage::new_dictionary("withdrawable_cspr")
+        .unwrap_or_revert_with(Error::FailedToCreateDictionary);
     storage::new_dictionary("request_commitments")
         .unwrap_or_revert_with(Error::FailedToCreateDictionary);
+    storage::new_dictionary("requests").unwrap_or_revert_with(Error::FailedToCreateDictionary);
+    storage::new_dictionary("pending_request_counts")
+        .unwrap_or_revert_with(Error::FailedToCreateDictionary);
+    storage::new_dictionary("random_words").unwrap_or_revert_with(Error::FailedToCreateDictionary);
+    storage::new_dictionary("random_words_recipients")
+        .unwrap_or_revert_with(Error::FailedToCreateDictionary);
     save_config(Default::default());
     save_fee_config(Default::default());
 }
@@ -97,6 +120,44 @@ get_set_dict!(
     set_subscription
 );
 
+get_set_dict!(
+    "subscription_limits",
+    "sub_id",
+    u64,
+    SubscriptionLimits,
+    SubscriptionLimits::default(),
+    save_subscription_limits,
+    read_subscription_limits,
+    get_subscription_limits,
+    set_subscription_limits
+);
+
+get_set_dict!(
+    "subscription_usage",
+    "sub_id",
+    u64,
+    WindowUsage,
+    WindowUsage::default(),
+    save_subscription_usage,
+    read_subscription_usage,
+    get_subscription_usage,
+    set_subscription_usage
+);
+
+get_set_nested_dict!(
+    "consumer_usage",
+    "consumer",
+    "sub_id",
+    Key,
+    u64,
+    WindowUsage,
+    WindowUsage::default(),
+    save_consumer_usage,
+    read_consumer_usage,
+    get_consumer_usage,
+    set_consumer_usage
+);
+
 get_set_no_set!(
     current_sub_id,
     "current_sub_id",
@@ -107,22 +168,71 @@ get_set_no_set!(
     get_current_sub_id
 );
 
+get_set_dict!(
+    "payment_token_configs",
+    "payment_token",
+    Key,
+    PaymentTokenConfig,
+    PaymentTokenConfig::default(),
+    save_payment_token_config,
+    read_payment_token_config,
+    get_payment_token_config,
+    set_payment_token_config
+);
+
 get_set_no_set!(
-    total_balance,
-    "total_balance",
+    payment_tokens,
+    "payment_tokens",
+    Vec<Key>,
+    vec![],
+    save_payment_tokens,
+    read_payment_tokens,
+    get_payment_tokens
+);
+
+get_set_dict!(
+    "total_balances",
+    "payment_token",
+    Key,
     U128,
     U128::zero(),
     save_total_balance,
     read_total_balance,
-    get_total_balance
+    get_total_balance_of,
+    set_total_balance
 );
 
+get_set_no_set!(
+    total_native_balance,
+    "total_native_balance",
+    U512,
+    U512::zero(),
+    save_total_native_balance,
+    read_total_native_balance,
+    get_total_native_balance
+);
+
+const CONTRACT_PURSE: &str = "contract_purse";
+
+/// Purse holding the CSPR of subscriptions and oracles. It is created on first use, which
+/// also gives one to coordinators upgraded from a version without it.
+pub fn contract_purse() -> URef {
+    match runtime::get_key(CONTRACT_PURSE) {
+        Some(key) => key.into_uref().unwrap_or_revert(),
+        None => {
+            let purse = system::create_purse();
+            runtime::put_key(CONTRACT_PURSE, purse.into());
+            purse
+        }
+    }
+}
+
 get_set_dict!(
     "proving_keys",
     "key_hash",
     HashAddr,
-    Key,
-    helpers::null_key(),
+    ProvingKey,
+    ProvingKey::default(),
     save_proving_key,
     read_proving_key,
     get_proving_key,
@@ -139,18 +249,32 @@ get_set_no_set!(
     get_proving_key_hashes
 );
 
-get_set_dict!(
+get_set_nested_dict!(
     "withdrawable_tokens",
     "oracle",
+    "payment_token",
+    Key,
     Key,
     U128,
     U128::zero(),
     save_withdrawable_token,
     read_withdrawable_token,
-    get_withdrawable_token,
+    get_withdrawable_token_of,
     set_withdrawable_token
 );
 
+get_set_dict!(
+    "withdrawable_cspr",
+    "oracle",
+    Key,
+    U512,
+    U512::zero(),
+    save_withdrawable_cspr,
+    read_withdrawable_cspr,
+    get_withdrawable_cspr,
+    set_withdrawable_cspr
+);
+
 get_set_dict!(
     "request_commitments",
     "request_id",
@@ -159,10 +283,58 @@ get_set_dict!(
     HashAddr::default(),
     save_request_commitment,
     read_request_commitment,
-    get_request_commitment,
+    get_request_commitment_hash,
     set_request_commitment
 );
 
+get_set_dict!(
+    "requests",
+    "request_id",
+    U256,
+    RequestRecord,
+    RequestRecord::default(),
+    save_request,
+    read_request,
+    get_request_commitment,
+    set_request
+);
+
+get_set_dict!(
+    "pending_request_counts",
+    "sub_id",
+    u64,
+    u64,
+    0,
+    save_pending_request_count,
+    read_pending_request_count,
+    get_pending_requests,
+    set_pending_request_count
+);
+
+get_set_dict!(
+    "random_words",
+    "request_id",
+    U256,
+    Vec<U256>,
+    vec![],
+    save_random_words,
+    read_random_words,
+    get_random_words,
+    set_random_words
+);
+
+get_set_dict!(
+    "random_words_recipients",
+    "request_id",
+    U256,
+    Key,
+    helpers::null_key(),
+    save_random_words_recipient,
+    read_random_words_recipient,
+    get_random_words_recipient,
+    set_random_words_recipient
+);
+
 get_set_no_set!(
     config,
     "config",
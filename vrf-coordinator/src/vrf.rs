@@ -1,63 +1,99 @@
-use core::ops::{Add, Shr};
-
 use alloc::{vec, vec::Vec};
 use casper_contract::contract_api::runtime;
-use casper_types::{bytesrepr::Bytes, HashAddr, Key, U256};
+use casper_types::{bytesrepr::Bytes, Key, U256};
 use common::{
     error::Error,
-    helpers::{add_mod, mulmod, null_key, u256_from_hash},
+    helpers::{null_key, u256_from_hash},
+};
+use k256::{
+    elliptic_curve::sec1::{FromEncodedPoint, ToEncodedPoint},
+    AffinePoint, EncodedPoint, FieldBytes, FieldElement, ProjectivePoint, Scalar,
 };
 
-fn field_size() -> U256 {
-    U256::from_str_radix(
-        "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F",
-        16,
-    )
-    .unwrap()
-}
+/// secp256k1 field prime FFFFFFFF...FFFFFFFE FFFFFC2F, as little-endian limbs so that it is not
+/// parsed again by every modular operation.
+const FIELD_SIZE: U256 = U256([0xFFFF_FFFE_FFFF_FC2F, u64::MAX, u64::MAX, u64::MAX]);
 
-fn group_order() -> U256 {
-    U256::from_str_radix(
-        "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141",
-        16,
-    )
-    .unwrap()
+fn field_size() -> U256 {
+    FIELD_SIZE
 }
 
 use common::{
     data_types::Proof,
-    helpers::{self, require},
+    helpers::{self, ensure},
 };
 
-use crate::signature::verify_signature;
-
-fn y_squred(x: &U256) -> U256 {
-    let x_cubed = helpers::mulmod(*x, mulmod(*x, *x, field_size()), field_size());
-    add_mod(x_cubed, 7.into(), field_size())
+fn y_squred(x: FieldElement) -> FieldElement {
+    x.square().mul(&x) + &FieldElement::one().mul_single(7)
 }
 
-fn is_on_secp256k1_curve_(bs: &Bytes) -> bool {
-    let p: Vec<U256> = helpers::decode_1(bs);
+fn is_on_secp256k1_curve_(bs: &Bytes) -> Result<bool, Error> {
+    let p: Vec<U256> = helpers::try_decode_1(bs)?;
     is_on_secp256k1_curve(&p)
 }
 
-fn is_on_secp256k1_curve(p: &Vec<U256>) -> bool {
-    require(p.len() == 2, Error::InvalidProvingKeyLength);
+fn is_on_secp256k1_curve(p: &Vec<U256>) -> Result<bool, Error> {
+    ensure(p.len() == 2, Error::InvalidProvingKeyLength)?;
+
+    ensure(p[0].lt(&field_size()), Error::InvalidXCordinate)?;
+    ensure(p[1].lt(&field_size()), Error::InvalidXCordinate)?;
+    Ok(y_squred(to_field(p[0])).normalize() == to_field(p[1]).square().normalize())
+}
+
+fn field_bytes(v: U256) -> FieldBytes {
+    let mut b = [0u8; 32];
+    v.to_big_endian(&mut b);
+    FieldBytes::from(b)
+}
+
+/// `v` in the secp256k1 field. Any U256 is below twice the prime, so one subtraction reduces it.
+fn to_field(v: U256) -> FieldElement {
+    let v = if v >= FIELD_SIZE { v - FIELD_SIZE } else { v };
+    FieldElement::from_bytes(&field_bytes(v)).unwrap()
+}
+
+fn from_field(f: FieldElement) -> U256 {
+    U256::from_big_endian(&f.normalize().to_bytes())
+}
+
+fn to_scalar(v: U256) -> Scalar {
+    Scalar::from_bytes_reduced(&field_bytes(v))
+}
+
+fn to_projective_point(p: &[U256]) -> Result<ProjectivePoint, Error> {
+    let encoded =
+        EncodedPoint::from_affine_coordinates(&field_bytes(p[0]), &field_bytes(p[1]), false);
+    let affine = AffinePoint::from_encoded_point(&encoded).ok_or(Error::KeyNotOnCurve)?;
+    Ok(ProjectivePoint::from(affine))
+}
+
+/// Affine coordinates of `p`, or `None` for the point at infinity.
+fn to_affine_coordinates(p: &ProjectivePoint) -> Option<Vec<U256>> {
+    let encoded = p.to_affine().to_encoded_point(false);
+    Some(vec![
+        U256::from_big_endian(encoded.x()?),
+        U256::from_big_endian(encoded.y()?),
+    ])
+}
 
-    require(p[0].lt(&field_size()), Error::InvalidXCordinate);
-    require(p[1].lt(&field_size()), Error::InvalidXCordinate);
-    y_squred(&p[0]) == mulmod(p[1], p[1], field_size())
+/// Address of a point, standing in for the ethereum address recovered by ecrecover.
+fn point_address(p: &Vec<U256>) -> Key {
+    Key::Hash(runtime::blake2b(helpers::encode_1(p)))
 }
 
+/// Checks that `lc_witness` is the address of `c*p + s*g`.
 fn verify_linear_combination_with_generator(
-    _c: U256,
-    _p: &[U256],
-    _s: U256,
+    c: U256,
+    p: &[U256],
+    s: U256,
     lc_witness: Key,
-) -> bool {
-    // TODO: do the real verification
-    require(lc_witness != null_key(), Error::BadWitness);
-    true
+) -> Result<bool, Error> {
+    ensure(lc_witness != null_key(), Error::BadWitness)?;
+    let lc = to_projective_point(p)? * to_scalar(c) + ProjectivePoint::generator() * to_scalar(s);
+    Ok(match to_affine_coordinates(&lc) {
+        Some(lc) => point_address(&lc) == lc_witness,
+        None => false,
+    })
 }
 
 fn field_hash(b: &Bytes) -> U256 {
@@ -68,24 +104,6 @@ fn field_hash(b: &Bytes) -> U256 {
     ret
 }
 
-fn big_mod_exp(base: U256, exponent: U256) -> U256 {
-    let mut ret = U256::one();
-    let mut base = base % field_size();
-    let mut exponent = exponent;
-    while exponent.gt(&0.into()) {
-        if exponent % 2 == U256::one() {
-            ret = mulmod(ret, base, field_size());
-        }
-        exponent = exponent.shr(1);
-        base = mulmod(base, base, field_size());
-    }
-    ret
-}
-
-fn sqrt_power() -> U256 {
-    field_size().add(1).shr(2)
-}
-
 fn hash_to_curve_hash_prefix() -> U256 {
     U256::from(1)
 }
@@ -98,64 +116,101 @@ fn vrf_random_output_hash_prefix() -> U256 {
     U256::from(3)
 }
 
-fn square_root(x: U256) -> U256 {
-    big_mod_exp(x, sqrt_power())
+fn pow2k(x: &FieldElement, k: usize) -> FieldElement {
+    let mut ret = *x;
+    for _ in 0..k {
+        ret = ret.square();
+    }
+    ret
+}
+
+/// `x^((p+1)/4)`, with the addition chain of `FieldElement::sqrt`. Unlike it, the power is also
+/// returned when `x` has no root, since hash_to_curve hashes a failed candidate into the next.
+fn square_root(x: FieldElement) -> U256 {
+    let x2 = pow2k(&x, 1).mul(&x);
+    let x3 = pow2k(&x2, 1).mul(&x);
+    let x6 = pow2k(&x3, 3).mul(&x3);
+    let x9 = pow2k(&x6, 3).mul(&x3);
+    let x11 = pow2k(&x9, 2).mul(&x2);
+    let x22 = pow2k(&x11, 11).mul(&x11);
+    let x44 = pow2k(&x22, 22).mul(&x22);
+    let x88 = pow2k(&x44, 44).mul(&x44);
+    let x176 = pow2k(&x88, 88).mul(&x88);
+    let x220 = pow2k(&x176, 44).mul(&x44);
+    let x223 = pow2k(&x220, 3).mul(&x3);
+    let ret = pow2k(&x223, 23).mul(&x22);
+    let ret = pow2k(&ret, 6).mul(&x2);
+    from_field(pow2k(&ret, 2))
 }
 
 fn new_candidate_secp256k1_point(b: &Bytes) -> Vec<U256> {
-    let mut p: Vec<U256> = vec![field_hash(b), square_root(y_squred(&field_hash(b)))];
+    let mut p: Vec<U256> = vec![field_hash(b), square_root(y_squred(to_field(field_hash(b))))];
     if p[1] % U256::from(2) == U256::one() {
         p[1] = field_size() - p[1];
     }
     p
 }
 
-fn hash_to_curve(pk: &Vec<U256>, input: U256) -> Vec<U256> {
+fn hash_to_curve(pk: &Vec<U256>, input: U256) -> Result<Vec<U256>, Error> {
     let mut rv = new_candidate_secp256k1_point(&Bytes::from(helpers::encode_3(
         &hash_to_curve_hash_prefix(),
         pk,
         &input,
     )));
-    while !is_on_secp256k1_curve(&rv) {
+    while !is_on_secp256k1_curve(&rv)? {
         rv = new_candidate_secp256k1_point(&Bytes::from(helpers::encode_1(&rv)));
     }
-    rv
+    Ok(rv)
 }
 
-fn ecmul_verify(multiplic_and: &[U256], scalar: U256, product: &[U256]) -> bool {
-    require(scalar != U256::zero(), Error::ScalarZero);
-    let x = multiplic_and[0];
-    let _v: u8 = if multiplic_and[1] % U256::from(2) == U256::zero() {
-        27
-    } else {
-        28
-    };
-    let scalar_times_x = mulmod(scalar, x, group_order());
-    verify_signature(
-        &Bytes::from(helpers::encode_1(&Vec::from(product))),
-        HashAddr::default(),
-        &Bytes::from(helpers::encode_2(&x, &scalar_times_x)),
-    );
+/// Checks that `scalar * multiplicand == product`. The k256 multiplication is the deepest call
+/// of the verification, so this is kept out of line to leave no more than its own frame above it.
+#[inline(never)]
+fn ecmul_verify(multiplicand: &[U256], scalar: U256, product: &[U256]) -> Result<bool, Error> {
+    ensure(scalar != U256::zero(), Error::ScalarZero)?;
+    let p = to_projective_point(multiplicand)? * to_scalar(scalar);
+    Ok(match to_affine_coordinates(&p) {
+        Some(p) => p.as_slice() == product,
+        None => false,
+    })
+}
 
-    true
+fn projective_mul(
+    x1: FieldElement,
+    z1: FieldElement,
+    x2: FieldElement,
+    z2: FieldElement,
+) -> (FieldElement, FieldElement) {
+    (x1.mul(&x2), z1.mul(&z2))
 }
 
-fn projective_mul(x1: U256, z1: U256, x2: U256, z2: U256) -> (U256, U256) {
-    (mulmod(x1, x2, field_size()), mulmod(z1, z2, field_size()))
+/// `a-b`, for operands of magnitude 1 as left by `mul`, and of magnitude 1 itself.
+fn field_sub(a: FieldElement, b: FieldElement) -> FieldElement {
+    (a + &b.negate(1)).normalize_weak()
 }
 
-fn projective_sub(x1: U256, z1: U256, x2: U256, z2: U256) -> (U256, U256) {
-    let num1 = mulmod(z2, x1, field_size());
-    let num2 = mulmod(field_size() - x2, z1, field_size());
-    (
-        add_mod(num1, num2, field_size()),
-        mulmod(z1, z2, field_size()),
-    )
+fn projective_sub(
+    x1: FieldElement,
+    z1: FieldElement,
+    x2: FieldElement,
+    z2: FieldElement,
+) -> (FieldElement, FieldElement) {
+    let num1 = z2.mul(&x1);
+    let num2 = x2.mul(&z1);
+    (field_sub(num1, num2), z1.mul(&z2))
 }
-fn projective_ec_add(px: U256, py: U256, qx: U256, qy: U256) -> (U256, U256, U256) {
-    let (z1, z2) = (U256::one(), U256::one());
-    let lx = add_mod(qy, field_size() - py, field_size());
-    let lz = add_mod(qx, field_size() - px, field_size());
+
+/// Sum of the affine points `p` and `q` in projective coordinates, computed on field elements so
+/// that the coordinates are converted once rather than around every operation.
+fn projective_ec_add(
+    px: FieldElement,
+    py: FieldElement,
+    qx: FieldElement,
+    qy: FieldElement,
+) -> (FieldElement, FieldElement, FieldElement) {
+    let (z1, z2) = (FieldElement::one(), FieldElement::one());
+    let lx = field_sub(qy, py);
+    let lz = field_sub(qx, px);
 
     let (mut sx, mut dx) = projective_mul(lx, lz, lx, lz);
     (sx, dx) = projective_sub(sx, dx, px, z1);
@@ -165,26 +220,31 @@ fn projective_ec_add(px: U256, py: U256, qx: U256, qy: U256) -> (U256, U256, U25
     (sy, dy) = projective_mul(sy, dy, lx, lz);
     (sy, dy) = projective_sub(sy, dy, py, z1);
 
-    let sz = if dx != dy {
-        sx = mulmod(sx, dy, field_size());
-        sy = mulmod(sy, dx, field_size());
-        mulmod(dx, dy, field_size())
+    let sz = if dx.normalize() != dy.normalize() {
+        sx = sx.mul(&dy);
+        sy = sy.mul(&dx);
+        dx.mul(&dy)
     } else {
         dx
     };
     (sx, sy, sz)
 }
 
-fn affine_ec_add(p1: &[U256], p2: &[U256], inv_z: U256) -> Vec<U256> {
-    let (x, y, z) = projective_ec_add(p1[0], p1[1], p2[0], p2[1]);
-    require(
-        mulmod(z, inv_z, field_size()) == U256::one(),
-        Error::InvZMustBeInverseOfZ,
+/// `p1 + p2`, given the inverse of the projective z coordinate of the sum.
+#[inline(never)]
+fn affine_ec_add(p1: &[U256], p2: &[U256], inv_z: U256) -> Result<Vec<U256>, Error> {
+    let (x, y, z) = projective_ec_add(
+        to_field(p1[0]),
+        to_field(p1[1]),
+        to_field(p2[0]),
+        to_field(p2[1]),
     );
-    vec![
-        mulmod(x, inv_z, field_size()),
-        mulmod(y, inv_z, field_size()),
-    ]
+    let inv_z = to_field(inv_z);
+    ensure(
+        from_field(z.mul(&inv_z)) == U256::one(),
+        Error::InvZMustBeInverseOfZ,
+    )?;
+    Ok(vec![from_field(x.mul(&inv_z)), from_field(y.mul(&inv_z))])
 }
 
 fn linear_combination(
@@ -195,16 +255,19 @@ fn linear_combination(
     p2: &[U256],
     sp2_witness: &[U256],
     z_inv: U256,
-) -> Vec<U256> {
-    require(
+) -> Result<Vec<U256>, Error> {
+    ensure(
         cp1_witness[0] % field_size() != sp2_witness[0] % field_size(),
         Error::PointsSumMustBeDistinct,
-    );
-    require(ecmul_verify(p1, c, cp1_witness), Error::FirstMulCheckFailed);
-    require(
-        ecmul_verify(p2, s, sp2_witness),
+    )?;
+    ensure(
+        ecmul_verify(p1, c, cp1_witness)?,
+        Error::FirstMulCheckFailed,
+    )?;
+    ensure(
+        ecmul_verify(p2, s, sp2_witness)?,
         Error::SecondMulCheckFailed,
-    );
+    )?;
 
     affine_ec_add(cp1_witness, sp2_witness, z_inv)
 }
@@ -237,25 +300,28 @@ pub fn verify_vrf_proof(
     c_gamma_witness: &Bytes,
     s_hash_witness: &Bytes,
     z_inv: U256,
-) {
-    let pk: Vec<U256> = helpers::decode_1(pk);
-    require(is_on_secp256k1_curve(&pk), Error::KeyNotOnCurve);
-    require(is_on_secp256k1_curve_(gamma), Error::KeyNotOnCurve);
-    require(
-        is_on_secp256k1_curve_(c_gamma_witness),
+) -> Result<(), Error> {
+    let pk: Vec<U256> = helpers::try_decode_1(pk)?;
+    ensure(is_on_secp256k1_curve(&pk)?, Error::KeyNotOnCurve)?;
+    ensure(is_on_secp256k1_curve_(gamma)?, Error::KeyNotOnCurve)?;
+    ensure(
+        is_on_secp256k1_curve_(c_gamma_witness)?,
         Error::KeyNotOnCurve,
-    );
-    require(is_on_secp256k1_curve_(s_hash_witness), Error::KeyNotOnCurve);
+    )?;
+    ensure(
+        is_on_secp256k1_curve_(s_hash_witness)?,
+        Error::KeyNotOnCurve,
+    )?;
 
-    require(
-        verify_linear_combination_with_generator(c, &pk, s, u_witness),
+    ensure(
+        verify_linear_combination_with_generator(c, &pk, s, u_witness)?,
         Error::BadLinearCombinationWithGenerator,
-    );
+    )?;
 
-    let hash = hash_to_curve(&pk, seed);
-    let gamma: Vec<U256> = helpers::decode_1(gamma);
-    let c_gamma_witness: Vec<U256> = helpers::decode_1(c_gamma_witness);
-    let s_hash_witness: Vec<U256> = helpers::decode_1(s_hash_witness);
+    let hash = hash_to_curve(&pk, seed)?;
+    let gamma: Vec<U256> = helpers::try_decode_1(gamma)?;
+    let c_gamma_witness: Vec<U256> = helpers::try_decode_1(c_gamma_witness)?;
+    let s_hash_witness: Vec<U256> = helpers::try_decode_1(s_hash_witness)?;
     let v = linear_combination(
         c,
         &gamma,
@@ -264,13 +330,16 @@ pub fn verify_vrf_proof(
         &hash,
         &s_hash_witness,
         z_inv,
-    );
+    )?;
 
     let derived_c = scalar_from_curve_points(&hash, &pk, &gamma, u_witness, &v);
-    require(c == derived_c, Error::InvalidProof);
+    ensure(c == derived_c, Error::InvalidProof)
 }
 
-pub fn random_value_from_vrf_proof(proof: &Proof, seed: U256) -> U256 {
+/// Verifies `proof` for `seed` and returns the VRF output. Not inlined into the coordinator, whose
+/// frames would otherwise sit under the whole verification.
+#[inline(never)]
+pub fn random_value_from_vrf_proof(proof: &Proof, seed: U256) -> Result<U256, Error> {
     verify_vrf_proof(
         &proof.pk,
         &proof.gamma,
@@ -281,10 +350,10 @@ pub fn random_value_from_vrf_proof(proof: &Proof, seed: U256) -> U256 {
         &proof.c_gamma_witness,
         &proof.s_hash_witness,
         proof.z_inv,
-    );
+    )?;
 
-    u256_from_hash(runtime::blake2b(helpers::encode_2(
+    Ok(u256_from_hash(runtime::blake2b(helpers::encode_2(
         &vrf_random_output_hash_prefix(),
         &proof.gamma,
-    )))
+    ))))
 }
@@ -0,0 +1,294 @@
+use std::process::Command;
+
+use casper_types::{
+    bytesrepr::{FromBytes, ToBytes},
+    HashAddr, U128,
+};
+use common::data_types::{BlockRef, Proof, RequestCommitment};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::error::Error;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DeployStatus {
+    Pending,
+    Executed,
+    Failed(String),
+}
+
+/// Access to the chain the coordinator runs on.
+pub trait ChainClient {
+    fn latest_block_height(&mut self) -> Result<u64, Error>;
+
+    /// Block the block-hash-store holds at `timestamp` and its hash, `None` until it is set.
+    ///
+    /// The coordinator commits requests to their block timestamp, so this is called with the
+    /// request timestamp, and fulfillments must carry the block stored under it.
+    fn block(&mut self, timestamp: u64) -> Result<Option<(BlockRef, HashAddr)>, Error>;
+
+    /// Gas the consumer callback of a fulfillment would use, `None` if it cannot be measured.
+    fn estimate_callback_gas(
+        &mut self,
+        _proof: &Proof,
+        _rc: &RequestCommitment,
+    ) -> Result<Option<U128>, Error> {
+        Ok(None)
+    }
+
+    /// Sends a `fulfill_random_words` deploy and returns its hash. With `skip_callback` the
+    /// coordinator stores the words instead of calling the consumer, with `callback_gas_used`
+    /// it bills that gas instead of the request's gas limit.
+    fn fulfill_random_words(
+        &mut self,
+        proof: &Proof,
+        rc: &RequestCommitment,
+        skip_callback: bool,
+        callback_gas_used: Option<U128>,
+    ) -> Result<String, Error>;
+
+    fn deploy_status(&mut self, deploy_hash: &str) -> Result<DeployStatus, Error>;
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct CasperClientConfig {
+    /// Path of the `casper-client` binary.
+    #[serde(default = "default_client_path")]
+    pub client_path: String,
+    pub node_address: String,
+    pub chain_name: String,
+    /// PEM secret key of the oracle account signing the deploys.
+    pub secret_key_path: String,
+    /// `hash-` prefixed package hash of the coordinator.
+    pub coordinator_package_hash: String,
+    /// `hash-` prefixed contract hash of the block-hash-store.
+    pub block_hash_store_contract_hash: String,
+    /// Motes paid for each `fulfill_random_words` deploy.
+    pub payment_amount: u64,
+    /// Measures the gas of consumer callbacks by executing fulfillments speculatively, with and
+    /// without the callback, before sending them. `node_address` must serve speculative
+    /// execution.
+    #[serde(default)]
+    pub measure_callback_gas: bool,
+}
+
+fn default_client_path() -> String {
+    "casper-client".into()
+}
+
+/// `ChainClient` driving the `casper-client` command line tool.
+pub struct CasperClient {
+    config: CasperClientConfig,
+}
+
+impl CasperClient {
+    pub fn new(config: CasperClientConfig) -> Self {
+        Self { config }
+    }
+
+    fn run(&self, args: &[&str]) -> Result<Value, Error> {
+        let output = Command::new(&self.config.client_path)
+            .args(args)
+            .args(["--node-address", &self.config.node_address])
+            .output()?;
+        if !output.status.success() {
+            return Err(Error::Client(
+                String::from_utf8_lossy(&output.stderr).into_owned(),
+            ));
+        }
+        let response: Value = serde_json::from_slice(&output.stdout)?;
+        Ok(response["result"].clone())
+    }
+
+    /// Runs `put-deploy` of `fulfill_random_words` with `args`, followed by `extra` options.
+    fn put_fulfill_deploy(&self, args: &Value, extra: &[&str]) -> Result<Value, Error> {
+        let payment_amount = self.config.payment_amount.to_string();
+        let args = args.to_string();
+        let mut command: Vec<&str> = vec![
+            "put-deploy",
+            "--chain-name",
+            &self.config.chain_name,
+            "--secret-key",
+            &self.config.secret_key_path,
+            "--payment-amount",
+            &payment_amount,
+            "--session-package-hash",
+            &self.config.coordinator_package_hash,
+            "--session-entry-point",
+            "fulfill_random_words",
+            "--session-args-json",
+            &args,
+        ];
+        command.extend_from_slice(extra);
+        self.run(&command)
+    }
+
+    /// Cost of a speculative execution of the fulfillment, which must succeed.
+    fn speculative_cost(
+        &self,
+        proof: &Proof,
+        rc: &RequestCommitment,
+        skip_callback: bool,
+    ) -> Result<u128, Error> {
+        let args = fulfill_args(proof, rc, skip_callback, None)?;
+        let result = self.put_fulfill_deploy(&args, &["--speculative-exec"])?;
+        let execution = &result["execution_result"];
+        if let Some(failure) = execution.get("Failure") {
+            return Err(Error::Client(format!(
+                "speculative execution failed: {}",
+                failure["error_message"]
+            )));
+        }
+        execution["Success"]["cost"]
+            .as_str()
+            .and_then(|cost| cost.parse().ok())
+            .ok_or_else(|| Error::Client("missing speculative execution cost".into()))
+    }
+
+    /// `CLValue` the block-hash-store holds at `timestamp` in dictionary `name`, `None` when unset.
+    fn block_hash_store_item(
+        &self,
+        state_root_hash: &str,
+        name: &str,
+        timestamp: u64,
+    ) -> Result<Option<Value>, Error> {
+        let timestamp = timestamp.to_string();
+        let result = self.run(&[
+            "get-dictionary-item",
+            "--state-root-hash",
+            state_root_hash,
+            "--contract-hash",
+            &self.config.block_hash_store_contract_hash,
+            "--dictionary-name",
+            name,
+            "--dictionary-item-key",
+            &timestamp,
+        ]);
+        match result {
+            Ok(result) => Ok(Some(result["stored_value"]["CLValue"].clone())),
+            // the node reports unset dictionary items as missing values
+            Err(Error::Client(e)) if e.contains("ValueNotFound") => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn state_root_hash(&self) -> Result<String, Error> {
+        let result = self.run(&["get-state-root-hash"])?;
+        result["state_root_hash"]
+            .as_str()
+            .map(String::from)
+            .ok_or_else(|| Error::Client("missing state_root_hash".into()))
+    }
+}
+
+fn bytes_arg(name: &str, bytes: Vec<u8>) -> Value {
+    json!({ "name": name, "type": { "List": "U8" }, "value": hex::encode(bytes) })
+}
+
+fn fulfill_args(
+    proof: &Proof,
+    rc: &RequestCommitment,
+    skip_callback: bool,
+    callback_gas_used: Option<U128>,
+) -> Result<Value, Error> {
+    let to_bytes = |e| Error::Client(format!("serialization failed: {:?}", e));
+    let mut args = vec![
+        bytes_arg("proof", proof.to_bytes().map_err(to_bytes)?),
+        bytes_arg("rc", rc.to_bytes().map_err(to_bytes)?),
+        json!({ "name": "skip_callback", "type": "Bool", "value": skip_callback }),
+    ];
+    if let Some(gas) = callback_gas_used {
+        args.push(json!({ "name": "callback_gas_used", "type": "U128", "value": gas.to_string() }));
+    }
+    Ok(Value::Array(args))
+}
+
+impl ChainClient for CasperClient {
+    fn latest_block_height(&mut self) -> Result<u64, Error> {
+        let result = self.run(&["get-block"])?;
+        result["block"]["header"]["height"]
+            .as_u64()
+            .ok_or_else(|| Error::Client("missing block height".into()))
+    }
+
+    fn block(&mut self, timestamp: u64) -> Result<Option<(BlockRef, HashAddr)>, Error> {
+        let state_root_hash = self.state_root_hash()?;
+        let hash = match self.block_hash_store_item(&state_root_hash, "block_hashes", timestamp)? {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+        let hash = hash["parsed"]
+            .as_str()
+            .ok_or_else(|| Error::Client("block hash is not a string".into()))?;
+        if hash.is_empty() {
+            return Ok(None);
+        }
+        let mut h = HashAddr::default();
+        hex::decode_to_slice(hash, &mut h).map_err(|e| Error::Client(e.to_string()))?;
+
+        let block = match self.block_hash_store_item(&state_root_hash, "block_refs", timestamp)? {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+        // the node does not parse BlockRef, it is read from the serialized value
+        let bytes = block["bytes"]
+            .as_str()
+            .and_then(|bytes| hex::decode(bytes).ok())
+            .ok_or_else(|| Error::Client("block ref is not serialized".into()))?;
+        let (block, _) = BlockRef::from_bytes(&bytes)
+            .map_err(|e| Error::Client(format!("invalid block ref: {:?}", e)))?;
+        Ok(Some((block, h)))
+    }
+
+    fn estimate_callback_gas(
+        &mut self,
+        proof: &Proof,
+        rc: &RequestCommitment,
+    ) -> Result<Option<U128>, Error> {
+        if !self.config.measure_callback_gas {
+            return Ok(None);
+        }
+        let without_callback = self.speculative_cost(proof, rc, true)?;
+        // a reverting callback is left to the deploy, whose failure makes the next attempt skip it
+        let with_callback = match self.speculative_cost(proof, rc, false) {
+            Ok(cost) => cost,
+            Err(_) => return Ok(None),
+        };
+        Ok(Some(U128::from(
+            with_callback.saturating_sub(without_callback),
+        )))
+    }
+
+    fn fulfill_random_words(
+        &mut self,
+        proof: &Proof,
+        rc: &RequestCommitment,
+        skip_callback: bool,
+        callback_gas_used: Option<U128>,
+    ) -> Result<String, Error> {
+        let args = fulfill_args(proof, rc, skip_callback, callback_gas_used)?;
+        let result = self.put_fulfill_deploy(&args, &[])?;
+        result["deploy_hash"]
+            .as_str()
+            .map(String::from)
+            .ok_or_else(|| Error::Client("missing deploy_hash".into()))
+    }
+
+    fn deploy_status(&mut self, deploy_hash: &str) -> Result<DeployStatus, Error> {
+        let result = self.run(&["get-deploy", deploy_hash])?;
+        let execution = match result["execution_results"]
+            .as_array()
+            .and_then(|r| r.first())
+        {
+            Some(execution) => &execution["result"],
+            None => return Ok(DeployStatus::Pending),
+        };
+        if execution.get("Success").is_some() {
+            return Ok(DeployStatus::Executed);
+        }
+        let message = execution["Failure"]["error_message"]
+            .as_str()
+            .unwrap_or("unknown failure");
+        Ok(DeployStatus::Failed(message.into()))
+    }
+}
@@ -0,0 +1,37 @@
+use std::{fs, path::Path, path::PathBuf};
+
+use serde::Deserialize;
+use vrf_prover::Prover;
+
+use crate::{chain::CasperClientConfig, error::Error, oracle::RetryConfig};
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct Config {
+    /// Hex encoded secp256k1 secret key of the registered proving key.
+    pub proving_key: String,
+    /// JSON array of `RequestedEvent` exported from the coordinator's events.
+    pub events_file: PathBuf,
+    pub progress_file: PathBuf,
+    #[serde(default = "default_poll_interval_secs")]
+    pub poll_interval_secs: u64,
+    #[serde(default)]
+    pub retry: RetryConfig,
+    pub chain: CasperClientConfig,
+}
+
+fn default_poll_interval_secs() -> u64 {
+    10
+}
+
+impl Config {
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        Ok(serde_json::from_slice(&fs::read(path)?)?)
+    }
+
+    pub fn prover(&self) -> Result<Prover, Error> {
+        let mut secret_key = [0u8; 32];
+        hex::decode_to_slice(self.proving_key.trim_start_matches("0x"), &mut secret_key)
+            .map_err(|e| Error::InvalidConfig(format!("proving_key: {}", e)))?;
+        Prover::new(secret_key).map_err(|e| Error::InvalidConfig(format!("proving_key: {}", e)))
+    }
+}
@@ -0,0 +1,111 @@
+use std::{fs, io, path::PathBuf};
+
+use casper_types::{HashAddr, Key, U128, U256};
+use common::events::RandomWordsRequested;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::error::Error;
+
+/// `RandomWordsRequested` together with the height of the block that emitted it, which the
+/// event itself does not carry.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RequestedEvent {
+    pub block_height: u64,
+    #[serde(serialize_with = "to_hex", deserialize_with = "from_hex")]
+    pub key_hash: HashAddr,
+    pub request_id: U256,
+    pub pre_seed: U256,
+    pub sub_id: u64,
+    pub minimum_request_confirmations: u64,
+    pub callback_gas_limit: U128,
+    pub num_words: u64,
+    pub sender: Key,
+    pub timestamp: u64,
+}
+
+impl RequestedEvent {
+    pub fn new(block_height: u64, event: RandomWordsRequested) -> Self {
+        Self {
+            block_height,
+            key_hash: event.key_hash,
+            request_id: event.request_id,
+            pre_seed: event.pre_seed,
+            sub_id: event.sub_id,
+            minimum_request_confirmations: event.minimum_request_confirmations,
+            callback_gas_limit: event.callback_cas_limit,
+            num_words: event.num_words,
+            sender: event.sender,
+            timestamp: event.block.timestamp,
+        }
+    }
+}
+
+fn to_hex<S: Serializer>(h: &HashAddr, s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_str(&hex::encode(h))
+}
+
+fn from_hex<'de, D: Deserializer<'de>>(d: D) -> Result<HashAddr, D::Error> {
+    let s = String::deserialize(d)?;
+    let mut h = HashAddr::default();
+    hex::decode_to_slice(s, &mut h).map_err(serde::de::Error::custom)?;
+    Ok(h)
+}
+
+/// Source of the coordinator's `RandomWordsRequested` events.
+///
+/// Events are numbered from 0 in emission order, as the CES event ids of the coordinator are,
+/// so the oracle can resume after the last one it handled.
+pub trait EventSource {
+    /// Events numbered `from` and later, in order.
+    fn events_from(&mut self, from: u64) -> Result<Vec<(u64, RequestedEvent)>, Error>;
+}
+
+/// Reads events from a JSON array of `RequestedEvent`, re-read on every poll so an exporter
+/// can keep appending to it. A missing file holds no events yet.
+pub struct JsonFileSource {
+    path: PathBuf,
+}
+
+impl JsonFileSource {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl EventSource for JsonFileSource {
+    fn events_from(&mut self, from: u64) -> Result<Vec<(u64, RequestedEvent)>, Error> {
+        let data = match fs::read(&self.path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(e) => return Err(e.into()),
+        };
+        let events: Vec<RequestedEvent> = serde_json::from_slice(&data)?;
+        Ok(numbered_from(events, from))
+    }
+}
+
+/// Events held in memory, for tests.
+#[derive(Default)]
+pub struct MemorySource {
+    events: Vec<RequestedEvent>,
+}
+
+impl MemorySource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, event: RequestedEvent) {
+        self.events.push(event);
+    }
+}
+
+impl EventSource for MemorySource {
+    fn events_from(&mut self, from: u64) -> Result<Vec<(u64, RequestedEvent)>, Error> {
+        Ok(numbered_from(self.events.clone(), from))
+    }
+}
+
+fn numbered_from(events: Vec<RequestedEvent>, from: u64) -> Vec<(u64, RequestedEvent)> {
+    (0u64..).zip(events).skip(from as usize).collect()
+}
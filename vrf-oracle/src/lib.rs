@@ -0,0 +1,8 @@
+//! Oracle node answering the coordinator's `RandomWordsRequested` events with
+//! `fulfill_random_words` deploys.
+pub mod chain;
+pub mod config;
+pub mod error;
+pub mod event_source;
+pub mod oracle;
+pub mod progress;
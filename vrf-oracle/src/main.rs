@@ -0,0 +1,37 @@
+use std::{env, path::PathBuf, process, thread, time::Duration};
+
+use vrf_oracle::{
+    chain::CasperClient, config::Config, error::Error, event_source::JsonFileSource, oracle::Oracle,
+};
+
+fn run(config_path: PathBuf) -> Result<(), Error> {
+    let config = Config::load(&config_path)?;
+    let mut oracle = Oracle::new(
+        JsonFileSource::new(&config.events_file),
+        CasperClient::new(config.chain.clone()),
+        config.prover()?,
+        config.retry.clone(),
+        Some(config.progress_file.clone()),
+    )?;
+    loop {
+        // a failed poll leaves the saved progress untouched, the next one picks up from there
+        if let Err(e) = oracle.poll() {
+            eprintln!("poll failed: {}", e);
+        }
+        thread::sleep(Duration::from_secs(config.poll_interval_secs));
+    }
+}
+
+fn main() {
+    let config_path = match env::args().nth(1) {
+        Some(path) => PathBuf::from(path),
+        None => {
+            eprintln!("usage: vrf-oracle <config.json>");
+            process::exit(2);
+        }
+    };
+    if let Err(e) = run(config_path) {
+        eprintln!("{}", e);
+        process::exit(1);
+    }
+}
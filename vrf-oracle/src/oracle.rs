@@ -0,0 +1,242 @@
+use std::path::PathBuf;
+
+use casper_types::HashAddr;
+use common::{
+    data_types::{BlockRef, RequestCommitment},
+    error::Error as CoordinatorError,
+};
+use serde::Deserialize;
+use vrf_prover::Prover;
+
+use crate::{
+    chain::{ChainClient, DeployStatus},
+    error::Error,
+    event_source::{EventSource, RequestedEvent},
+    progress::{PendingRequest, Progress},
+};
+
+#[derive(Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RetryConfig {
+    /// Deploys sent for a request before it is moved to the failed list.
+    pub max_attempts: u32,
+    /// Blocks to wait after a failed attempt before the next one.
+    pub retry_delay_blocks: u64,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            retry_delay_blocks: 10,
+        }
+    }
+}
+
+/// Fulfills the requests made to one proving key.
+pub struct Oracle<S: EventSource, C: ChainClient> {
+    source: S,
+    chain: C,
+    prover: Prover,
+    key_hash: HashAddr,
+    retry: RetryConfig,
+    progress: Progress,
+    progress_path: Option<PathBuf>,
+}
+
+impl<S: EventSource, C: ChainClient> Oracle<S, C> {
+    /// Resumes from the progress saved at `progress_path`, which is kept up to date after each
+    /// poll. Without a path progress only lives in memory.
+    pub fn new(
+        source: S,
+        chain: C,
+        prover: Prover,
+        retry: RetryConfig,
+        progress_path: Option<PathBuf>,
+    ) -> Result<Self, Error> {
+        let progress = match &progress_path {
+            Some(path) => Progress::load(path)?,
+            None => Progress::default(),
+        };
+        let key_hash = vrf_prover::blake2b(prover.public_key());
+        Ok(Self {
+            source,
+            chain,
+            prover,
+            key_hash,
+            retry,
+            progress,
+            progress_path,
+        })
+    }
+
+    pub fn progress(&self) -> &Progress {
+        &self.progress
+    }
+
+    pub fn chain_mut(&mut self) -> &mut C {
+        &mut self.chain
+    }
+
+    pub fn source_mut(&mut self) -> &mut S {
+        &mut self.source
+    }
+
+    /// Reads new events, follows up on sent deploys and fulfills the requests that are ready.
+    pub fn poll(&mut self) -> Result<(), Error> {
+        let height = self.chain.latest_block_height()?;
+        self.read_events()?;
+        let request_ids: Vec<String> = self.progress.pending.keys().cloned().collect();
+        for request_id in request_ids {
+            self.process(&request_id, height)?;
+        }
+        self.save()
+    }
+
+    fn read_events(&mut self) -> Result<(), Error> {
+        for (number, event) in self.source.events_from(self.progress.next_event)? {
+            self.progress.next_event = number + 1;
+            if event.key_hash != self.key_hash {
+                continue;
+            }
+            self.progress.pending.insert(
+                event.request_id.to_string(),
+                PendingRequest {
+                    event,
+                    attempts: 0,
+                    deploy_hash: None,
+                    retry_at: 0,
+                    last_error: None,
+                    skip_callback: false,
+                },
+            );
+        }
+        Ok(())
+    }
+
+    fn process(&mut self, request_id: &str, height: u64) -> Result<(), Error> {
+        let mut request = self.progress.pending[request_id].clone();
+        if let Some(deploy_hash) = &request.deploy_hash {
+            match self.chain.deploy_status(deploy_hash)? {
+                DeployStatus::Pending => return Ok(()),
+                DeployStatus::Executed => {
+                    self.progress.pending.remove(request_id);
+                    return Ok(());
+                }
+                DeployStatus::Failed(e) => {
+                    request.deploy_hash = None;
+                    request.skip_callback |= is_callback_failure(&e);
+                    self.attempt_failed(request_id, request, height, e);
+                    return Ok(());
+                }
+            }
+        }
+
+        let event = &request.event;
+        if height < event.block_height + event.minimum_request_confirmations
+            || height < request.retry_at
+        {
+            return Ok(());
+        }
+        let (block, block_hash) = match self.chain.block(event.timestamp)? {
+            Some(block) => block,
+            None => return Ok(()),
+        };
+
+        let proof = self.prover.generate_proof(event.pre_seed, block_hash);
+        let rc = request_commitment(event, block);
+        request.attempts += 1;
+        let mut callback_gas_used = None;
+        if !request.skip_callback {
+            callback_gas_used = match self.chain.estimate_callback_gas(&proof, &rc) {
+                Ok(gas) => gas,
+                Err(e) => {
+                    self.attempt_failed(request_id, request, height, e.to_string());
+                    return Ok(());
+                }
+            };
+            // the coordinator refuses to make a callback measured above its gas limit
+            request.skip_callback =
+                callback_gas_used.map_or(false, |gas| gas > rc.callback_gas_limit);
+        }
+        match self
+            .chain
+            .fulfill_random_words(&proof, &rc, request.skip_callback, callback_gas_used)
+        {
+            Ok(deploy_hash) => {
+                request.deploy_hash = Some(deploy_hash);
+                self.progress.pending.insert(request_id.into(), request);
+            }
+            Err(e) => self.attempt_failed(request_id, request, height, e.to_string()),
+        }
+        Ok(())
+    }
+
+    fn attempt_failed(
+        &mut self,
+        request_id: &str,
+        mut request: PendingRequest,
+        height: u64,
+        error: String,
+    ) {
+        request.last_error = Some(error);
+        request.retry_at = height + self.retry.retry_delay_blocks;
+        if request.attempts >= self.retry.max_attempts {
+            self.progress.pending.remove(request_id);
+            self.progress.failed.push(request);
+        } else {
+            self.progress.pending.insert(request_id.into(), request);
+        }
+    }
+
+    fn save(&self) -> Result<(), Error> {
+        match &self.progress_path {
+            Some(path) => self.progress.save(path),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Errors the coordinator raises while checking a fulfillment, before it calls the consumer.
+const COORDINATOR_ERRORS: [CoordinatorError; 10] = [
+    CoordinatorError::InsufficientBalance,
+    CoordinatorError::NoSuchProvingKey,
+    CoordinatorError::InvalidLinkWeiPrice,
+    CoordinatorError::NoCorrespondingRequest,
+    CoordinatorError::IncorrectCommitment,
+    CoordinatorError::BlockhashNotInStore,
+    CoordinatorError::PaymentTooLarge,
+    CoordinatorError::Reentrant,
+    CoordinatorError::NotEnoughConfirmations,
+    CoordinatorError::IncorrectBlockRef,
+];
+
+/// Whether a failed `fulfill_random_words` deploy reverted in the consumer callback.
+///
+/// The coordinator cannot catch a reverting consumer, so a user error other than those of its
+/// own checks or proof verification is put on the callback, which is then skipped for good.
+/// Other failures, such as running out of gas, may as well come from the coordinator and are
+/// retried with the callback.
+pub fn is_callback_failure(error: &str) -> bool {
+    let code = match error
+        .strip_prefix("User error: ")
+        .and_then(|code| code.trim().parse::<u16>().ok())
+    {
+        Some(code) => code,
+        None => return false,
+    };
+    let proof_errors = CoordinatorError::FailedToDecodeInputBytes as u16
+        ..=CoordinatorError::InvalidProvingKeyType as u16;
+    !(proof_errors.contains(&code) || COORDINATOR_ERRORS.iter().any(|e| *e as u16 == code))
+}
+
+/// Commitment the coordinator stored for `event`, made in the stored `block`.
+pub fn request_commitment(event: &RequestedEvent, block: BlockRef) -> RequestCommitment {
+    RequestCommitment {
+        block,
+        request_confirmations: event.minimum_request_confirmations,
+        sub_id: event.sub_id,
+        callback_gas_limit: event.callback_gas_limit,
+        num_words: event.num_words as u32,
+        sender: event.sender,
+    }
+}
@@ -0,0 +1,52 @@
+use std::{collections::BTreeMap, fs, io, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{error::Error, event_source::RequestedEvent};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PendingRequest {
+    pub event: RequestedEvent,
+    /// Number of `fulfill_random_words` deploys sent so far.
+    pub attempts: u32,
+    /// Deploy of the current attempt, until its execution result is known.
+    pub deploy_hash: Option<String>,
+    /// Block height before which the request is not retried.
+    pub retry_at: u64,
+    pub last_error: Option<String>,
+    /// Set once an attempt failed in the consumer callback or the callback was measured above
+    /// its gas limit, so the next ones store the words in the coordinator instead of calling the
+    /// consumer.
+    #[serde(default)]
+    pub skip_callback: bool,
+}
+
+/// State the oracle persists between polls, so a restart neither skips nor repeats requests.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Progress {
+    /// Number of the next event to read from the source.
+    pub next_event: u64,
+    /// Requests for our proving key that are not fulfilled yet, by request id.
+    pub pending: BTreeMap<String, PendingRequest>,
+    /// Requests given up on after the maximum number of attempts.
+    pub failed: Vec<PendingRequest>,
+}
+
+impl Progress {
+    /// Progress saved at `path`, or a fresh one if nothing was saved yet.
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        match fs::read(path) {
+            Ok(data) => Ok(serde_json::from_slice(&data)?),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Writes through a temporary file so a crash cannot leave a truncated state behind.
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, serde_json::to_vec_pretty(self)?)?;
+        fs::rename(tmp, path)?;
+        Ok(())
+    }
+}
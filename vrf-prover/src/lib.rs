@@ -0,0 +1,334 @@
+//! Off-chain generation of the secp256k1 proofs accepted by the coordinator's
+//! `vrf::random_value_from_vrf_proof`.
+//!
+//! Hashing and point encodings mirror the contract exactly: `runtime::blake2b` is blake2b-256
+//! and every hashed value is serialized with casper bytesrepr, as `helpers::encode_*` does.
+use std::fmt;
+
+use blake2::{
+    digest::{Update, VariableOutput},
+    VarBlake2b,
+};
+use casper_types::{
+    bytesrepr::{Bytes, ToBytes},
+    HashAddr, Key, U256, U512,
+};
+use common::data_types::Proof;
+use k256::{
+    elliptic_curve::{
+        ff::PrimeField,
+        sec1::{FromEncodedPoint, ToEncodedPoint},
+    },
+    AffinePoint, EncodedPoint, FieldBytes, ProjectivePoint, Scalar,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// The secret key is zero or not below the group order.
+    InvalidSecretKey,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidSecretKey => write!(f, "invalid secp256k1 secret key"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+const HASH_TO_CURVE_HASH_PREFIX: u64 = 1;
+const SCALAR_FROM_CURVE_POINTS_HASH_PREFIX: u64 = 2;
+const VRF_RANDOM_OUTPUT_HASH_PREFIX: u64 = 3;
+
+/// Same digest as the contract runtime's `blake2b`.
+pub fn blake2b<T: AsRef<[u8]>>(data: T) -> HashAddr {
+    let mut result = [0u8; 32];
+    let mut hasher = VarBlake2b::new(32).expect("32 is a valid blake2b output size");
+    hasher.update(data);
+    hasher.finalize_variable(|hash| result.copy_from_slice(hash));
+    result
+}
+
+fn encode<T: ToBytes>(t: &T) -> Vec<u8> {
+    t.to_bytes()
+        .expect("values used in proofs should serialize")
+}
+
+fn u256_from_hash(h: HashAddr) -> U256 {
+    U256::from_big_endian(&h)
+}
+
+fn field_size() -> U256 {
+    U256::from_str_radix(
+        "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F",
+        16,
+    )
+    .unwrap()
+}
+
+fn widen(v: U256) -> U512 {
+    let mut b = [0u8; 32];
+    v.to_little_endian(&mut b);
+    U512::from_little_endian(&b)
+}
+
+fn narrow(v: U512) -> U256 {
+    let mut b = [0u8; 64];
+    v.to_little_endian(&mut b);
+    U256::from_little_endian(&b[..32])
+}
+
+fn mulmod(a: U256, b: U256) -> U256 {
+    narrow(widen(a) * widen(b) % widen(field_size()))
+}
+
+fn add_mod(a: U256, b: U256) -> U256 {
+    narrow((widen(a) + widen(b)) % widen(field_size()))
+}
+
+fn big_mod_exp(base: U256, exponent: U256) -> U256 {
+    let mut ret = U256::one();
+    let mut base = base % field_size();
+    let mut exponent = exponent;
+    while !exponent.is_zero() {
+        if exponent.bit(0) {
+            ret = mulmod(ret, base);
+        }
+        exponent >>= 1;
+        base = mulmod(base, base);
+    }
+    ret
+}
+
+fn y_squared(x: U256) -> U256 {
+    add_mod(mulmod(x, mulmod(x, x)), 7.into())
+}
+
+fn is_on_curve(p: &[U256]) -> bool {
+    p[0] < field_size() && p[1] < field_size() && y_squared(p[0]) == mulmod(p[1], p[1])
+}
+
+fn field_hash(b: &[u8]) -> U256 {
+    let mut ret = u256_from_hash(blake2b(b));
+    while ret >= field_size() {
+        ret = u256_from_hash(blake2b(encode(&ret)));
+    }
+    ret
+}
+
+fn new_candidate_point(b: &[u8]) -> Vec<U256> {
+    let x = field_hash(b);
+    let mut y = big_mod_exp(y_squared(x), (field_size() + 1) >> 2);
+    if y.bit(0) {
+        y = field_size() - y;
+    }
+    vec![x, y]
+}
+
+/// Point the contract derives from `pk` and the seed in `hash_to_curve`.
+fn hash_to_curve(pk: &Vec<U256>, input: U256) -> Vec<U256> {
+    let mut b = encode(&U256::from(HASH_TO_CURVE_HASH_PREFIX));
+    b.extend(encode(pk));
+    b.extend(encode(&input));
+    let mut rv = new_candidate_point(&b);
+    while !is_on_curve(&rv) {
+        rv = new_candidate_point(&encode(&rv));
+    }
+    rv
+}
+
+fn projective_mul(x1: U256, z1: U256, x2: U256, z2: U256) -> (U256, U256) {
+    (mulmod(x1, x2), mulmod(z1, z2))
+}
+
+fn projective_sub(x1: U256, z1: U256, x2: U256, z2: U256) -> (U256, U256) {
+    let num1 = mulmod(z2, x1);
+    let num2 = mulmod(field_size() - x2, z1);
+    (add_mod(num1, num2), mulmod(z1, z2))
+}
+
+/// Same formulas as the contract's `projective_ec_add`, whose `z` the proof carries the
+/// inverse of.
+fn projective_ec_add(px: U256, py: U256, qx: U256, qy: U256) -> (U256, U256, U256) {
+    let (z1, z2) = (U256::one(), U256::one());
+    let lx = add_mod(qy, field_size() - py);
+    let lz = add_mod(qx, field_size() - px);
+
+    let (mut sx, mut dx) = projective_mul(lx, lz, lx, lz);
+    (sx, dx) = projective_sub(sx, dx, px, z1);
+    (sx, dx) = projective_sub(sx, dx, qx, z2);
+
+    let (mut sy, mut dy) = projective_sub(px, z1, sx, dx);
+    (sy, dy) = projective_mul(sy, dy, lx, lz);
+    (sy, dy) = projective_sub(sy, dy, py, z1);
+
+    let sz = if dx != dy {
+        sx = mulmod(sx, dy);
+        sy = mulmod(sy, dx);
+        mulmod(dx, dy)
+    } else {
+        dx
+    };
+    (sx, sy, sz)
+}
+
+fn field_bytes(v: U256) -> FieldBytes {
+    let mut b = [0u8; 32];
+    v.to_big_endian(&mut b);
+    FieldBytes::from(b)
+}
+
+fn to_scalar(v: U256) -> Scalar {
+    Scalar::from_bytes_reduced(&field_bytes(v))
+}
+
+fn scalar_to_u256(s: &Scalar) -> U256 {
+    U256::from_big_endian(&s.to_bytes())
+}
+
+fn to_projective_point(p: &[U256]) -> ProjectivePoint {
+    let encoded =
+        EncodedPoint::from_affine_coordinates(&field_bytes(p[0]), &field_bytes(p[1]), false);
+    ProjectivePoint::from(
+        AffinePoint::from_encoded_point(&encoded).expect("hashed points lie on the curve"),
+    )
+}
+
+/// Affine coordinates of `p`, or `None` for the point at infinity.
+fn to_affine_coordinates(p: &ProjectivePoint) -> Option<Vec<U256>> {
+    let encoded = p.to_affine().to_encoded_point(false);
+    Some(vec![
+        U256::from_big_endian(encoded.x()?),
+        U256::from_big_endian(encoded.y()?),
+    ])
+}
+
+fn point_bytes(p: &Vec<U256>) -> Bytes {
+    Bytes::from(encode(p))
+}
+
+/// Seed the coordinator verifies proofs against, from the request's `pre_seed` and the hash
+/// of the block it was committed to.
+pub fn actual_seed(pre_seed: U256, block_hash: HashAddr) -> U256 {
+    let mut b = encode(&pre_seed);
+    b.extend(encode(&block_hash));
+    u256_from_hash(blake2b(b))
+}
+
+/// Randomness the coordinator derives from a verified proof.
+pub fn random_value(proof: &Proof) -> U256 {
+    let mut b = encode(&U256::from(VRF_RANDOM_OUTPUT_HASH_PREFIX));
+    b.extend(encode(&proof.gamma));
+    u256_from_hash(blake2b(b))
+}
+
+/// Words delivered to the consumer for `randomness`.
+pub fn random_words(randomness: U256, num_words: u32) -> Vec<U256> {
+    (0..num_words)
+        .map(|i| {
+            let mut b = encode(&randomness);
+            b.extend(encode(&i));
+            u256_from_hash(blake2b(b))
+        })
+        .collect()
+}
+
+pub struct Prover {
+    secret_key: Scalar,
+    public_key: Vec<U256>,
+}
+
+impl Prover {
+    /// `secret_key` is the big endian secp256k1 scalar.
+    pub fn new(secret_key: [u8; 32]) -> Result<Self, Error> {
+        let secret_key = Scalar::from_repr(FieldBytes::from(secret_key))
+            .filter(|sk| *sk != Scalar::zero())
+            .ok_or(Error::InvalidSecretKey)?;
+        let public_key = to_affine_coordinates(&(ProjectivePoint::generator() * secret_key))
+            .ok_or(Error::InvalidSecretKey)?;
+        Ok(Self {
+            secret_key,
+            public_key,
+        })
+    }
+
+    /// Key to pass as `public_proving_key` to `register_proving_key`.
+    pub fn public_key(&self) -> Bytes {
+        point_bytes(&self.public_key)
+    }
+
+    /// Nonce for the `attempt`th try at proving `seed`, derived from the secret key so proofs
+    /// are reproducible.
+    fn nonce(&self, seed: U256, attempt: u32) -> Scalar {
+        let mut b = self.secret_key.to_bytes().to_vec();
+        b.extend(encode(&seed));
+        b.extend(encode(&attempt));
+        Scalar::from_bytes_reduced(&FieldBytes::from(blake2b(b)))
+    }
+
+    /// Proof for a request with `pre_seed`, committed to the block with `block_hash`.
+    pub fn generate_proof(&self, pre_seed: U256, block_hash: HashAddr) -> Proof {
+        let seed = actual_seed(pre_seed, block_hash);
+        let hash = hash_to_curve(&self.public_key, seed);
+        let hash_point = to_projective_point(&hash);
+        let gamma = to_affine_coordinates(&(hash_point * self.secret_key))
+            .expect("secret key is a non zero scalar");
+
+        let mut attempt = 0u32;
+        loop {
+            if let Some(proof) = self.try_prove(pre_seed, seed, &hash, &gamma, attempt) {
+                return proof;
+            }
+            attempt += 1;
+        }
+    }
+
+    fn try_prove(
+        &self,
+        pre_seed: U256,
+        seed: U256,
+        hash: &Vec<U256>,
+        gamma: &Vec<U256>,
+        attempt: u32,
+    ) -> Option<Proof> {
+        let k = self.nonce(seed, attempt);
+        let u = to_affine_coordinates(&(ProjectivePoint::generator() * k))?;
+        let u_witness = Key::Hash(blake2b(encode(&u)));
+        let v = to_affine_coordinates(&(to_projective_point(hash) * k))?;
+
+        let mut b = encode(&U256::from(SCALAR_FROM_CURVE_POINTS_HASH_PREFIX));
+        b.extend(encode(hash));
+        b.extend(encode(&self.public_key));
+        b.extend(encode(gamma));
+        b.extend(encode(&v));
+        b.extend(encode(&u_witness));
+        let c = u256_from_hash(blake2b(b));
+        let s = k - to_scalar(c) * self.secret_key;
+        let s = scalar_to_u256(&s);
+        if c.is_zero() || s.is_zero() {
+            return None;
+        }
+
+        let c_gamma = to_affine_coordinates(&(to_projective_point(gamma) * to_scalar(c)))?;
+        let s_hash = to_affine_coordinates(&(to_projective_point(hash) * to_scalar(s)))?;
+        if c_gamma[0] == s_hash[0] {
+            return None;
+        }
+        let (_, _, z) = projective_ec_add(c_gamma[0], c_gamma[1], s_hash[0], s_hash[1]);
+        let z_inv = big_mod_exp(z, field_size() - 2);
+
+        Some(Proof {
+            pk: self.public_key(),
+            gamma: point_bytes(gamma),
+            c,
+            s,
+            seed: pre_seed,
+            u_witness,
+            c_gamma_witness: point_bytes(&c_gamma),
+            s_hash_witness: point_bytes(&s_hash),
+            z_inv,
+        })
+    }
+}
@@ -0,0 +1,107 @@
+use alloc::{string::String, vec, vec::Vec};
+use casper_types::{CLType, EntryPoint, EntryPointAccess, EntryPointType, EntryPoints, Parameter};
+
+use common::owner;
+
+fn add_entry_points(entry_points: &mut EntryPoints, list: &Vec<EntryPoint>) {
+    for e in list {
+        entry_points.add_entry_point(e.clone());
+    }
+}
+
+pub(crate) fn default() -> EntryPoints {
+    let mut entry_points = EntryPoints::new();
+    add_entry_points(&mut entry_points, &owner::entry_points());
+    add_entry_points(&mut entry_points, &vrf_consumer::entry_points());
+
+    entry_points.add_entry_point(EntryPoint::new(
+        String::from("set_config"),
+        vec![
+            Parameter::new("key_hash", CLType::ByteArray(32)),
+            Parameter::new("wrapper_gas_overhead", CLType::U128),
+            Parameter::new("wrapper_premium_percentage", CLType::U8),
+            Parameter::new("max_num_words", CLType::U64),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        String::from("request_random_words"),
+        vec![
+            Parameter::new("callback_gas_limit", CLType::U128),
+            Parameter::new("request_confirmations", CLType::U64),
+            Parameter::new("num_words", CLType::U64),
+        ],
+        CLType::U256,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        String::from("withdraw"),
+        vec![Parameter::new("to", CLType::Key)],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        String::from("forward_random_words"),
+        vec![Parameter::new("request_id", CLType::U256)],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    // view functions
+    entry_points.add_entry_point(EntryPoint::new(
+        String::from("calculate_request_price"),
+        vec![Parameter::new("callback_gas_limit", CLType::U128)],
+        CLType::U128,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        String::from("get_config"),
+        vec![],
+        CLType::Any,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        String::from("get_sub_id"),
+        vec![],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        String::from("get_payment_token"),
+        vec![],
+        CLType::Key,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        String::from("get_callback"),
+        vec![Parameter::new("request_id", CLType::U256)],
+        CLType::Any,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        String::from("init"),
+        vec![],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points
+}
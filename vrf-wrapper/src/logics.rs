@@ -0,0 +1,220 @@
+use alloc::vec::Vec;
+use casper_contract::{
+    contract_api::runtime::{self, revert},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{CLValue, HashAddr, Key, U128, U256};
+use common::{
+    constants::MAX_NUM_WORDS,
+    data_types::{WrapperCallback, WrapperConfig},
+    erc20_helpers,
+    error::Error,
+    helpers::{self, get_immediate_caller_key, get_self_key, null_key, to_vec_string},
+    interfaces,
+    owner::only_owner,
+};
+
+use crate::store::{
+    self, read_callback, read_config, read_payment_token, read_sub_id, save_callback, save_config,
+    save_sub_id,
+};
+
+/**
+ * @notice Creates the subscription the wrapper pays requests from and adds the wrapper to it
+ * @param vrf_coordinator package of the coordinator requests are forwarded to
+ * @param config initial wrapper configuration
+ */
+pub fn initialize(vrf_coordinator: Key, config: WrapperConfig) {
+    _check_config(&config);
+    vrf_consumer::init(vrf_coordinator);
+    store::initialize(
+        interfaces::get_payment_token(vrf_coordinator),
+        _create_subscription(vrf_coordinator),
+        config,
+    );
+}
+
+fn _create_subscription(vrf_coordinator: Key) -> u64 {
+    let sub_id = interfaces::create_subscription(vrf_coordinator);
+    interfaces::add_consumer(vrf_coordinator, sub_id, get_self_key());
+    sub_id
+}
+
+fn _check_config(config: &WrapperConfig) {
+    if config.max_num_words > MAX_NUM_WORDS {
+        revert(Error::NumWordsTooBig);
+    }
+}
+
+/**
+ * @notice Sets the configuration of the wrapper
+ * @param key_hash proving key the wrapper requests words from
+ * @param wrapper_gas_overhead gas used by the wrapper to forward the words to the requester
+ * @param wrapper_premium_percentage premium charged on top of the coordinator payment, in percent
+ * @param max_num_words maximum number of words a request can ask for
+ */
+#[no_mangle]
+pub extern "C" fn set_config() {
+    only_owner();
+    let (key_hash, wrapper_gas_overhead, wrapper_premium_percentage, max_num_words) =
+        helpers::get_named_args_4::<HashAddr, U128, u8, u64>(to_vec_string(&[
+            "key_hash",
+            "wrapper_gas_overhead",
+            "wrapper_premium_percentage",
+            "max_num_words",
+        ]));
+    let config = WrapperConfig {
+        key_hash,
+        wrapper_gas_overhead,
+        wrapper_premium_percentage,
+        max_num_words,
+    };
+    _check_config(&config);
+    save_config(config);
+}
+
+/**
+ * @notice Withdraws the balance of the wrapper subscription, the premiums and overestimated
+ * @notice gas of past requests, by cancelling it and opening a new one for later requests
+ * @param to address receiving the balance
+ * @dev reverts while the wrapper has requests pending
+ */
+#[no_mangle]
+pub extern "C" fn withdraw() {
+    only_owner();
+    let to: Key = runtime::get_named_arg("to");
+    let vrf_coordinator = vrf_consumer::vrf_coordinator();
+    interfaces::cancel_subscription(vrf_coordinator, read_sub_id(), to);
+    save_sub_id(_create_subscription(vrf_coordinator));
+}
+
+/**
+ * @notice Price of a request paid to the wrapper, in payment token units
+ * @param callback_gas_limit gas limit of the requester's callback
+ */
+#[no_mangle]
+pub extern "C" fn calculate_request_price() {
+    let callback_gas_limit: U128 = runtime::get_named_arg("callback_gas_limit");
+    runtime::ret(CLValue::from_t(_calculate_request_price(callback_gas_limit)).unwrap_or_revert())
+}
+
+/**
+ * @notice Requests random words paid inline with the payment token, which the caller must
+ * @notice have approved the wrapper to spend the calculate_request_price quote of
+ * @param callback_gas_limit gas limit of the callback the words are delivered with
+ * @param request_confirmations number of confirmations the oracle waits for
+ * @param num_words number of random words requested
+ * @dev the caller must be a contract exposing raw_fulfill_random_words, which the words are
+ * @dev forwarded to once the coordinator fulfills the request
+ */
+#[no_mangle]
+pub extern "C" fn request_random_words() {
+    let callback_address = get_immediate_caller_key();
+    if callback_address.into_hash().is_none() {
+        revert(Error::RequesterMustBeContract);
+    }
+    let (callback_gas_limit, request_confirmations, num_words) =
+        helpers::get_named_args_3::<U128, u64, u64>(to_vec_string(&[
+            "callback_gas_limit",
+            "request_confirmations",
+            "num_words",
+        ]));
+    let config = read_config();
+    if num_words > config.max_num_words {
+        revert(Error::NumWordsTooBig);
+    }
+
+    let request_price = _calculate_request_price(callback_gas_limit);
+    let payment_token = read_payment_token();
+    let vrf_coordinator = vrf_consumer::vrf_coordinator();
+    let sub_id = read_sub_id();
+    erc20_helpers::transfer_from(
+        payment_token,
+        callback_address,
+        get_self_key(),
+        request_price,
+    );
+    erc20_helpers::approve(payment_token, vrf_coordinator, request_price);
+    interfaces::deposit_token(vrf_coordinator, sub_id, request_price);
+
+    let request_id = interfaces::request_random_words(
+        vrf_coordinator,
+        config.key_hash,
+        sub_id,
+        request_confirmations,
+        callback_gas_limit + config.wrapper_gas_overhead,
+        num_words,
+        false,
+    );
+    save_callback(
+        &request_id,
+        &WrapperCallback {
+            callback_address,
+            callback_gas_limit,
+            request_price,
+        },
+    );
+    runtime::ret(CLValue::from_t(request_id).unwrap_or_revert())
+}
+
+/**
+ * @notice Forwards the words of a request the coordinator fulfilled without calling the
+ * @notice wrapper back to its requester
+ * @param request_id id of the coordinator request
+ * @dev callable by anyone, the words are claimed from the coordinator and only go to the
+ * @dev requester, a reverting requester reverts the claim so the words can be forwarded again
+ */
+#[no_mangle]
+pub extern "C" fn forward_random_words() {
+    let request_id: U256 = runtime::get_named_arg("request_id");
+    let random_words = interfaces::claim_random_words(vrf_consumer::vrf_coordinator(), request_id);
+    fulfill_random_words(request_id, random_words);
+}
+
+/**
+ * @notice Forwards the words of a fulfilled request to its requester
+ * @dev a reverting requester reverts the callback of the coordinator, the oracle then skips the
+ * @dev callback and the words are left in the coordinator for forward_random_words
+ */
+fn fulfill_random_words(request_id: U256, random_words: Vec<U256>) {
+    let callback = read_callback(&request_id);
+    if callback.callback_address == null_key() {
+        revert(Error::UnknownWrapperRequest);
+    }
+    save_callback(&request_id, &WrapperCallback::default());
+    interfaces::call_raw_fulfill_random_words(callback.callback_address, request_id, &random_words);
+}
+
+vrf_consumer::raw_fulfill_random_words!(fulfill_random_words);
+
+/**
+ * @notice Price of a request: the coordinator payment for the callback and wrapper gas, with
+ * @notice the wrapper premium, plus the flat fee of the tier the wrapper subscription is in
+ * @dev the token price and fee tier are those the coordinator bills the wrapper subscription
+ * @dev with, tiers only get cheaper with the request count so the fee at fulfillment is at most
+ * @dev this
+ */
+fn _calculate_request_price(callback_gas_limit: U128) -> U128 {
+    let vrf_coordinator = vrf_consumer::vrf_coordinator();
+    let subscription = interfaces::get_subscription_view(vrf_coordinator, read_sub_id());
+    let motes_per_unit_link =
+        interfaces::get_motes_per_unit_link(vrf_coordinator, subscription.payment_token);
+    let (_, fulfillment_flat_fee_link_ppm) = interfaces::get_fee_tier(
+        vrf_coordinator,
+        subscription.req_count,
+        subscription.payment_token,
+    );
+    let coordinator_config = interfaces::get_config(vrf_coordinator);
+    let config = read_config();
+    let gas = U256::from(coordinator_config.gas_after_payment_calculation.as_u128())
+        + U256::from(callback_gas_limit.as_u128())
+        + U256::from(config.wrapper_gas_overhead.as_u128());
+    let base_fee = U256::from(1_000_000_000_000_000_000_u64) * gas / motes_per_unit_link;
+    let fee_with_premium =
+        base_fee * U256::from(100 + config.wrapper_premium_percentage as u64) / U256::from(100);
+    let fee = U256::from(1_000_000_000_000_u64) * U256::from(fulfillment_flat_fee_link_ppm);
+    if fee_with_premium > U256::from(u128::MAX) - fee {
+        revert(Error::PaymentTooLarge);
+    }
+    U128::from((fee_with_premium + fee).as_u128())
+}
@@ -0,0 +1,72 @@
+//! Direct funding wrapper of the VRF coordinator: contracts pay each request inline with the
+//! payment token instead of managing a subscription.
+#![no_main]
+#![no_std]
+
+extern crate alloc;
+mod entry_points;
+
+pub mod logics;
+pub mod store;
+
+use alloc::{format, string::String};
+use casper_contract::contract_api::runtime;
+use casper_types::{contracts::NamedKeys, runtime_args, HashAddr, Key, RuntimeArgs, U128};
+use common::constants::*;
+use common::data_types::WrapperConfig;
+use common::error::Error;
+use common::helpers;
+use common::owner;
+use common::upgrade;
+
+#[no_mangle]
+pub extern "C" fn init() {
+    if helpers::get_key::<Key>("contract_hash").is_some() {
+        runtime::revert(Error::ContractAlreadyInitialized);
+    }
+
+    let caller = helpers::get_immediate_caller_key();
+    let contract_hash: Key = runtime::get_named_arg("contract_hash");
+    let contract_package_hash: Key = runtime::get_named_arg("contract_package_hash");
+    let vrf_coordinator: Key = runtime::get_named_arg("vrf_coordinator");
+    let config = WrapperConfig {
+        key_hash: runtime::get_named_arg("key_hash"),
+        wrapper_gas_overhead: runtime::get_named_arg("wrapper_gas_overhead"),
+        wrapper_premium_percentage: runtime::get_named_arg("wrapper_premium_percentage"),
+        max_num_words: runtime::get_named_arg("max_num_words"),
+    };
+
+    helpers::set_key("contract_hash", contract_hash);
+    helpers::set_key("contract_package_hash", contract_package_hash);
+    owner::init(caller);
+    logics::initialize(vrf_coordinator, config);
+}
+
+#[no_mangle]
+fn call() {
+    let contract_name: String = runtime::get_named_arg("contract_name");
+    if !runtime::has_key(&format!("{}_package_hash", contract_name)) {
+        let (contract_hash, contract_package_hash) =
+            upgrade::install_contract(contract_name, entry_points::default(), NamedKeys::new());
+        let vrf_coordinator: Key = runtime::get_named_arg("vrf_coordinator");
+        let key_hash: HashAddr = runtime::get_named_arg("key_hash");
+        let wrapper_gas_overhead: U128 = runtime::get_named_arg("wrapper_gas_overhead");
+        let wrapper_premium_percentage: u8 = runtime::get_named_arg("wrapper_premium_percentage");
+        let max_num_words: u64 = runtime::get_named_arg("max_num_words");
+        runtime::call_contract::<()>(
+            contract_hash,
+            INIT_ENTRY_POINT_NAME,
+            runtime_args! {
+                "contract_hash" => Key::from(contract_hash),
+                "contract_package_hash" => Key::from(contract_package_hash),
+                "vrf_coordinator" => vrf_coordinator,
+                "key_hash" => key_hash,
+                "wrapper_gas_overhead" => wrapper_gas_overhead,
+                "wrapper_premium_percentage" => wrapper_premium_percentage,
+                "max_num_words" => max_num_words
+            },
+        );
+    } else {
+        upgrade::upgrade_contract(contract_name, entry_points::default(), NamedKeys::new());
+    }
+}
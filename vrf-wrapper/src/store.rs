@@ -0,0 +1,57 @@
+use casper_contract::contract_api::{runtime, storage};
+use casper_contract::unwrap_or_revert::UnwrapOrRevert;
+use casper_types::{CLValue, Key, U256};
+use common::error::Error;
+use common::{
+    data_types::{WrapperCallback, WrapperConfig},
+    get_set_dict, get_set_no_set, helpers,
+};
+
+pub fn initialize(payment_token: Key, sub_id: u64, config: WrapperConfig) {
+    save_payment_token(payment_token);
+    save_sub_id(sub_id);
+    save_config(config);
+    storage::new_dictionary("callbacks").unwrap_or_revert_with(Error::FailedToCreateDictionary);
+}
+
+get_set_no_set!(
+    payment_token,
+    "payment_token",
+    Key,
+    helpers::null_key(),
+    save_payment_token,
+    read_payment_token,
+    get_payment_token
+);
+
+get_set_no_set!(
+    sub_id,
+    "sub_id",
+    u64,
+    0,
+    save_sub_id,
+    read_sub_id,
+    get_sub_id
+);
+
+get_set_no_set!(
+    config,
+    "config",
+    WrapperConfig,
+    WrapperConfig::default(),
+    save_config,
+    read_config,
+    get_config
+);
+
+get_set_dict!(
+    "callbacks",
+    "request_id",
+    U256,
+    WrapperCallback,
+    WrapperCallback::default(),
+    save_callback,
+    read_callback,
+    get_callback,
+    set_callback
+);